@@ -0,0 +1,28 @@
+-- ============================================================
+-- 后台任务队列
+-- 用于链接元数据抓取等需要访问外部网络的异步任务
+-- 任务成功后删除, 失败按配置重试, 超过最大次数后保留为 failed 便于排查
+-- 创建时间: 2025-01-09
+-- ============================================================
+
+CREATE TABLE background_jobs (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    resource_id INTEGER REFERENCES resources(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'running', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    run_after INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    updated_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+-- 同一资源同一类型的任务只保留一个待处理项, 入队时使用 INSERT OR IGNORE 去重
+CREATE UNIQUE INDEX idx_background_jobs_pending
+    ON background_jobs(kind, resource_id)
+    WHERE status = 'pending';
+
+-- worker 按 run_after 顺序领取待处理任务
+CREATE INDEX idx_background_jobs_status_run_after
+    ON background_jobs(status, run_after);
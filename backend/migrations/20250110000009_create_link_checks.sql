@@ -0,0 +1,23 @@
+-- ============================================================
+-- 链接健康检查结果
+-- 每个链接资源保留最近一次检查结果, URL 变更时删除, 等待重新检查
+-- 创建时间: 2025-01-10
+-- ============================================================
+
+CREATE TABLE link_checks (
+    resource_id INTEGER PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    -- 最近一次响应的状态码, 网络错误时为 NULL
+    status_code INTEGER,
+    -- 重定向后的最终地址, 未发生重定向时为 NULL
+    final_url TEXT,
+    -- 最近一次失败原因
+    error TEXT,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    last_checked_at INTEGER NOT NULL,
+    last_success_at INTEGER
+);
+
+-- 调度器按检查时间挑选需要复查的链接
+CREATE INDEX idx_link_checks_last_checked ON link_checks(last_checked_at);
+-- link_status=broken 过滤
+CREATE INDEX idx_link_checks_failures ON link_checks(consecutive_failures);
@@ -0,0 +1,54 @@
+-- ============================================================
+-- 网页离线存档
+-- 每个链接资源保留最近一次抓取的可读快照 (清理后的 HTML 和纯文本),
+-- 纯文本写入 FTS 索引的 extracted_text 列
+-- 创建时间: 2025-01-11
+-- ============================================================
+
+CREATE TABLE resource_archives (
+    resource_id INTEGER PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    -- 快照对应的最终地址 (跟随重定向后)
+    url TEXT NOT NULL,
+    title TEXT,
+    -- 清理后的 HTML, 配置了存储目录时为 NULL
+    html TEXT,
+    -- 配置了存储目录时 HTML 文件的路径
+    storage_path TEXT,
+    -- 提取的纯文本
+    text TEXT NOT NULL,
+    -- HTML 字节数
+    size INTEGER NOT NULL,
+    archived_at INTEGER NOT NULL,
+    CHECK (html IS NOT NULL OR storage_path IS NOT NULL)
+);
+
+-- 重建 FTS5 索引, 增加 extracted_text 列
+DROP TABLE IF EXISTS resources_fts;
+
+CREATE VIRTUAL TABLE resources_fts USING fts5(
+    title,           -- 资源标题
+    description,     -- 资源描述
+    content,         -- 笔记/代码片段内容
+    tags,            -- 标签文本
+    url,             -- URL（可选）
+    extracted_text,  -- 网页存档提取的正文
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- 迁移现有数据到新 FTS 索引
+INSERT INTO resources_fts (rowid, title, description, content, tags, url, extracted_text)
+SELECT
+    r.id,
+    r.title,
+    COALESCE(r.description, ''),
+    COALESCE(r.content, ''),
+    COALESCE(
+        (SELECT GROUP_CONCAT(t.name, ' ')
+         FROM resource_tags rt
+         JOIN tags t ON rt.tag_id = t.id
+         WHERE rt.resource_id = r.id),
+        ''
+    ) as tags,
+    COALESCE(r.url, ''),
+    ''
+FROM resources r;
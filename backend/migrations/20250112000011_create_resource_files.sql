@@ -0,0 +1,30 @@
+-- ============================================================
+-- 上传文件
+-- File 资源的文件内容保存在存储后端 (本地目录或 S3 兼容对象存储),
+-- 这里记录对象位置、大小、校验和与内容类型
+-- 创建时间: 2025-01-12
+-- ============================================================
+
+CREATE TABLE resource_files (
+    resource_id INTEGER PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    -- 写入时使用的存储后端: local / s3
+    storage_backend TEXT NOT NULL,
+    -- 对象在存储后端中的键
+    storage_key TEXT NOT NULL UNIQUE,
+    -- 上传时的原始文件名
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    -- SHA-256 (十六进制)
+    checksum TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+-- 待删除的存储对象
+-- 删除资源时在同一事务中登记, 由后台任务 worker 删除对象后移除记录
+CREATE TABLE storage_deletions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    storage_backend TEXT NOT NULL,
+    storage_key TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
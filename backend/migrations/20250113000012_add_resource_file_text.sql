@@ -0,0 +1,12 @@
+-- ============================================================
+-- 上传文件正文
+-- 上传后由后台任务提取文件正文 (纯文本、Markdown、HTML、PDF、Office 文档),
+-- IndexerService 将其写入 resources_fts.extracted_text
+-- 创建时间: 2025-01-13
+-- ============================================================
+
+-- 提取的正文, 未提取或格式不支持时为 NULL
+ALTER TABLE resource_files ADD COLUMN extracted_text TEXT;
+
+-- 提取完成时间 (Unix 时间戳), 提取失败或格式不支持时保持 NULL
+ALTER TABLE resource_files ADD COLUMN extracted_at INTEGER;
@@ -0,0 +1,33 @@
+-- ============================================================
+-- 保存的搜索 (智能收藏夹)
+-- 保存查询文本和过滤条件, 成员在读取时按条件实时计算
+-- 创建时间: 2025-01-14
+-- ============================================================
+
+CREATE TABLE saved_searches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- 查询语言文本, 为空时只按过滤条件筛选
+    query TEXT NOT NULL DEFAULT '',
+    -- all / title / content / url
+    search_type TEXT NOT NULL DEFAULT 'all',
+    -- 收藏夹删除后条件失效, 置空而不是删除保存的搜索
+    collection_id INTEGER REFERENCES collections(id) ON DELETE SET NULL,
+    -- 标签名称的 JSON 数组, 需同时包含
+    tags TEXT NOT NULL DEFAULT '[]',
+    resource_type TEXT,
+    is_favorite BOOLEAN,
+    is_archived BOOLEAN,
+    is_private BOOLEAN,
+    is_read BOOLEAN,
+    date_from INTEGER,
+    date_to INTEGER,
+    sort_by TEXT NOT NULL DEFAULT 'created_at',
+    sort_order TEXT NOT NULL DEFAULT 'desc',
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    updated_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX idx_saved_searches_user_id ON saved_searches(user_id);
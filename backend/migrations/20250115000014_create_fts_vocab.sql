@@ -0,0 +1,11 @@
+-- ============================================================
+-- 搜索词表 (fts5vocab)
+-- 基于 resources_fts 的索引词, 用于搜索建议和拼写纠正
+-- 创建时间: 2025-01-15
+-- ============================================================
+
+-- 每个词一行: term, doc (包含该词的文档数), cnt (出现次数), 覆盖所有用户
+CREATE VIRTUAL TABLE resources_fts_vocab USING fts5vocab(resources_fts, 'row');
+
+-- 每次出现一行: term, doc (即 resources.id), col, offset; 与 resources 关联后按用户统计
+CREATE VIRTUAL TABLE resources_fts_vocab_instance USING fts5vocab(resources_fts, 'instance');
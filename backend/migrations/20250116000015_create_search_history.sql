@@ -0,0 +1,22 @@
+-- ============================================================
+-- 搜索历史
+-- 记录每次执行的搜索, 用于最近搜索、热门搜索和搜索建议
+-- 创建时间: 2025-01-16
+-- ============================================================
+
+CREATE TABLE search_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    query TEXT NOT NULL,
+    -- all / title / content / url
+    search_type TEXT NOT NULL DEFAULT 'all',
+    -- 请求中的过滤条件 (JSON 对象), 只包含设置了的条件
+    filters TEXT NOT NULL DEFAULT '{}',
+    result_count INTEGER NOT NULL DEFAULT 0,
+    -- 搜索耗时 (秒), 与搜索响应中的 search_time 一致
+    search_time REAL NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+CREATE INDEX idx_search_history_user_created ON search_history(user_id, created_at DESC);
+CREATE INDEX idx_search_history_user_query ON search_history(user_id, query COLLATE NOCASE);
@@ -0,0 +1,19 @@
+-- ============================================================
+-- 资源向量
+-- 语义搜索 (embeddings feature) 使用的向量, 由资源的标题、描述和内容计算,
+-- 资源更新或模型变化后在下次语义搜索时重新计算
+-- 创建时间: 2025-01-17
+-- ============================================================
+
+CREATE TABLE resource_embeddings (
+    resource_id INTEGER PRIMARY KEY REFERENCES resources(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- 计算向量的模型名称
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    -- 小端序 f32 数组, 已归一化
+    vector BLOB NOT NULL,
+    embedded_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+CREATE INDEX idx_resource_embeddings_user ON resource_embeddings(user_id);
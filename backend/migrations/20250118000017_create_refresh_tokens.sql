@@ -0,0 +1,22 @@
+-- ============================================================
+-- 刷新令牌
+-- 每次登录创建一个令牌家族 (family_id, 即会话), 每次刷新轮换出同一家族的新令牌;
+-- 已轮换的令牌再次使用视为泄露, 撤销整个家族
+-- 创建时间: 2025-01-18
+-- ============================================================
+
+CREATE TABLE refresh_tokens (
+    -- 令牌中的 jti
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id TEXT NOT NULL,
+    expires_at INTEGER NOT NULL,
+    -- 轮换时设置, 之后不能再使用
+    used_at INTEGER,
+    -- 登出或检测到重放时设置
+    revoked_at INTEGER,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+CREATE INDEX idx_refresh_tokens_family ON refresh_tokens(family_id);
+CREATE INDEX idx_refresh_tokens_user ON refresh_tokens(user_id);
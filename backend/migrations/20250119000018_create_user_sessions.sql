@@ -0,0 +1,33 @@
+-- ============================================================
+-- 登录会话
+-- 每个会话对应一个刷新令牌家族 (refresh_tokens.family_id), 记录登录设备信息,
+-- 撤销后该会话的访问令牌和刷新令牌都不能再使用
+-- 创建时间: 2025-01-19
+-- ============================================================
+
+CREATE TABLE user_sessions (
+    -- 与 refresh_tokens.family_id 及访问令牌的 sid 声明相同
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip_address TEXT,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    -- 最近一次刷新或使用访问令牌的时间, 访问令牌的使用最多每分钟记录一次
+    last_used_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    -- 最新刷新令牌的过期时间
+    expires_at INTEGER NOT NULL,
+    revoked_at INTEGER
+);
+
+CREATE INDEX idx_user_sessions_user ON user_sessions(user_id, last_used_at DESC);
+
+-- 为已有的刷新令牌家族创建会话
+INSERT INTO user_sessions (id, user_id, created_at, last_used_at, expires_at, revoked_at)
+SELECT family_id,
+       user_id,
+       MIN(created_at),
+       MAX(COALESCE(used_at, created_at)),
+       MAX(expires_at),
+       CASE WHEN COUNT(revoked_at) = COUNT(*) THEN MAX(revoked_at) END
+FROM refresh_tokens
+GROUP BY family_id, user_id;
@@ -0,0 +1,8 @@
+-- ============================================================
+-- 邮箱验证
+-- 记录验证邮件的发送时间, 用于计算验证链接的有效期和限制重发频率;
+-- email_verification_token 保存令牌的 SHA-256 摘要
+-- 创建时间: 2025-01-20
+-- ============================================================
+
+ALTER TABLE users ADD COLUMN email_verification_sent_at INTEGER;
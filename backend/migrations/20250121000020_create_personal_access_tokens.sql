@@ -0,0 +1,34 @@
+-- ============================================================
+-- 个人访问令牌
+-- 供脚本和浏览器扩展长期使用, 代替保存用户密码; 只保存令牌的 SHA-256 摘要,
+-- 权限范围为 read / write / admin, 可以限定只能访问部分收藏夹
+-- 创建时间: 2025-01-21
+-- ============================================================
+
+CREATE TABLE personal_access_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    -- 令牌开头的几个字符, 用于在列表中辨认令牌
+    token_prefix TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('read', 'write', 'admin')),
+    -- 为 1 时只能访问 personal_access_token_collections 中的收藏夹;
+    -- 收藏夹全部删除后令牌不能访问任何资源, 而不是恢复为不受限
+    collection_limited INTEGER NOT NULL DEFAULT 0,
+    -- NULL 表示不过期
+    expires_at INTEGER,
+    -- 最近一次使用的时间和地址, 最多每分钟记录一次
+    last_used_at INTEGER,
+    last_used_ip TEXT,
+    revoked_at INTEGER,
+    created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+);
+
+CREATE INDEX idx_personal_access_tokens_user ON personal_access_tokens(user_id, created_at DESC);
+
+CREATE TABLE personal_access_token_collections (
+    token_id INTEGER NOT NULL REFERENCES personal_access_tokens(id) ON DELETE CASCADE,
+    collection_id INTEGER NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    PRIMARY KEY (token_id, collection_id)
+);
@@ -0,0 +1,7 @@
+-- ============================================================
+-- 重置密码邮件的发送时间
+-- 申请重置密码不需要登录, 记录上次发送时间用于限制同一用户的发送频率
+-- 创建时间: 2025-01-22
+-- ============================================================
+
+ALTER TABLE users ADD COLUMN password_reset_sent_at INTEGER;
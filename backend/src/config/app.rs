@@ -6,6 +6,20 @@ pub struct AppConfig {
     pub database: super::DatabaseConfig,
     pub auth: super::AuthConfig,
     #[serde(default)]
+    pub jobs: super::JobsConfig,
+    #[serde(default)]
+    pub fetcher: super::FetcherConfig,
+    #[serde(default)]
+    pub link_checker: super::LinkCheckerConfig,
+    #[serde(default)]
+    pub archive: super::ArchiveConfig,
+    #[serde(default)]
+    pub storage: super::StorageConfig,
+    #[serde(default)]
+    pub search: super::SearchConfig,
+    #[serde(default)]
+    pub mail: super::MailConfig,
+    #[serde(default)]
     pub environment: Environment,
 }
 
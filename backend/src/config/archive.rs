@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// 网页离线存档配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// 抓取链接元数据时是否同时保存页面快照
+    pub enabled: bool,
+    /// 快照 HTML 的存储目录, 未配置时保存在数据库中
+    pub storage_dir: Option<String>,
+}
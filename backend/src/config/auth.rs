@@ -5,15 +5,58 @@ pub struct AuthConfig {
     pub jwt_secret: String,
     pub jwt_expires_in: u64,
     pub refresh_token_expires_in: u64,
+    /// 重置密码链接的有效期 (分钟)
+    #[serde(default = "default_password_reset_expires_in")]
+    pub password_reset_expires_in: u64,
+    /// 同一用户重复发送重置密码邮件的最短间隔 (秒)
+    #[serde(default = "default_password_reset_resend_interval")]
+    pub password_reset_resend_interval: u64,
+    /// 前端地址, 邮件中的链接指向该地址
+    #[serde(default = "default_public_url")]
+    pub public_url: String,
+    /// 邮箱验证链接的有效期 (分钟)
+    #[serde(default = "default_email_verification_expires_in")]
+    pub email_verification_expires_in: u64,
+    /// 重新发送验证邮件的最短间隔 (秒)
+    #[serde(default = "default_email_verification_resend_interval")]
+    pub email_verification_resend_interval: u64,
+    /// 公开收藏夹等操作是否要求邮箱已验证
+    #[serde(default)]
+    pub require_verified_email: bool,
+}
+
+fn default_password_reset_expires_in() -> u64 {
+    60
+}
+
+fn default_password_reset_resend_interval() -> u64 {
+    60
+}
+
+fn default_public_url() -> String {
+    "http://localhost:5173".to_string()
+}
+
+fn default_email_verification_expires_in() -> u64 {
+    24 * 60
+}
+
+fn default_email_verification_resend_interval() -> u64 {
+    60
 }
 
 impl AuthConfig {
-    #[allow(dead_code)]
     pub fn new(jwt_secret: String) -> Self {
         Self {
             jwt_secret,
             jwt_expires_in: 15,                    // 15 minutes
             refresh_token_expires_in: 7 * 24 * 60, // 7 days in minutes
+            password_reset_expires_in: default_password_reset_expires_in(),
+            password_reset_resend_interval: default_password_reset_resend_interval(),
+            public_url: default_public_url(),
+            email_verification_expires_in: default_email_verification_expires_in(),
+            email_verification_resend_interval: default_email_verification_resend_interval(),
+            require_verified_email: false,
         }
     }
 }
@@ -24,6 +67,12 @@ impl Default for AuthConfig {
             jwt_secret: String::new(),
             jwt_expires_in: 15,                    // 15 minutes
             refresh_token_expires_in: 7 * 24 * 60, // 7 days in minutes
+            password_reset_expires_in: default_password_reset_expires_in(),
+            password_reset_resend_interval: default_password_reset_resend_interval(),
+            public_url: default_public_url(),
+            email_verification_expires_in: default_email_verification_expires_in(),
+            email_verification_resend_interval: default_email_verification_resend_interval(),
+            require_verified_email: false,
         }
     }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// 外部网页抓取配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetcherConfig {
+    pub user_agent: String,
+    /// 单次请求超时 (秒)
+    pub timeout_secs: u64,
+    /// 最多读取的响应体大小 (字节)
+    pub max_body_bytes: usize,
+    /// 最多跟随的重定向次数
+    pub max_redirects: usize,
+    /// 是否允许访问内网/回环地址 (仅用于开发和测试)
+    pub allow_private_networks: bool,
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: concat!("resources-api/", env!("CARGO_PKG_VERSION")).to_string(),
+            timeout_secs: 10,
+            max_body_bytes: 1024 * 1024,
+            max_redirects: 5,
+            allow_private_networks: false,
+        }
+    }
+}
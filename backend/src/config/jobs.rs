@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// 后台任务配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobsConfig {
+    /// 是否启动后台任务 worker
+    pub enabled: bool,
+    /// 没有待处理任务时的轮询间隔 (秒)
+    pub poll_interval_secs: u64,
+    /// 单个任务的最大尝试次数
+    pub max_attempts: i64,
+    /// 失败重试的基础延迟 (秒), 按尝试次数线性递增
+    pub retry_delay_secs: i64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_secs: 5,
+            max_attempts: 3,
+            retry_delay_secs: 60,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// 失效链接检查配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkCheckerConfig {
+    /// 是否启动定时检查
+    pub enabled: bool,
+    /// 调度间隔 (秒)
+    pub interval_secs: u64,
+    /// 距上次检查超过该时长 (秒) 的链接才会被复查
+    pub recheck_after_secs: i64,
+    /// 每轮最多检查的链接数
+    pub batch_size: i64,
+    /// 同时进行的请求数
+    pub concurrency: usize,
+    /// 对同一主机的两次请求之间的最小间隔 (毫秒)
+    pub per_host_interval_ms: u64,
+}
+
+impl Default for LinkCheckerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 3600,
+            recheck_after_secs: 7 * 24 * 3600,
+            batch_size: 200,
+            concurrency: 4,
+            per_host_interval_ms: 1000,
+        }
+    }
+}
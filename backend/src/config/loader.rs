@@ -1,4 +1,4 @@
-use crate::config::AppConfig;
+use crate::config::{AppConfig, MailBackend};
 use config::{Config, Environment, File};
 
 impl AppConfig {
@@ -68,6 +68,18 @@ impl AppConfig {
                 })?;
         }
 
+        // Public URL (邮件中的链接), 真正发送邮件时必须设置
+        if config.auth.public_url.is_empty() {
+            config.auth.public_url = std::env::var("PUBLIC_URL")
+                .or_else(|_| std::env::var("APP_AUTH__PUBLIC_URL"))
+                .unwrap_or_default();
+        }
+        if config.auth.public_url.is_empty() && config.mail.backend == MailBackend::Smtp {
+            anyhow::bail!(
+                "Public URL must be set via PUBLIC_URL, APP_AUTH__PUBLIC_URL, or config file when mail backend is smtp"
+            );
+        }
+
         Ok(())
     }
 }
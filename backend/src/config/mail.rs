@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// 邮件发送方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MailBackend {
+    #[default]
+    Outbox, // 写入日志, 配置了 outbox_dir 时同时保存为文件, 不需要邮件服务器
+    Smtp, // 通过 SMTP 服务器发送
+}
+
+/// 邮件配置 (重置密码等通知邮件)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailConfig {
+    pub backend: MailBackend,
+    /// 发件人地址
+    pub from: String,
+    /// outbox 后端保存邮件的目录, 未配置时只写日志
+    pub outbox_dir: Option<String>,
+    pub smtp: SmtpConfig,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            backend: MailBackend::Outbox,
+            from: "noreply@localhost".to_string(),
+            outbox_dir: None,
+            smtp: SmtpConfig::default(),
+        }
+    }
+}
+
+/// SMTP 连接的加密方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    #[default]
+    StartTls, // 明文连接后升级为 TLS, 通常使用 587 端口
+    Tls,  // 直接建立 TLS 连接, 通常使用 465 端口
+    None, // 不加密, 只适合本机或内网的测试服务器
+}
+
+/// SMTP 服务器配置, 密码建议通过 APP_MAIL__SMTP__PASSWORD 环境变量提供
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    /// 用户名为空时不进行认证
+    pub username: String,
+    pub password: String,
+    /// 连接和发送的超时时间 (秒)
+    pub timeout_secs: u64,
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 587,
+            tls: SmtpTls::StartTls,
+            username: String::new(),
+            password: String::new(),
+            timeout_secs: 10,
+        }
+    }
+}
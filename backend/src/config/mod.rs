@@ -1,8 +1,22 @@
 pub mod app;
+pub mod archive;
 pub mod auth;
 pub mod database;
+pub mod fetcher;
+pub mod jobs;
+pub mod link_checker;
 pub mod loader;
+pub mod mail;
+pub mod search;
+pub mod storage;
 
 pub use app::AppConfig;
+pub use archive::ArchiveConfig;
 pub use auth::AuthConfig;
 pub use database::DatabaseConfig;
+pub use fetcher::FetcherConfig;
+pub use jobs::JobsConfig;
+pub use link_checker::LinkCheckerConfig;
+pub use mail::{MailBackend, MailConfig, SmtpConfig, SmtpTls};
+pub use search::{RankingConfig, SearchConfig};
+pub use storage::{S3Config, StorageBackend, StorageConfig};
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// 搜索配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// 高亮片段中匹配词前插入的标记
+    pub highlight_start: String,
+    /// 高亮片段中匹配词后插入的标记
+    pub highlight_end: String,
+    /// 每个片段包含的最大词数 (FTS5 snippet() 上限为 64)
+    pub snippet_tokens: i64,
+    /// 标签、收藏夹和域名分面返回的最大项数
+    pub facet_limit: i64,
+    pub ranking: RankingConfig,
+    /// 语义搜索 (需要启用 embeddings feature)
+    pub semantic: SemanticConfig,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            highlight_start: "<mark>".to_string(),
+            highlight_end: "</mark>".to_string(),
+            snippet_tokens: 16,
+            facet_limit: 10,
+            ranking: RankingConfig::default(),
+            semantic: SemanticConfig::default(),
+        }
+    }
+}
+
+/// 相关度排序配置
+///
+/// 文本相关度为 bm25() 按列加权的得分, 再按收藏、访问次数和新近度加成:
+/// `score = text_score * (1 + 各项加成)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    /// 各全文索引列的 bm25() 权重
+    pub title_weight: f64,
+    pub description_weight: f64,
+    pub content_weight: f64,
+    pub tags_weight: f64,
+    pub url_weight: f64,
+    pub extracted_text_weight: f64,
+    /// 收藏资源的加成
+    pub favorite_boost: f64,
+    /// 访问次数的加成上限, 访问越多越接近该值
+    pub visit_boost: f64,
+    /// 新近度的加成上限, 刚创建的资源取得全部加成
+    pub recency_boost: f64,
+    /// 新近度加成减半所需的天数
+    pub recency_half_life_days: f64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            title_weight: 10.0,
+            description_weight: 4.0,
+            content_weight: 1.0,
+            tags_weight: 6.0,
+            url_weight: 2.0,
+            extracted_text_weight: 1.0,
+            favorite_boost: 0.2,
+            visit_boost: 0.1,
+            recency_boost: 0.1,
+            recency_half_life_days: 90.0,
+        }
+    }
+}
+
+/// 语义搜索配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SemanticConfig {
+    /// 向量维度, 修改后已保存的向量会在下次搜索时重新计算
+    pub dimensions: usize,
+    /// 语义搜索结果的最低相似度 (余弦相似度, -1 到 1)
+    pub min_similarity: f64,
+    /// 混合搜索时关键词和语义两路各取的候选数量
+    pub hybrid_candidates: i64,
+    /// 倒数排名融合 (RRF) 的平滑常数, 越大排名靠后的结果权重越接近靠前的结果
+    pub rrf_k: f64,
+}
+
+impl Default for SemanticConfig {
+    fn default() -> Self {
+        Self {
+            dimensions: 256,
+            min_similarity: 0.1,
+            hybrid_candidates: 200,
+            rrf_k: 60.0,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// 文件存储后端
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Local, // 本地文件系统
+    S3, // S3 兼容对象存储 (AWS S3、MinIO 等)
+}
+
+/// 上传文件存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// 单个上传文件的大小上限 (字节)
+    pub max_upload_bytes: usize,
+    /// 本地存储根目录
+    pub local_dir: String,
+    pub s3: S3Config,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Local,
+            max_upload_bytes: 50 * 1024 * 1024,
+            local_dir: "data/files".to_string(),
+            s3: S3Config::default(),
+        }
+    }
+}
+
+/// S3 兼容存储配置, 密钥建议通过 APP_STORAGE__S3__SECRET_ACCESS_KEY 等环境变量提供
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    /// 服务地址, 例如 https://s3.us-east-1.amazonaws.com 或 http://127.0.0.1:9000
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// 使用路径风格地址 (endpoint/bucket/key), MinIO 等自建服务通常需要开启
+    pub path_style: bool,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            path_style: true,
+        }
+    }
+}
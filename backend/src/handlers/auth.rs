@@ -1,62 +1,81 @@
 use axum::{
-    extract::{Json, State},
+    extract::{Json, Path, Query, State},
     response::Response,
 };
 use serde_json::{json, Value};
 use sqlx::SqlitePool;
 
-use crate::services::AuthService;
+use crate::services::{AccessTokenService, AuthService};
+use crate::state::AppState;
 use crate::utils::error::AppError;
 use crate::utils::response::{
     success_message_response, success_response, success_response_with_message,
 };
 use crate::{
-    middleware::AuthenticatedUser,
-    models::{ChangePassword, CreateUser, LoginUser, UserResponse},
+    middleware::{AuthenticatedUser, CurrentSession},
+    models::{
+        ChangePassword, ClientInfo, CreatePersonalAccessToken, CreateUser, LoginUser,
+        RequestPasswordReset, ResetPassword, RevokeSessionsQuery, UserResponse, VerifyEmail,
+    },
 };
 
 pub async fn register(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
+    client: ClientInfo,
     Json(user_data): Json<CreateUser>,
 ) -> Result<Response, AppError> {
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT secret not configured".to_string()))?;
-
-    let auth_service = AuthService::new(jwt_secret);
+    let auth_service = AuthService::from_config(&state.auth_config);
 
-    let user = auth_service.register(user_data, &db_pool).await?;
-    let access_token = auth_service.generate_access_token(user.id)?;
-    let refresh_token = auth_service.generate_refresh_token(user.id)?;
+    let user = auth_service.register(user_data, &state.db_pool).await?;
+    // 邮件发送失败不影响注册, 用户可以稍后重新发送验证邮件
+    if let Err(e) = auth_service
+        .send_verification_email(
+            &user,
+            &state.auth_config,
+            state.mailer.as_ref(),
+            &state.db_pool,
+        )
+        .await
+    {
+        tracing::warn!(
+            "Failed to send verification mail to user {}: {}",
+            user.id,
+            e
+        );
+    }
+    let tokens = auth_service
+        .create_session(user.id, &client, &state.db_pool)
+        .await?;
 
     Ok(success_response(json!({
         "user": UserResponse::from(user),
-        "access_token": access_token,
-        "refresh_token": refresh_token
+        "access_token": tokens.access_token,
+        "refresh_token": tokens.refresh_token
     })))
 }
 
 pub async fn login(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
+    client: ClientInfo,
     Json(login_data): Json<LoginUser>,
 ) -> Result<Response, AppError> {
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT secret not configured".to_string()))?;
-
-    let auth_service = AuthService::new(jwt_secret);
+    let auth_service = AuthService::from_config(&state.auth_config);
 
-    let user = auth_service.login(login_data, &db_pool).await?;
-    let access_token = auth_service.generate_access_token(user.id)?;
-    let refresh_token = auth_service.generate_refresh_token(user.id)?;
+    let user = auth_service.login(login_data, &state.db_pool).await?;
+    let tokens = auth_service
+        .create_session(user.id, &client, &state.db_pool)
+        .await?;
 
     Ok(success_response(json!({
         "user": UserResponse::from(user),
-        "access_token": access_token,
-        "refresh_token": refresh_token
+        "access_token": tokens.access_token,
+        "refresh_token": tokens.refresh_token
     })))
 }
 
 pub async fn refresh_token(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
+    client: ClientInfo,
     Json(body): Json<Value>,
 ) -> Result<Response, AppError> {
     let refresh_token = body
@@ -64,35 +83,22 @@ pub async fn refresh_token(
         .and_then(|v| v.as_str())
         .ok_or_else(|| AppError::BadRequest("Missing refresh_token".to_string()))?;
 
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT secret not configured".to_string()))?;
-
-    let auth_service = AuthService::new(jwt_secret);
-    let user_id = auth_service.verify_token(refresh_token)?;
-
-    // Verify user still exists and is active
-    let user = auth_service
-        .get_user_by_id(user_id, &db_pool)
-        .await?
-        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
-
-    let new_access_token = auth_service.generate_access_token(user.id)?;
+    // 刷新令牌每次使用后轮换, 客户端需要保存新的刷新令牌
+    let auth_service = AuthService::from_config(&state.auth_config);
+    let tokens = auth_service
+        .refresh_session(refresh_token, &client, &state.db_pool)
+        .await?;
 
-    Ok(success_response(json!({
-        "access_token": new_access_token
-    })))
+    Ok(success_response(tokens))
 }
 
 pub async fn get_current_user(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
     AuthenticatedUser(user_id): AuthenticatedUser,
 ) -> Result<Response, AppError> {
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT secret not configured".to_string()))?;
-
-    let auth_service = AuthService::new(jwt_secret);
+    let auth_service = AuthService::from_config(&state.auth_config);
     let user = auth_service
-        .get_user_by_id(user_id, &db_pool)
+        .get_user_by_id(user_id, &state.db_pool)
         .await?
         .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
@@ -106,29 +112,199 @@ pub async fn get_current_user(
 }
 
 pub async fn change_password(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
     AuthenticatedUser(user_id): AuthenticatedUser,
     Json(password_data): Json<ChangePassword>,
 ) -> Result<Response, AppError> {
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT secret not configured".to_string()))?;
-
-    let auth_service = AuthService::new(jwt_secret);
+    let auth_service = AuthService::from_config(&state.auth_config);
     auth_service
         .change_password(
             user_id,
             password_data.current_password,
             password_data.new_password,
-            &db_pool,
+            &state.db_pool,
         )
         .await?;
 
     Ok(success_message_response("Password changed successfully"))
 }
 
-pub async fn logout(AuthenticatedUser(_user_id): AuthenticatedUser) -> Result<Response, AppError> {
+/// 使用验证邮件中的令牌验证邮箱
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(request): Json<VerifyEmail>,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    auth_service
+        .verify_email(&request.token, &state.auth_config, &state.db_pool)
+        .await?;
+
+    Ok(success_message_response("Email verified successfully"))
+}
+
+/// 重新发送验证邮件, 有最短发送间隔限制
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    auth_service
+        .resend_verification_email(
+            user_id,
+            &state.auth_config,
+            state.mailer.as_ref(),
+            &state.db_pool,
+        )
+        .await?;
+
+    Ok(success_message_response("Verification email sent"))
+}
+
+/// 申请重置密码: 邮箱是否注册都返回相同的响应
+///
+/// 查找用户和发送邮件在后台进行, 响应时间不随邮箱是否注册而变化
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(request): Json<RequestPasswordReset>,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    tokio::spawn(async move {
+        if let Err(e) = auth_service
+            .request_password_reset(
+                &request.email,
+                &state.auth_config,
+                state.mailer.as_ref(),
+                &state.db_pool,
+            )
+            .await
+        {
+            tracing::warn!("Failed to process password reset request: {}", e);
+        }
+    });
+
+    Ok(success_message_response(
+        "If the email is registered, a password reset link has been sent",
+    ))
+}
+
+/// 使用重置令牌设置新密码, 成功后所有设备都需要重新登录
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(request): Json<ResetPassword>,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    auth_service
+        .reset_password(&request.token, &request.new_password, &state.db_pool)
+        .await?;
+
+    Ok(success_message_response("Password reset successfully"))
+}
+
+/// 登出: 撤销当前会话, 该会话的刷新令牌不能再使用
+pub async fn logout(
+    State(state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    CurrentSession(session_id): CurrentSession,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    auth_service
+        .revoke_session(user_id, &session_id, &state.db_pool)
+        .await?;
+
     Ok(success_response_with_message(
         Value::Null,
         "Logout successful",
     ))
 }
+
+/// 获取当前用户已登录的会话 (设备) 列表
+pub async fn get_sessions(
+    State(state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    CurrentSession(session_id): CurrentSession,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    let sessions = auth_service
+        .list_sessions(user_id, &session_id, &state.db_pool)
+        .await?;
+
+    Ok(success_response(json!({ "items": sessions })))
+}
+
+/// 撤销一个会话, 该设备需要重新登录
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    let revoked = auth_service
+        .revoke_session(user_id, &session_id, &state.db_pool)
+        .await?;
+
+    if !revoked {
+        return Err(AppError::NotFound("Session not found".to_string()));
+    }
+
+    Ok(success_message_response("Session revoked successfully"))
+}
+
+/// 在所有设备上登出, keep_current=true 时保留当前会话
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    Query(query): Query<RevokeSessionsQuery>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    CurrentSession(session_id): CurrentSession,
+) -> Result<Response, AppError> {
+    let auth_service = AuthService::from_config(&state.auth_config);
+    let keep_session_id = query.keep_current.then_some(session_id.as_str());
+    let revoked = auth_service
+        .revoke_all_sessions(user_id, keep_session_id, &state.db_pool)
+        .await?;
+
+    Ok(success_response_with_message(
+        json!({ "revoked": revoked }),
+        "Sessions revoked successfully",
+    ))
+}
+
+/// 获取个人访问令牌列表, 不包含令牌本身
+pub async fn get_access_tokens(
+    State(db_pool): State<SqlitePool>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let tokens = AccessTokenService::list_tokens(user_id, &db_pool).await?;
+
+    Ok(success_response(json!({ "items": tokens })))
+}
+
+/// 创建个人访问令牌, 完整的令牌只在响应中返回一次
+pub async fn create_access_token(
+    State(db_pool): State<SqlitePool>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Json(token_data): Json<CreatePersonalAccessToken>,
+) -> Result<Response, AppError> {
+    let token = AccessTokenService::create_token(user_id, token_data, &db_pool).await?;
+
+    Ok(success_response_with_message(
+        token,
+        "Access token created, copy it now as it will not be shown again",
+    ))
+}
+
+/// 撤销个人访问令牌, 使用该令牌的脚本和扩展立即失效
+pub async fn revoke_access_token(
+    State(db_pool): State<SqlitePool>,
+    Path(token_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let revoked = AccessTokenService::revoke_token(user_id, token_id, &db_pool).await?;
+
+    if !revoked {
+        return Err(AppError::NotFound("Access token not found".to_string()));
+    }
+
+    Ok(success_message_response(
+        "Access token revoked successfully",
+    ))
+}
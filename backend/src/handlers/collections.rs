@@ -3,11 +3,13 @@ use axum::{
     response::Response,
 };
 use serde::Deserialize;
+use serde_json::json;
 use sqlx::SqlitePool;
 
 use crate::middleware::AuthenticatedUser;
 use crate::models::{CollectionQuery, CreateCollection, UpdateCollection};
-use crate::services::CollectionService;
+use crate::services::{AuthService, CollectionService, SavedSearchService};
+use crate::state::AppState;
 use crate::utils::error::AppError;
 use crate::utils::response::{success_message_response, success_response};
 
@@ -17,6 +19,8 @@ pub struct CollectionListQuery {
     pub is_public: Option<bool>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// 同时返回保存的搜索 (智能收藏夹)
+    pub include_saved_searches: Option<bool>,
 }
 
 pub async fn get_collections(
@@ -24,6 +28,7 @@ pub async fn get_collections(
     Query(query): Query<CollectionListQuery>,
     AuthenticatedUser(user_id): AuthenticatedUser,
 ) -> Result<Response, AppError> {
+    let include_saved_searches = query.include_saved_searches.unwrap_or(false);
     let collection_query = CollectionQuery {
         parent_id: query.parent_id,
         is_public: query.is_public,
@@ -34,6 +39,15 @@ pub async fn get_collections(
     let collections =
         CollectionService::get_collections(user_id, collection_query, &db_pool).await?;
 
+    // 智能收藏夹的成员在读取时计算, 数量为实时结果
+    if include_saved_searches {
+        let saved_searches = SavedSearchService::get_saved_searches(user_id, &db_pool).await?;
+        return Ok(success_response(json!({
+            "collections": collections,
+            "saved_searches": saved_searches,
+        })));
+    }
+
     Ok(success_response(collections))
 }
 
@@ -61,13 +75,17 @@ pub async fn create_collection(
 }
 
 pub async fn update_collection(
-    State(db_pool): State<SqlitePool>,
+    State(state): State<AppState>,
     Path(collection_id): Path<i64>,
     AuthenticatedUser(user_id): AuthenticatedUser,
     Json(update_data): Json<UpdateCollection>,
 ) -> Result<Response, AppError> {
+    if update_data.is_public == Some(true) {
+        AuthService::ensure_email_verified(user_id, &state.auth_config, &state.db_pool).await?;
+    }
+
     let collection =
-        CollectionService::update_collection(user_id, collection_id, update_data, &db_pool)
+        CollectionService::update_collection(user_id, collection_id, update_data, &state.db_pool)
             .await?
             .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
 
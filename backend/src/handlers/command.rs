@@ -6,13 +6,18 @@ use axum::{
 use serde_json::json;
 use tracing::{error, info};
 
+use crate::handlers::search::{build_filters, execute_search, SearchQueryParams};
 use crate::middleware::AuthenticatedUser;
 use crate::models::{
     Action, CommandRequest, CommandResponse, CreateCollection, CreateResource, CreateTag,
-    ResourceBatchRequest, ResourceQuery, StatsPeriod, UpdateResource,
+    ConflictPolicy, ExportFormat, ExportQuery, ImportFormat, ImportOptions, ImportRequest,
+    ResourceBatchRequest, ResourceQuery, SaveSearchRequest, SearchHistoryQuery, StatsPeriod,
+    UpdateResource,
 };
 use crate::services::{
-    CollectionService, ResourceService, StatsService, TagService,
+    ArchiveService, CollectionService, ExportService, FileService, ImportService,
+    LinkCheckerService, ResourceService, SavedSearchService, SearchHistoryService,
+    StatsService, TagService,
 };
 use crate::state::AppState;
 use crate::utils::error::AppError;
@@ -66,7 +71,9 @@ impl From<AppError> for CommandExecutionError {
         let (code, message) = match &error {
             AppError::BadRequest(msg) => ("BAD_REQUEST".to_string(), msg.clone()),
             AppError::Unauthorized(msg) => ("UNAUTHORIZED".to_string(), msg.clone()),
+            AppError::Forbidden(msg) => ("FORBIDDEN".to_string(), msg.clone()),
             AppError::NotFound(msg) => ("NOT_FOUND".to_string(), msg.clone()),
+            AppError::TooManyRequests(msg) => ("TOO_MANY_REQUESTS".to_string(), msg.clone()),
             AppError::Conflict(msg) => ("CONFLICT".to_string(), msg.clone()),
             AppError::Internal(msg) => ("INTERNAL_ERROR".to_string(), msg.clone()),
             AppError::Database(_) => ("DATABASE_ERROR".to_string(), "Database operation failed".to_string()),
@@ -74,6 +81,7 @@ impl From<AppError> for CommandExecutionError {
             AppError::Bcrypt(_) => ("PASSWORD_ERROR".to_string(), "Password processing failed".to_string()),
             AppError::Config(_) => ("CONFIG_ERROR".to_string(), "Configuration error".to_string()),
             AppError::Serialization(_) => ("SERIALIZATION_ERROR".to_string(), "Data serialization failed".to_string()),
+            AppError::Http(_) => ("UPSTREAM_ERROR".to_string(), "Upstream request failed".to_string()),
             AppError::Anyhow(_) => ("UNKNOWN_ERROR".to_string(), "An unknown error occurred".to_string()),
         };
 
@@ -106,17 +114,24 @@ async fn execute_command(
                 })?
             };
 
-            let resources = ResourceService::get_resources(user_id, params, &app_state.db_pool)
-                .await
-                .map_err(|e| {
-                    let mut cmd_error: CommandExecutionError = e.into();
-                    cmd_error.action = Action::GetResources;
-                    cmd_error
-                })?;
+            let response = if params.cursor.is_some() {
+                ResourceService::get_resources_page(user_id, params, &app_state.db_pool)
+                    .await
+                    .map(|page| json!(page))
+            } else {
+                ResourceService::get_resources(user_id, params, &app_state.db_pool)
+                    .await
+                    .map(|resources| json!(resources))
+            }
+            .map_err(|e| {
+                let mut cmd_error: CommandExecutionError = e.into();
+                cmd_error.action = Action::GetResources;
+                cmd_error
+            })?;
 
             Ok(CommandResult {
                 action: Action::GetResources,
-                response: json!(resources),
+                response,
             })
         }
 
@@ -253,30 +268,275 @@ async fn execute_command(
             })
         }
 
+        Action::ImportResources => {
+            let import_request: ImportRequest = command.get_params().map_err(|e| {
+                CommandExecutionError {
+                    action: Action::ImportResources,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: format!("导入参数解析失败: {}", e),
+                    error_details: None,
+                }
+            })?;
+
+            let format = ImportFormat::from(import_request.format.as_deref().unwrap_or("netscape"))
+                .map_err(|e| CommandExecutionError {
+                    action: Action::ImportResources,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: e,
+                    error_details: None,
+                })?;
+
+            let conflict_policy = ConflictPolicy::from(
+                import_request.conflict_policy.as_deref().unwrap_or("skip"),
+            )
+            .map_err(|e| CommandExecutionError {
+                action: Action::ImportResources,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: e,
+                error_details: None,
+            })?;
+
+            let options = ImportOptions {
+                collection_id: import_request.collection_id,
+                conflict_policy,
+                dry_run: import_request.dry_run.unwrap_or(false),
+            };
+
+            let result = ImportService::import(
+                user_id,
+                format,
+                &import_request.content,
+                &options,
+                &app_state.auth_config,
+                &app_state.db_pool,
+            )
+            .await
+            .map_err(|e| {
+                let mut cmd_error: CommandExecutionError = e.into();
+                cmd_error.action = Action::ImportResources;
+                cmd_error
+            })?;
+
+            Ok(CommandResult {
+                action: Action::ImportResources,
+                response: json!(result),
+            })
+        }
+
+        Action::ExportResources => {
+            let export_query: ExportQuery = if command.params.is_null() {
+                ExportQuery::default()
+            } else {
+                command.get_params().map_err(|e| CommandExecutionError {
+                    action: Action::ExportResources,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: format!("导出参数解析失败: {}", e),
+                    error_details: None,
+                })?
+            };
+
+            let format = ExportFormat::from(export_query.format.as_deref().unwrap_or("json"))
+                .map_err(|e| CommandExecutionError {
+                    action: Action::ExportResources,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: e,
+                    error_details: None,
+                })?;
+
+            let export = ExportService::export_library(
+                user_id,
+                export_query.collection_id,
+                export_query.include_archived.unwrap_or(true),
+                &app_state.db_pool,
+            )
+            .await
+            .map_err(|e| {
+                let mut cmd_error: CommandExecutionError = e.into();
+                cmd_error.action = Action::ExportResources;
+                cmd_error
+            })?;
+
+            // 命令模式下 Netscape 格式以字符串形式返回文件内容
+            let response = match format {
+                ExportFormat::Json => json!(export),
+                ExportFormat::Netscape => json!({
+                    "format": "netscape",
+                    "content": ExportService::render_netscape(&export),
+                }),
+            };
+
+            Ok(CommandResult {
+                action: Action::ExportResources,
+                response,
+            })
+        }
+
+        Action::GetResourceLinkStatus => {
+            let resource_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::GetResourceLinkStatus,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let link_check = LinkCheckerService::get_link_check(user_id, resource_id, &app_state.db_pool)
+                .await
+                .and_then(|link_check| {
+                    link_check.ok_or_else(|| AppError::NotFound("Resource not found".to_string()))
+                })
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetResourceLinkStatus;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetResourceLinkStatus,
+                response: json!(link_check),
+            })
+        }
+
+        Action::GetResourceArchive => {
+            let resource_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::GetResourceArchive,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let archive = ArchiveService::get_archive(user_id, resource_id, &app_state.db_pool)
+                .await
+                .and_then(|archive| {
+                    archive.ok_or_else(|| AppError::NotFound("Archive not found".to_string()))
+                })
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetResourceArchive;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetResourceArchive,
+                response: json!(archive),
+            })
+        }
+
+        Action::GetResourceFile => {
+            let resource_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::GetResourceFile,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let file = FileService::get_file(user_id, resource_id, &app_state.db_pool)
+                .await
+                .and_then(|file| {
+                    file.ok_or_else(|| AppError::NotFound("File not found".to_string()))
+                })
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetResourceFile;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetResourceFile,
+                response: json!(file),
+            })
+        }
+
         // 搜索命令
         Action::SearchResources => {
-            let params: ResourceQuery = if command.params.is_null() {
-                ResourceQuery::default()
+            // 参数与 /api/search/resources 的查询参数一致
+            let params: SearchQueryParams = command.get_params().map_err(|e| CommandExecutionError {
+                action: Action::SearchResources,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("搜索参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let result = async {
+                let filters = build_filters(&params, &app_state.search_config)?;
+                execute_search(app_state, user_id, filters).await
+            }
+            .await
+            .map_err(|e| {
+                let mut cmd_error: CommandExecutionError = e.into();
+                cmd_error.action = Action::SearchResources;
+                cmd_error
+            })?;
+
+            Ok(CommandResult {
+                action: Action::SearchResources,
+                response: json!(result),
+            })
+        }
+
+        Action::GetSearchHistory => {
+            let params: SearchHistoryQuery = if command.params.is_null() {
+                SearchHistoryQuery::default()
             } else {
                 command.get_params().map_err(|e| CommandExecutionError {
-                    action: Action::SearchResources,
+                    action: Action::GetSearchHistory,
                     error_code: "INVALID_PARAMS".to_string(),
-                    error_message: format!("搜索参数解析失败: {}", e),
+                    error_message: format!("参数解析失败: {}", e),
                     error_details: None,
                 })?
             };
 
-            let resources = ResourceService::get_resources(user_id, params, &app_state.db_pool)
+            let history = SearchHistoryService::get_recent_searches(user_id, params, &app_state.db_pool)
                 .await
                 .map_err(|e| {
                     let mut cmd_error: CommandExecutionError = e.into();
-                    cmd_error.action = Action::SearchResources;
+                    cmd_error.action = Action::GetSearchHistory;
                     cmd_error
                 })?;
 
             Ok(CommandResult {
-                action: Action::SearchResources,
-                response: json!(resources),
+                action: Action::GetSearchHistory,
+                response: json!(history),
+            })
+        }
+
+        Action::GetPopularSearches => {
+            let params: SearchHistoryQuery = if command.params.is_null() {
+                SearchHistoryQuery::default()
+            } else {
+                command.get_params().map_err(|e| CommandExecutionError {
+                    action: Action::GetPopularSearches,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: format!("参数解析失败: {}", e),
+                    error_details: None,
+                })?
+            };
+
+            let popular = SearchHistoryService::get_popular_searches(user_id, params, &app_state.db_pool)
+                .await
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetPopularSearches;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetPopularSearches,
+                response: json!(popular),
+            })
+        }
+
+        Action::ClearSearchHistory => {
+            let deleted = SearchHistoryService::clear_history(user_id, &app_state.db_pool)
+                .await
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::ClearSearchHistory;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::ClearSearchHistory,
+                response: json!({"message": "搜索历史已清空", "deleted": deleted}),
             })
         }
 
@@ -346,6 +606,139 @@ async fn execute_command(
             })
         }
 
+        // 保存的搜索 (智能收藏夹) 命令
+        Action::GetSavedSearches => {
+            let saved_searches = SavedSearchService::get_saved_searches(user_id, &app_state.db_pool)
+                .await
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetSavedSearches;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetSavedSearches,
+                response: json!(saved_searches),
+            })
+        }
+
+        Action::GetSavedSearch => {
+            let saved_search_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::GetSavedSearch,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let saved_search = SavedSearchService::get_saved_search(user_id, saved_search_id, &app_state.db_pool)
+                .await
+                .and_then(|saved_search| {
+                    saved_search.ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))
+                })
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::GetSavedSearch;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::GetSavedSearch,
+                response: json!(saved_search),
+            })
+        }
+
+        Action::CreateSavedSearch => {
+            let create_data: SaveSearchRequest = command.get_params().map_err(|e| {
+                CommandExecutionError {
+                    action: Action::CreateSavedSearch,
+                    error_code: "INVALID_PARAMS".to_string(),
+                    error_message: format!("创建保存的搜索参数解析失败: {}", e),
+                    error_details: None,
+                }
+            })?;
+
+            let saved_search = SavedSearchService::create_saved_search(user_id, create_data, &app_state.db_pool)
+                .await
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::CreateSavedSearch;
+                    cmd_error
+                })?;
+
+            Ok(CommandResult {
+                action: Action::CreateSavedSearch,
+                response: json!(saved_search),
+            })
+        }
+
+        Action::UpdateSavedSearch => {
+            let saved_search_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::UpdateSavedSearch,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let update_data: SaveSearchRequest = command.get_param("data").map_err(|e| CommandExecutionError {
+                action: Action::UpdateSavedSearch,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("更新数据参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let saved_search = SavedSearchService::update_saved_search(
+                user_id,
+                saved_search_id,
+                update_data,
+                &app_state.db_pool,
+            )
+            .await
+            .and_then(|saved_search| {
+                saved_search.ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))
+            })
+            .map_err(|e| {
+                let mut cmd_error: CommandExecutionError = e.into();
+                cmd_error.action = Action::UpdateSavedSearch;
+                cmd_error
+            })?;
+
+            Ok(CommandResult {
+                action: Action::UpdateSavedSearch,
+                response: json!(saved_search),
+            })
+        }
+
+        Action::DeleteSavedSearch => {
+            let saved_search_id: i64 = command.get_param("id").map_err(|e| CommandExecutionError {
+                action: Action::DeleteSavedSearch,
+                error_code: "INVALID_PARAMS".to_string(),
+                error_message: format!("id参数解析失败: {}", e),
+                error_details: None,
+            })?;
+
+            let deleted = SavedSearchService::delete_saved_search(user_id, saved_search_id, &app_state.db_pool)
+                .await
+                .map_err(|e| {
+                    let mut cmd_error: CommandExecutionError = e.into();
+                    cmd_error.action = Action::DeleteSavedSearch;
+                    cmd_error
+                })?;
+
+            if !deleted {
+                return Err(CommandExecutionError {
+                    action: Action::DeleteSavedSearch,
+                    error_code: "NOT_FOUND".to_string(),
+                    error_message: "保存的搜索未找到".to_string(),
+                    error_details: None,
+                });
+            }
+
+            Ok(CommandResult {
+                action: Action::DeleteSavedSearch,
+                response: json!({"message": "保存的搜索删除成功"}),
+            })
+        }
+
         // 标签管理命令
         Action::GetTags => {
             use crate::models::TagQuery;
@@ -411,6 +804,29 @@ mod tests {
     use crate::models::{Action, CommandRequest, CommandResponse};
     use serde_json::json;
 
+    #[test]
+    fn test_search_params_accept_lists() {
+        use crate::handlers::search::SearchQueryParams;
+        use axum::extract::Query;
+
+        // 命令参数中列表可以是数组, 也可以和查询字符串一样用逗号分隔
+        let command = CommandRequest::_new(
+            Action::SearchResources,
+            json!({"q": "rust", "resource_type": ["link", "note"], "exclude_tags": "old, draft"}),
+        );
+        let params: SearchQueryParams = command.get_params().unwrap();
+        assert_eq!(params.resource_type, vec!["link", "note"]);
+        assert_eq!(params.exclude_tags, vec!["old", "draft"]);
+        assert!(params.tags.is_empty());
+
+        let uri = "/api/search/resources?q=rust&tags=web,rust&is_favorite=true"
+            .parse()
+            .unwrap();
+        let Query(params) = Query::<SearchQueryParams>::try_from_uri(&uri).unwrap();
+        assert_eq!(params.tags, vec!["web", "rust"]);
+        assert_eq!(params.is_favorite, Some(true));
+    }
+
     #[test]
     fn test_command_request_parsing() {
         let params = json!({
@@ -441,7 +857,7 @@ mod tests {
         );
 
         // 验证前端期望的格式结构
-        assert_eq!(backend_response.success, true);
+        assert!(backend_response.success);
         assert!(backend_response.data.is_some());
         assert_eq!(backend_response.request_id, Some("test_request_id".to_string()));
         assert!(backend_response.error.is_none());
@@ -464,7 +880,7 @@ mod tests {
         );
 
         // 验证错误格式
-        assert_eq!(error_response.success, false);
+        assert!(!error_response.success);
         assert!(error_response.data.is_none());
         assert!(error_response.error.is_some());
 
@@ -2,6 +2,7 @@ pub mod auth;
 pub mod collections;
 pub mod command;
 pub mod resources;
+pub mod saved_searches;
 pub mod search;
 pub mod stats;
 pub mod tags;
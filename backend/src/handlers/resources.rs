@@ -1,16 +1,24 @@
 use axum::{
-    extract::{Json, Path, Query, State},
-    response::Response,
+    body::Body,
+    extract::{multipart::Field, Json, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use serde::Deserialize;
 use sqlx::SqlitePool;
 
 use crate::middleware::AuthenticatedUser;
 use crate::models::{
-    CreateResource, CreateResourceReference, ResourceBatchRequest, ResourceBatchResult,
-    ResourceQuery, ResourceReferenceQuery, UpdateResource,
+    AcceptRelatedResource, ArchiveFormat, ArchiveQuery, ConflictPolicy, CreateResource,
+    CreateResourceReference, ExportFormat, ExportQuery, ImportFormat, ImportOptions, RelatedQuery,
+    ResourceBatchRequest, ResourceBatchResult, ResourceQuery, ResourceReferenceQuery,
+    UpdateResource,
+};
+use crate::services::storage::{parse_range, RangeNotSatisfiable};
+use crate::services::{
+    ArchiveService, ExportService, FileService, ImportService, LibraryExporter, LinkCheckerService,
+    RelatedService, ResourceService, UploadedFile,
 };
-use crate::services::ResourceService;
 use crate::state::AppState;
 use crate::utils::error::AppError;
 use crate::utils::response::{
@@ -31,6 +39,9 @@ pub struct ResourceListQuery {
     pub offset: Option<i64>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
+    pub link_status: Option<String>, // ok / redirected / broken / unchecked
+    pub saved_search_id: Option<i64>, // 保存的搜索, 设置后忽略其他过滤条件
+    pub cursor: Option<String>,      // 游标分页, 空字符串表示第一页
 }
 
 /// 获取资源列表
@@ -59,8 +70,17 @@ pub async fn get_resources(
         sort_by: query.sort_by,
         sort_order: query.sort_order,
         resource_type: None, // 暂不从查询参数中获取,后续可以扩展
+        link_status: query.link_status,
+        saved_search_id: query.saved_search_id,
+        cursor: query.cursor,
     };
 
+    // 传入 cursor 时使用游标分页, 否则保持原有的 offset 分页和响应格式
+    if resource_query.cursor.is_some() {
+        let page = ResourceService::get_resources_page(user_id, resource_query, &db_pool).await?;
+        return Ok(success_response(page));
+    }
+
     let resources = ResourceService::get_resources(user_id, resource_query, &db_pool).await?;
 
     Ok(success_response(resources))
@@ -141,6 +161,148 @@ pub async fn batch_update_resources(
     ))
 }
 
+/// 导入文件大小上限 (10MB)
+pub const MAX_IMPORT_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+/// 导入资源
+/// multipart 表单字段: file (必需), format (可选, 默认 netscape), collection_id (可选),
+/// conflict_policy (可选, 默认 skip), dry_run (可选, 默认 false)
+pub async fn import_resources(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<Response, AppError> {
+    let mut content: Option<String> = None;
+    let mut format = ImportFormat::Netscape;
+    let mut options = ImportOptions::default();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart data: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+                let text = String::from_utf8(bytes.to_vec()).map_err(|_| {
+                    AppError::BadRequest("Import file must be UTF-8 encoded".to_string())
+                })?;
+                content = Some(text);
+            }
+            "format" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Invalid format field: {}", e)))?;
+                format = ImportFormat::from(value.trim()).map_err(AppError::BadRequest)?;
+            }
+            "collection_id" => {
+                let value = field.text().await.map_err(|e| {
+                    AppError::BadRequest(format!("Invalid collection_id field: {}", e))
+                })?;
+                if !value.trim().is_empty() {
+                    options.collection_id = Some(value.trim().parse::<i64>().map_err(|_| {
+                        AppError::BadRequest("collection_id must be an integer".to_string())
+                    })?);
+                }
+            }
+            "conflict_policy" => {
+                let value = field.text().await.map_err(|e| {
+                    AppError::BadRequest(format!("Invalid conflict_policy field: {}", e))
+                })?;
+                options.conflict_policy =
+                    ConflictPolicy::from(value.trim()).map_err(AppError::BadRequest)?;
+            }
+            "dry_run" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Invalid dry_run field: {}", e)))?;
+                options.dry_run = value.trim().parse::<bool>().map_err(|_| {
+                    AppError::BadRequest("dry_run must be true or false".to_string())
+                })?;
+            }
+            _ => {}
+        }
+    }
+
+    let content = content.ok_or_else(|| AppError::BadRequest("Missing file field".to_string()))?;
+
+    let result = ImportService::import(
+        user_id,
+        format,
+        &content,
+        &options,
+        &app_state.auth_config,
+        &app_state.db_pool,
+    )
+    .await?;
+
+    let message = if result.dry_run {
+        "Import dry run completed"
+    } else {
+        "Import completed"
+    };
+    Ok(success_response_with_message(result, message))
+}
+
+/// 导出资源库
+/// 以附件形式返回 JSON 文档或 Netscape 书签文件; JSON 文档按页读取资源, 流式写出
+pub async fn export_resources(
+    State(db_pool): State<SqlitePool>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let format = ExportFormat::from(query.format.as_deref().unwrap_or("json"))
+        .map_err(AppError::BadRequest)?;
+    let include_archived = query.include_archived.unwrap_or(true);
+
+    let date = chrono::Utc::now().format("%Y%m%d");
+    let (content_type, filename, body) = match format {
+        ExportFormat::Json => {
+            // 范围错误 (如收藏夹不存在) 在开始写出响应前返回
+            let exporter =
+                LibraryExporter::new(user_id, query.collection_id, include_archived, &db_pool)
+                    .await?;
+            (
+                "application/json; charset=utf-8",
+                format!("bookmarks-{}.json", date),
+                Body::from_stream(ExportService::export_json_stream(exporter, db_pool)),
+            )
+        }
+        ExportFormat::Netscape => {
+            let export = ExportService::export_library(
+                user_id,
+                query.collection_id,
+                include_archived,
+                &db_pool,
+            )
+            .await?;
+            (
+                "text/html; charset=utf-8",
+                format!("bookmarks-{}.html", date),
+                Body::from(ExportService::render_netscape(&export)),
+            )
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 // ==================== 资源引用管理 ====================
 
 /// 创建资源引用关系
@@ -217,3 +379,307 @@ pub async fn get_resource_references(
 
     Ok(success_response(references))
 }
+
+/// 获取推荐的相关资源
+/// 根据相同标签、域名、收藏夹、共同词语和间接引用打分, 每条推荐附带得分和依据
+pub async fn get_related_resources(
+    State(db_pool): State<SqlitePool>,
+    Path(resource_id): Path<i64>,
+    Query(query): Query<RelatedQuery>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let related =
+        RelatedService::get_related_resources(user_id, resource_id, query, &db_pool).await?;
+
+    Ok(success_response(related))
+}
+
+/// 采纳推荐, 创建到推荐资源的引用关系
+/// 请求体可省略, 引用类型默认为 related
+pub async fn accept_related_resource(
+    State(db_pool): State<SqlitePool>,
+    Path((resource_id, target_id)): Path<(i64, i64)>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    body: Option<Json<AcceptRelatedResource>>,
+) -> Result<Response, AppError> {
+    let Json(body) = body.unwrap_or_default();
+    let accepted = RelatedService::accept_related_resource(
+        user_id,
+        resource_id,
+        target_id,
+        body.reference_type,
+        &db_pool,
+    )
+    .await?;
+
+    Ok(success_response_with_message(
+        accepted,
+        "Related resource added as reference",
+    ))
+}
+
+/// 获取资源的链接健康状态
+/// 尚未检查的资源返回 status = unchecked
+pub async fn get_resource_link_status(
+    State(db_pool): State<SqlitePool>,
+    Path(resource_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let link_check = LinkCheckerService::get_link_check(user_id, resource_id, &db_pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Resource not found".to_string()))?;
+
+    Ok(success_response(link_check))
+}
+
+/// 获取链接资源的离线存档
+/// 默认返回清理后的 HTML 页面, format=text 返回纯文本, format=json 返回存档信息
+pub async fn get_resource_archive(
+    State(db_pool): State<SqlitePool>,
+    Path(resource_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Query(query): Query<ArchiveQuery>,
+) -> Result<Response, AppError> {
+    let format = ArchiveFormat::from(query.format.as_deref().unwrap_or("html"))
+        .map_err(AppError::BadRequest)?;
+
+    let archive = ArchiveService::get_archive(user_id, resource_id, &db_pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Archive not found".to_string()))?;
+
+    let response = match format {
+        ArchiveFormat::Json => success_response(archive),
+        ArchiveFormat::Text => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            archive.text,
+        )
+            .into_response(),
+        ArchiveFormat::Html => {
+            let html = ArchiveService::load_html(&archive).await?;
+            (
+                [
+                    (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+                    // 快照来自第三方页面, 禁止脚本执行并隔离到独立源
+                    (
+                        header::CONTENT_SECURITY_POLICY,
+                        "sandbox; default-src 'none'; img-src * data:; style-src * 'unsafe-inline'; font-src * data:",
+                    ),
+                ],
+                html,
+            )
+                .into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+/// 上传文件并创建 File 资源
+/// multipart 表单字段: file (必需), title (可选, 默认文件名), description, collection_id,
+/// tags (逗号分隔), is_favorite, is_private (均可选)
+pub async fn upload_resource_file(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    mut multipart: Multipart,
+) -> Result<Response, AppError> {
+    let mut file: Option<UploadedFile> = None;
+    let mut resource_data = CreateResource {
+        title: String::new(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "file".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    };
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart data: {}", e)))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let file_name = field.file_name().unwrap_or_default().to_string();
+                let content_type = field.content_type().map(str::to_string);
+
+                // 边读边检查大小, 超过上限时不再继续读取
+                let mut data = Vec::new();
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?
+                {
+                    if data.len() + chunk.len() > app_state.max_upload_bytes {
+                        return Err(AppError::BadRequest(format!(
+                            "File exceeds maximum size of {} bytes",
+                            app_state.max_upload_bytes
+                        )));
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+
+                file = Some(UploadedFile {
+                    file_name,
+                    content_type,
+                    data,
+                });
+            }
+            "title" => resource_data.title = read_text_field(field, "title").await?,
+            "description" => {
+                let value = read_text_field(field, "description").await?;
+                resource_data.description = (!value.is_empty()).then_some(value);
+            }
+            "collection_id" => {
+                let value = read_text_field(field, "collection_id").await?;
+                if !value.is_empty() {
+                    resource_data.collection_id = Some(value.parse::<i64>().map_err(|_| {
+                        AppError::BadRequest("collection_id must be an integer".to_string())
+                    })?);
+                }
+            }
+            "tags" => {
+                let value = read_text_field(field, "tags").await?;
+                let tags: Vec<String> = value
+                    .split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+                resource_data.tags = (!tags.is_empty()).then_some(tags);
+            }
+            "is_favorite" | "is_private" => {
+                let value = read_text_field(field, &name).await?;
+                let flag = value
+                    .parse::<bool>()
+                    .map_err(|_| AppError::BadRequest(format!("{} must be true or false", name)))?;
+                if name == "is_favorite" {
+                    resource_data.is_favorite = Some(flag);
+                } else {
+                    resource_data.is_private = Some(flag);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let file = file.ok_or_else(|| AppError::BadRequest("Missing file field".to_string()))?;
+
+    let uploaded = FileService::upload(
+        user_id,
+        resource_data,
+        file,
+        app_state.storage.as_ref(),
+        &app_state.db_pool,
+    )
+    .await?;
+
+    Ok(success_response(uploaded))
+}
+
+/// 下载资源的上传文件
+/// 支持单个范围的 Range 请求, 以附件形式返回避免浏览器直接渲染用户上传的内容
+pub async fn download_resource_file(
+    State(app_state): State<AppState>,
+    Path(resource_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let file = FileService::get_file(user_id, resource_id, &app_state.db_pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("File not found".to_string()))?;
+    let size = file.size as u64;
+
+    let range = match headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+    {
+        Some(value) => match parse_range(value, size) {
+            Ok(range) => range,
+            Err(RangeNotSatisfiable) => {
+                return Ok((
+                    StatusCode::RANGE_NOT_SATISFIABLE,
+                    [(header::CONTENT_RANGE, format!("bytes */{}", size))],
+                )
+                    .into_response());
+            }
+        },
+        None => None,
+    };
+
+    let data = FileService::read(&file, range, app_state.storage.as_ref()).await?;
+
+    let (status, content_range) = match range {
+        Some(range) => (
+            StatusCode::PARTIAL_CONTENT,
+            format!("bytes {}-{}/{}", range.start, range.end, size),
+        ),
+        None => (StatusCode::OK, String::new()),
+    };
+
+    let mut response = (
+        status,
+        [
+            (header::CONTENT_TYPE, file.content_type.clone()),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+            (header::ETAG, format!("\"{}\"", file.checksum)),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(&file.file_name),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        data,
+    )
+        .into_response();
+
+    if !content_range.is_empty() {
+        if let Ok(value) = content_range.parse() {
+            response.headers_mut().insert(header::CONTENT_RANGE, value);
+        }
+    }
+
+    Ok(response)
+}
+
+/// 读取 multipart 文本字段并去掉首尾空白
+async fn read_text_field(field: Field<'_>, name: &str) -> Result<String, AppError> {
+    field
+        .text()
+        .await
+        .map(|value| value.trim().to_string())
+        .map_err(|e| AppError::BadRequest(format!("Invalid {} field: {}", name, e)))
+}
+
+/// 附件文件名: filename 为 ASCII 兼容版本, filename* 为 UTF-8 百分号编码 (RFC 6266)
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Json, Path, State},
+    response::Response,
+};
+use sqlx::SqlitePool;
+
+use crate::middleware::AuthenticatedUser;
+use crate::models::SaveSearchRequest;
+use crate::services::SavedSearchService;
+use crate::utils::error::AppError;
+use crate::utils::response::{success_message_response, success_response};
+
+/// 获取保存的搜索列表, 包含实时的匹配数量
+pub async fn get_saved_searches(
+    State(db_pool): State<SqlitePool>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let saved_searches = SavedSearchService::get_saved_searches(user_id, &db_pool).await?;
+
+    Ok(success_response(saved_searches))
+}
+
+pub async fn get_saved_search(
+    State(db_pool): State<SqlitePool>,
+    Path(saved_search_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let saved_search = SavedSearchService::get_saved_search(user_id, saved_search_id, &db_pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+    Ok(success_response(saved_search))
+}
+
+pub async fn create_saved_search(
+    State(db_pool): State<SqlitePool>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Json(data): Json<SaveSearchRequest>,
+) -> Result<Response, AppError> {
+    let saved_search = SavedSearchService::create_saved_search(user_id, data, &db_pool).await?;
+
+    Ok(success_response(saved_search))
+}
+
+pub async fn update_saved_search(
+    State(db_pool): State<SqlitePool>,
+    Path(saved_search_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Json(data): Json<SaveSearchRequest>,
+) -> Result<Response, AppError> {
+    let saved_search =
+        SavedSearchService::update_saved_search(user_id, saved_search_id, data, &db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+    Ok(success_response(saved_search))
+}
+
+pub async fn delete_saved_search(
+    State(db_pool): State<SqlitePool>,
+    Path(saved_search_id): Path<i64>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let deleted =
+        SavedSearchService::delete_saved_search(user_id, saved_search_id, &db_pool).await?;
+
+    if !deleted {
+        return Err(AppError::NotFound("Saved search not found".to_string()));
+    }
+
+    Ok(success_message_response(
+        "Saved search deleted successfully",
+    ))
+}
@@ -2,29 +2,66 @@ use axum::{
     extract::{Query, State},
     response::Response,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use serde_json::json;
 
 use crate::{
+    config::SearchConfig,
     middleware::AuthenticatedUser,
-    models::{FilterCriteria, PaginationParams, SearchFilters, SearchResponse, SearchType},
-    services::SearchService,
+    models::{
+        FilterCriteria, HighlightOptions, LinkStatus, PaginationParams, ResourceType,
+        SearchFilters, SearchHistoryQuery, SearchMode, SearchResponse, SearchType,
+    },
+    services::{SearchHistoryService, SearchService},
     state::AppState,
     utils::error::AppError,
-    utils::response::success_response,
+    utils::response::{success_response, success_response_with_message},
 };
+#[cfg(feature = "embeddings")]
+use crate::{models::SemanticSearchQuery, services::SemanticSearchService};
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQueryParams {
     pub q: String,
     #[serde(rename = "type")]
     pub search_type: Option<String>,
+    /// keyword (默认) 或 hybrid
+    pub mode: Option<String>,
     pub collection_id: Option<i64>,
-    pub tags: Option<Vec<String>>,
+    /// 资源类型, 多个类型用逗号分隔, 任一匹配即可
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub resource_type: Vec<String>,
+    /// 逗号分隔, 需同时包含
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub tags: Vec<String>,
+    /// 逗号分隔, 排除包含任一标签的资源
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub exclude_tags: Vec<String>,
+    pub is_favorite: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+    pub is_read: Option<bool>,
+    /// ok / redirected / broken / unchecked
+    pub link_status: Option<String>,
     pub date_from: Option<i64>,
     pub date_to: Option<i64>,
     pub page: Option<i64>,
     pub limit: Option<i64>,
+    /// 是否返回高亮片段, 默认 true
+    pub highlight: Option<bool>,
+    /// 覆盖配置中的高亮标记
+    pub highlight_start: Option<String>,
+    pub highlight_end: Option<String>,
+    /// 覆盖配置中的片段词数
+    pub snippet_tokens: Option<i64>,
+    /// 是否返回分面计数, 默认 true
+    pub facets: Option<bool>,
+    /// 覆盖配置中的分面项数
+    pub facet_limit: Option<i64>,
+    /// 游标分页, 空字符串表示第一页; 设置后忽略 page
+    pub cursor: Option<String>,
+    /// 是否返回每个结果的相关度得分, 默认 false
+    pub include_scores: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,11 +75,56 @@ pub async fn search_resources(
     AuthenticatedUser(user_id): AuthenticatedUser,
     Query(query): Query<SearchQueryParams>,
 ) -> Result<Response, AppError> {
-    let filters = build_filters(&query)?;
+    let filters = build_filters(&query, &app_state.search_config)?;
+    let result = execute_search(&app_state, user_id, filters).await?;
 
-    // 使用 FTS5 进行搜索
-    let result: SearchResponse =
-        SearchService::search_resources(user_id, filters, &app_state.db_pool).await?;
+    Ok(success_response(result))
+}
+
+/// 按搜索模式执行搜索, 搜索接口和搜索命令共用
+pub async fn execute_search(
+    app_state: &AppState,
+    user_id: i64,
+    filters: SearchFilters,
+) -> Result<SearchResponse, AppError> {
+    match filters.mode {
+        // 使用 FTS5 进行搜索
+        SearchMode::Keyword => {
+            SearchService::search_resources(user_id, filters, &app_state.db_pool).await
+        }
+        #[cfg(feature = "embeddings")]
+        SearchMode::Hybrid => {
+            SemanticSearchService::hybrid_search(
+                user_id,
+                filters,
+                app_state.embedder.as_ref(),
+                &app_state.search_config.semantic,
+                &app_state.db_pool,
+            )
+            .await
+        }
+        #[cfg(not(feature = "embeddings"))]
+        SearchMode::Hybrid => Err(AppError::BadRequest(
+            "Hybrid search requires the embeddings feature".into(),
+        )),
+    }
+}
+
+/// 语义搜索
+#[cfg(feature = "embeddings")]
+pub async fn semantic_search(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Query(query): Query<SemanticSearchQuery>,
+) -> Result<Response, AppError> {
+    let result = SemanticSearchService::semantic_search(
+        user_id,
+        query,
+        app_state.embedder.as_ref(),
+        &app_state.search_config.semantic,
+        &app_state.db_pool,
+    )
+    .await?;
 
     Ok(success_response(result))
 }
@@ -61,29 +143,86 @@ pub async fn get_search_suggestions(
     })))
 }
 
-fn build_filters(query: &SearchQueryParams) -> Result<SearchFilters, AppError> {
+/// 最近的搜索历史
+pub async fn get_search_history(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Query(query): Query<SearchHistoryQuery>,
+) -> Result<Response, AppError> {
+    let history =
+        SearchHistoryService::get_recent_searches(user_id, query, &app_state.db_pool).await?;
+
+    Ok(success_response(history))
+}
+
+/// 搜索次数最多的查询
+pub async fn get_popular_searches(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+    Query(query): Query<SearchHistoryQuery>,
+) -> Result<Response, AppError> {
+    let popular =
+        SearchHistoryService::get_popular_searches(user_id, query, &app_state.db_pool).await?;
+
+    Ok(success_response(popular))
+}
+
+/// 清空搜索历史
+pub async fn clear_search_history(
+    State(app_state): State<AppState>,
+    AuthenticatedUser(user_id): AuthenticatedUser,
+) -> Result<Response, AppError> {
+    let deleted = SearchHistoryService::clear_history(user_id, &app_state.db_pool).await?;
+
+    Ok(success_response_with_message(
+        json!({ "deleted": deleted }),
+        "Search history cleared",
+    ))
+}
+
+pub fn build_filters(
+    query: &SearchQueryParams,
+    search_config: &SearchConfig,
+) -> Result<SearchFilters, AppError> {
     if query.q.trim().is_empty() {
         return Err(AppError::BadRequest("Search query cannot be empty".into()));
     }
 
-    // 解析搜索类型
-    let search_type = match query
-        .search_type
+    // 解析搜索类型, 无法识别时搜索全部字段
+    let search_type =
+        SearchType::from(query.search_type.as_deref().unwrap_or("all")).unwrap_or_default();
+    let mode = query
+        .mode
         .as_deref()
-        .unwrap_or("all")
-        .to_lowercase()
-        .as_str()
-    {
-        "title" => SearchType::Title,
-        "content" => SearchType::Content,
-        "url" => SearchType::Url,
-        _ => SearchType::All,
-    };
+        .map(SearchMode::from)
+        .transpose()
+        .map_err(AppError::BadRequest)?
+        .unwrap_or_default();
 
     // 构建过滤条件
+    let resource_types = query
+        .resource_type
+        .iter()
+        .map(|resource_type| ResourceType::from(resource_type).map(|t| t.as_str().to_string()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::BadRequest)?;
+    let link_status = query
+        .link_status
+        .as_deref()
+        .map(LinkStatus::from)
+        .transpose()
+        .map_err(AppError::BadRequest)?;
+
     let filters = FilterCriteria {
         collection_id: query.collection_id,
-        tags: query.tags.clone().unwrap_or_default(),
+        resource_types,
+        tags: query.tags.clone(),
+        exclude_tags: query.exclude_tags.clone(),
+        is_favorite: query.is_favorite,
+        is_archived: query.is_archived,
+        is_private: query.is_private,
+        is_read: query.is_read,
+        link_status,
         date_from: query.date_from,
         date_to: query.date_to,
     };
@@ -93,10 +232,97 @@ fn build_filters(query: &SearchQueryParams) -> Result<SearchFilters, AppError> {
     let page = query.page.unwrap_or(1).max(1);
     let pagination = PaginationParams::from_page(page, limit);
 
+    let highlight = if query.highlight.unwrap_or(true) {
+        Some(build_highlight_options(query, search_config)?)
+    } else {
+        None
+    };
+
+    let facet_limit = if query.facets.unwrap_or(true) {
+        Some(
+            query
+                .facet_limit
+                .unwrap_or(search_config.facet_limit)
+                .clamp(1, MAX_FACET_LIMIT),
+        )
+    } else {
+        None
+    };
+
     Ok(SearchFilters {
         query: query.q.clone(),
         search_type,
+        mode,
         filters,
         pagination,
+        highlight,
+        facet_limit,
+        cursor: query.cursor.clone(),
+        ranking: search_config.ranking.clone(),
+        include_scores: query.include_scores.unwrap_or(false),
+    })
+}
+
+/// 分面项数上限
+const MAX_FACET_LIMIT: i64 = 50;
+
+/// 列表参数: 查询字符串中为逗号分隔的字符串, JSON 中也可以是数组
+fn deserialize_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Joined(String),
+        Items(Vec<String>),
+    }
+
+    let items = match List::deserialize(deserializer)? {
+        List::Joined(joined) => joined.split(',').map(str::to_string).collect(),
+        List::Items(items) => items,
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+/// 高亮标记的最大长度
+const MAX_HIGHLIGHT_MARKER_LENGTH: usize = 32;
+
+fn build_highlight_options(
+    query: &SearchQueryParams,
+    search_config: &SearchConfig,
+) -> Result<HighlightOptions, AppError> {
+    let start_marker = query
+        .highlight_start
+        .clone()
+        .unwrap_or_else(|| search_config.highlight_start.clone());
+    let end_marker = query
+        .highlight_end
+        .clone()
+        .unwrap_or_else(|| search_config.highlight_end.clone());
+
+    // 开始标记用于判断字段是否命中, 不能为空
+    if start_marker.is_empty()
+        || start_marker.len() > MAX_HIGHLIGHT_MARKER_LENGTH
+        || end_marker.len() > MAX_HIGHLIGHT_MARKER_LENGTH
+    {
+        return Err(AppError::BadRequest(format!(
+            "Highlight markers must be 1-{} characters",
+            MAX_HIGHLIGHT_MARKER_LENGTH
+        )));
+    }
+
+    Ok(HighlightOptions {
+        start_marker,
+        end_marker,
+        // FTS5 snippet() 的词数上限为 64
+        snippet_tokens: query
+            .snippet_tokens
+            .unwrap_or(search_config.snippet_tokens)
+            .clamp(1, 64),
     })
 }
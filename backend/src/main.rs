@@ -16,8 +16,8 @@ mod utils;
 use config::AppConfig;
 use middleware::{auth_middleware, logging_middleware};
 use routes::{
-    ano_routes, auth_routes, collection_routes, command_routes, resource_routes, search_routes, stats_routes,
-    tag_routes,
+    ano_routes, auth_routes, collection_routes, command_routes, resource_routes, saved_search_routes,
+    search_routes, stats_routes, tag_routes,
 };
 use state::AppState;
 use utils::jwt::{JWTService, JwtClaims};
@@ -47,10 +47,46 @@ async fn main() -> anyhow::Result<()> {
     // 这个操作在后台异步执行，不会阻塞服务器启动
     services::check_and_rebuild_fts(db_pool.clone()).await?;
 
+    // 上传文件存储后端
+    let storage = services::storage::build_storage(&config.storage)?;
+
+    // 通知邮件发送后端
+    let mailer = services::mailer::build_mailer(&config.mail)?;
+
+    // 启动后台任务 worker (链接元数据抓取等)
+    if config.jobs.enabled {
+        let worker = services::JobWorker::new(
+            db_pool.clone(),
+            config.jobs.clone(),
+            config.fetcher.clone(),
+            config.archive.clone(),
+            storage.clone(),
+        )?;
+        tokio::spawn(worker.run());
+    }
+
+    // 启动定时失效链接检查
+    if config.link_checker.enabled {
+        let link_checker = services::LinkChecker::new(
+            db_pool.clone(),
+            config.link_checker.clone(),
+            config.fetcher.clone(),
+        )?;
+        tokio::spawn(link_checker.run());
+    }
+
     // Initialize shared JWT decoder for middleware
     let jwt_decoder: Decoder<JwtClaims> = Arc::new(JWTService::new(config.auth.jwt_secret.clone()));
 
-    let app_state = AppState::new(db_pool.clone(), jwt_decoder);
+    let app_state = AppState::new(
+        db_pool.clone(),
+        jwt_decoder,
+        storage,
+        config.storage.max_upload_bytes,
+        config.search.clone(),
+        config.auth.clone(),
+        mailer,
+    );
 
     // Protected routes requiring authentication
     let protected_routes = Router::new()
@@ -58,6 +94,7 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api/collections", collection_routes())
         .nest("/api/tags", tag_routes())
         .nest("/api/search", search_routes())
+        .nest("/api/saved-searches", saved_search_routes())
         .nest("/api/stats", stats_routes())
         .nest("/api/auth", auth_routes())
         .nest("/api/command", command_routes())
@@ -76,7 +113,12 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // 记录连接的对端地址, 会话列表中展示登录设备的 IP
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -1,14 +1,30 @@
+use std::net::SocketAddr;
+
 use axum::{
-    extract::{FromRequestParts, Request, State},
-    http::request::Parts,
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{header, request::Parts, Method},
     middleware::Next,
     response::Response,
 };
 use axum_jwt_auth::{AuthError, Claims as JwtClaimsExtractor};
+use chrono::Utc;
+use serde::Deserialize;
 
+use crate::models::{ClientInfo, CollectionScopedRequest};
+use crate::services::{AccessTokenService, ACCESS_TOKEN_PREFIX};
 use crate::state::AppState;
 use crate::utils::error::AppError;
-use crate::utils::jwt::JwtClaims;
+use crate::utils::jwt::{JwtClaims, TokenType};
+
+/// 访问令牌的使用记录到会话的最短间隔 (秒)
+const SESSION_TOUCH_INTERVAL_SECS: i64 = 60;
+/// 保存的 User-Agent 最大长度 (字符)
+const MAX_USER_AGENT_LENGTH: usize = 512;
+/// 检查限定收藏夹的令牌时读取的请求体上限 (字节)
+const MAX_SCOPED_BODY_BYTES: usize = 1024 * 1024;
+/// 受限令牌可以读取的资源子路径, 只涉及资源本身
+const RESOURCE_READ_SUBPATHS: [&str; 3] = ["archive", "file", "link-status"];
 
 pub async fn auth_middleware(
     State(app_state): State<AppState>,
@@ -17,36 +33,198 @@ pub async fn auth_middleware(
 ) -> Result<Response, AppError> {
     let (mut parts, body) = request.into_parts();
 
+    // 个人访问令牌以固定前缀开头, 其余按 JWT 处理
+    let access_token = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| token.starts_with(ACCESS_TOKEN_PREFIX))
+        .map(str::to_string);
+    if let Some(token) = access_token {
+        let (user_id, body) = authenticate_access_token(&app_state, &parts, &token, body).await?;
+        parts.extensions.insert(user_id);
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    }
+
     let claims = JwtClaimsExtractor::<JwtClaims>::from_request_parts(&mut parts, &app_state)
         .await
         .map_err(map_auth_error)?;
 
+    // 刷新令牌只能用于 /api/auth/refresh
+    if claims.claims.typ != TokenType::Access {
+        return Err(AppError::Unauthorized(
+            "Invalid authentication token".to_string(),
+        ));
+    }
+
     let user_id = claims
         .claims
         .sub
         .parse::<i64>()
         .map_err(|_| AppError::Unauthorized("Invalid token subject".to_string()))?;
 
-    // Verify user exists and is active
-    let user_exists: bool =
-        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND is_active = TRUE)")
-            .bind(user_id)
-            .fetch_one(&app_state.db_pool)
-            .await?;
+    // 会话被撤销 (登出或在其他设备上被移除) 后, 尚未过期的访问令牌也不能再使用
+    let session_active: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM user_sessions s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.id = $1 AND s.user_id = $2 AND s.revoked_at IS NULL AND u.is_active = TRUE
+        )
+        "#,
+    )
+    .bind(&claims.claims.sid)
+    .bind(user_id)
+    .fetch_one(&app_state.db_pool)
+    .await?;
 
-    if !user_exists {
+    if !session_active {
         return Err(AppError::Unauthorized(
-            "User not found or inactive".to_string(),
+            "Session revoked or user inactive".to_string(),
         ));
     }
 
+    // 记录会话的最近使用时间, 每分钟最多写一次
+    let now = Utc::now().timestamp();
+    sqlx::query("UPDATE user_sessions SET last_used_at = $1 WHERE id = $2 AND last_used_at < $3")
+        .bind(now)
+        .bind(&claims.claims.sid)
+        .bind(now - SESSION_TOUCH_INTERVAL_SECS)
+        .execute(&app_state.db_pool)
+        .await?;
+
     // Add user ID to request extensions
     parts.extensions.insert(user_id);
+    parts.extensions.insert(CurrentSession(claims.claims.sid));
     let request = Request::from_parts(parts, body);
 
     Ok(next.run(request).await)
 }
 
+/// 验证个人访问令牌并按权限范围检查请求, 返回用户 ID 和请求体
+///
+/// 限定收藏夹的令牌需要读取请求体中的 collection_id, 读取后重新构造请求体交给 handler。
+async fn authenticate_access_token(
+    app_state: &AppState,
+    parts: &Parts,
+    token: &str,
+    body: Body,
+) -> Result<(i64, Body), AppError> {
+    let ip_address = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let (user_id, access) =
+        AccessTokenService::authenticate(token, ip_address.as_deref(), &app_state.db_pool).await?;
+    AccessTokenService::check_scope(&access, &parts.method, parts.uri.path())?;
+
+    if access.collection_ids.is_none() {
+        return Ok((user_id, body));
+    }
+    let (scoped_request, body) = collection_scoped_request(parts, body).await?;
+    AccessTokenService::check_collection_access(
+        user_id,
+        &access,
+        scoped_request,
+        &app_state.db_pool,
+    )
+    .await?;
+
+    Ok((user_id, body))
+}
+
+/// 请求体中与收藏夹有关的字段
+#[derive(Debug, Default, Deserialize)]
+struct CollectionFields {
+    collection_id: Option<i64>,
+    clear_collection_id: Option<bool>,
+}
+
+/// 根据请求路径解析限定收藏夹的令牌要访问的收藏夹, 创建和修改资源时读取请求体
+async fn collection_scoped_request(
+    parts: &Parts,
+    body: Body,
+) -> Result<(CollectionScopedRequest, Body), AppError> {
+    let path = parts.uri.path().trim_end_matches('/');
+    let segments: Vec<&str> = path
+        .strip_prefix("/api/")
+        .unwrap_or_default()
+        .split('/')
+        .collect();
+    let method = &parts.method;
+
+    let request = match segments.as_slice() {
+        ["collections", id] if *method == Method::GET => id.parse().map_or(
+            CollectionScopedRequest::Other,
+            CollectionScopedRequest::Collection,
+        ),
+        ["resources"] if *method == Method::GET => {
+            let query: Vec<_> = parts
+                .uri
+                .query()
+                .map(|query| url::form_urlencoded::parse(query.as_bytes()).collect())
+                .unwrap_or_default();
+            // 保存的搜索会忽略 collection_id 过滤, 无法限定在收藏夹内
+            if query.iter().any(|(key, _)| key == "saved_search_id") {
+                CollectionScopedRequest::Other
+            } else {
+                let collection_id = query
+                    .iter()
+                    .find(|(key, _)| key == "collection_id")
+                    .and_then(|(_, value)| value.parse().ok());
+                CollectionScopedRequest::ListResources { collection_id }
+            }
+        }
+        ["resources"] if *method == Method::POST => {
+            let (fields, body) = read_collection_fields(body).await?;
+            let request = CollectionScopedRequest::CreateResource {
+                collection_id: fields.collection_id,
+            };
+            return Ok((request, body));
+        }
+        ["resources", id, rest @ ..] => match id.parse::<i64>() {
+            Ok(id) if rest.is_empty() && *method == Method::PUT => {
+                let (fields, body) = read_collection_fields(body).await?;
+                let request = CollectionScopedRequest::Resource {
+                    id,
+                    move_to: fields.collection_id,
+                    clear_collection: fields.clear_collection_id.unwrap_or(false),
+                };
+                return Ok((request, body));
+            }
+            // 只允许读取资源本身的子路径, 引用和相关推荐会返回其他收藏夹的资源
+            Ok(id)
+                if (*method == Method::GET
+                    && (rest.is_empty()
+                        || matches!(rest, [sub] if RESOURCE_READ_SUBPATHS.contains(sub))))
+                    || (rest.is_empty() && *method == Method::DELETE) =>
+            {
+                CollectionScopedRequest::Resource {
+                    id,
+                    move_to: None,
+                    clear_collection: false,
+                }
+            }
+            _ => CollectionScopedRequest::Other,
+        },
+        _ => CollectionScopedRequest::Other,
+    };
+
+    Ok((request, body))
+}
+
+/// 读取 JSON 请求体中的收藏夹字段, 无法解析时视为未指定, 由 handler 报告格式错误
+async fn read_collection_fields(body: Body) -> Result<(CollectionFields, Body), AppError> {
+    let bytes = axum::body::to_bytes(body, MAX_SCOPED_BODY_BYTES)
+        .await
+        .map_err(|_| AppError::BadRequest("Request body is too large".to_string()))?;
+    let fields = serde_json::from_slice(&bytes).unwrap_or_default();
+
+    Ok((fields, Body::from(bytes)))
+}
+
 // 自定义 Extractor：自动从 request extensions 提取 user_id
 #[derive(Debug, Clone, Copy)]
 pub struct AuthenticatedUser(pub i64);
@@ -67,6 +245,52 @@ where
     }
 }
 
+/// 当前访问令牌所属的会话 ID (刷新令牌家族 ID)
+#[derive(Debug, Clone)]
+pub struct CurrentSession(pub String);
+
+impl<S> FromRequestParts<S> for CurrentSession
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentSession>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("User not authenticated".to_string()))
+    }
+}
+
+/// 从请求中读取 User-Agent 和对端 IP 地址
+///
+/// IP 地址取自 TCP 连接, 部署在反向代理之后时为代理的地址; 仅用于在会话列表中展示。
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        Ok(ClientInfo {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
 fn map_auth_error(err: AuthError) -> AppError {
     match err {
         AuthError::MissingToken => AppError::Unauthorized("Missing authentication token".into()),
@@ -83,3 +307,82 @@ fn map_auth_error(err: AuthError) -> AppError {
         | AuthError::InternalError => AppError::Unauthorized("Invalid authentication token".into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn scoped(method: Method, uri: &str, body: &str) -> (CollectionScopedRequest, String) {
+        let (parts, body) = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+            .into_parts();
+        let (request, body) = collection_scoped_request(&parts, body).await.unwrap();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (request, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_collection_scoped_request() {
+        let (request, _) = scoped(Method::GET, "/api/resources?limit=5&collection_id=2", "").await;
+        assert_eq!(
+            request,
+            CollectionScopedRequest::ListResources {
+                collection_id: Some(2)
+            }
+        );
+
+        // 读取请求体后原样交给 handler
+        let body = r#"{"title":"Tokio","collection_id":3}"#;
+        let (request, forwarded) = scoped(Method::PUT, "/api/resources/5", body).await;
+        assert_eq!(
+            request,
+            CollectionScopedRequest::Resource {
+                id: 5,
+                move_to: Some(3),
+                clear_collection: false
+            }
+        );
+        assert_eq!(forwarded, body);
+
+        let (request, _) = scoped(Method::POST, "/api/resources", "not json").await;
+        assert_eq!(
+            request,
+            CollectionScopedRequest::CreateResource {
+                collection_id: None
+            }
+        );
+
+        let (request, _) = scoped(Method::GET, "/api/collections/7/", "").await;
+        assert_eq!(request, CollectionScopedRequest::Collection(7));
+
+        let (request, _) = scoped(Method::GET, "/api/resources/5/archive", "").await;
+        assert_eq!(
+            request,
+            CollectionScopedRequest::Resource {
+                id: 5,
+                move_to: None,
+                clear_collection: false
+            }
+        );
+
+        for (method, uri) in [
+            (Method::POST, "/api/resources/5/references"),
+            (Method::GET, "/api/resources/5/references"),
+            (Method::GET, "/api/resources/5/related"),
+            (Method::GET, "/api/resources/5/file/extra"),
+            (
+                Method::GET,
+                "/api/resources?collection_id=2&saved_search_id=1",
+            ),
+            (Method::GET, "/api/resources/export"),
+            (Method::GET, "/api/collections"),
+            (Method::GET, "/api/tags"),
+        ] {
+            let (request, _) = scoped(method, uri, "").await;
+            assert_eq!(request, CollectionScopedRequest::Other);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// 个人访问令牌的权限范围, 后者包含前者的权限
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    Read,  // 只能使用 GET 请求读取数据
+    Write, // 可以修改资源、收藏夹等数据, 不能管理账户
+    Admin, // 还可以管理账户: 访问令牌、会话、密码等
+}
+
+impl TokenScope {
+    /// 从字符串解析权限范围
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s {
+            "read" => Ok(TokenScope::Read),
+            "write" => Ok(TokenScope::Write),
+            "admin" => Ok(TokenScope::Admin),
+            _ => Err(format!("Unknown token scope: {}", s)),
+        }
+    }
+
+    /// 转换为数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Write => "write",
+            TokenScope::Admin => "admin",
+        }
+    }
+}
+
+/// 个人访问令牌, 令牌本身只在创建时返回一次
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonalAccessToken {
+    pub id: i64,
+    pub name: String,
+    /// 令牌开头的几个字符, 用于辨认令牌
+    pub token_prefix: String,
+    pub scope: TokenScope,
+    /// 限定可以访问的收藏夹, None 表示不限
+    pub collection_ids: Option<Vec<i64>>,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+    pub last_used_ip: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePersonalAccessToken {
+    pub name: String,
+    pub scope: TokenScope,
+    /// 只能访问这些收藏夹中的资源, 省略表示不限
+    pub collection_ids: Option<Vec<i64>>,
+    /// 有效天数, 省略表示不过期
+    pub expires_in_days: Option<i64>,
+}
+
+/// 创建令牌的响应, 包含完整的令牌
+#[derive(Debug, Serialize)]
+pub struct CreatedPersonalAccessToken {
+    #[serde(flatten)]
+    pub token: PersonalAccessToken,
+    /// 之后无法再次获取, 需要由客户端保存
+    #[serde(rename = "token")]
+    pub secret: String,
+}
+
+/// 通过认证的个人访问令牌的权限
+#[derive(Debug, Clone)]
+pub struct TokenAccess {
+    pub scope: TokenScope,
+    /// 限定可以访问的收藏夹, None 表示不限
+    pub collection_ids: Option<Vec<i64>>,
+}
+
+/// 限定收藏夹的令牌可以发起的请求, 由 auth_middleware 根据请求路径和请求体解析
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectionScopedRequest {
+    /// GET /api/collections/{id}
+    Collection(i64),
+    /// GET /api/resources, 必须指定 collection_id
+    ListResources { collection_id: Option<i64> },
+    /// POST /api/resources, 请求体中的 collection_id
+    CreateResource { collection_id: Option<i64> },
+    /// /api/resources/{id} 及其下的只读接口; 修改时请求体中的目标收藏夹
+    Resource {
+        id: i64,
+        move_to: Option<i64>,
+        clear_collection: bool,
+    },
+    /// 其他请求, 限定收藏夹的令牌不能访问
+    Other,
+}
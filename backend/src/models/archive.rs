@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 存档返回格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Html, // 清理后的 HTML 页面
+    Text, // 提取的纯文本
+    Json, // 存档信息和纯文本
+}
+
+impl ArchiveFormat {
+    /// 从字符串解析存档格式
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(ArchiveFormat::Html),
+            "text" | "txt" => Ok(ArchiveFormat::Text),
+            "json" => Ok(ArchiveFormat::Json),
+            _ => Err(format!("Unsupported archive format: {}", s)),
+        }
+    }
+}
+
+/// 存档查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct ArchiveQuery {
+    pub format: Option<String>, // 默认 html
+}
+
+/// 链接资源的离线存档
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResourceArchive {
+    pub resource_id: i64,
+    /// 快照对应的最终地址
+    pub url: String,
+    pub title: Option<String>,
+    pub text: String,
+    /// HTML 字节数
+    pub size: i64,
+    pub archived_at: i64,
+    /// 保存在数据库中的 HTML, 通过 ArchiveService::load_html 读取
+    #[serde(skip)]
+    pub html: Option<String>,
+    /// 保存在存储目录中的 HTML 文件路径
+    #[serde(skip)]
+    pub storage_path: Option<String>,
+}
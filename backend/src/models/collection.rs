@@ -36,6 +36,8 @@ pub struct UpdateCollection {
     pub parent_id: Option<i64>,
     pub clear_parent_id: Option<bool>,
     pub sort_order: Option<i32>,
+    /// 公开收藏夹, 配置 require_verified_email 时需要先验证邮箱
+    pub is_public: Option<bool>,
 }
 
 #[allow(dead_code)]
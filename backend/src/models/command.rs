@@ -11,6 +11,11 @@ pub enum Action {
     UpdateResource,
     DeleteResource,
     BatchUpdateResources,
+    ImportResources,
+    ExportResources,
+    GetResourceLinkStatus,
+    GetResourceArchive,
+    GetResourceFile,
 
     // 资源引用管理命令
     CreateResourceReference,
@@ -24,6 +29,13 @@ pub enum Action {
     UpdateCollection,
     DeleteCollection,
 
+    // 保存的搜索 (智能收藏夹) 命令
+    GetSavedSearches,
+    GetSavedSearch,
+    CreateSavedSearch,
+    UpdateSavedSearch,
+    DeleteSavedSearch,
+
     // 标签管理命令
     GetTags,
     GetTag,
@@ -33,6 +45,9 @@ pub enum Action {
 
     // 搜索命令
     SearchResources,
+    GetSearchHistory,
+    GetPopularSearches,
+    ClearSearchHistory,
 
     // 统计命令
     GetUserStats,
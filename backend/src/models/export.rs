@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 当前 JSON 导出格式版本
+/// 格式发生不兼容变化时递增, 导入时据此判断如何解析
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,     // 无损 JSON 文档
+    Netscape, // 浏览器可导入的 bookmarks.html (仅 link 资源)
+}
+
+impl ExportFormat {
+    /// 从字符串解析导出格式
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "netscape" | "html" => Ok(ExportFormat::Netscape),
+            _ => Err(format!("Unsupported export format: {}", s)),
+        }
+    }
+}
+
+/// 导出查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct ExportQuery {
+    pub format: Option<String>,         // 默认 json
+    pub collection_id: Option<i64>,     // 仅导出该收藏夹及其子收藏夹
+    pub include_archived: Option<bool>, // 默认 true
+}
+
+/// 无损导出文档
+///
+/// 所有 id 均为导出方数据库中的原始 id, 仅用于在文档内部建立关联,
+/// 导入时会重新分配
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryExport {
+    pub version: u32,
+    pub exported_at: i64,
+    pub collections: Vec<ExportedCollection>,
+    pub tags: Vec<ExportedTag>,
+    pub resources: Vec<ExportedResource>,
+    pub references: Vec<ExportedReference>,
+}
+
+/// 导出的收藏夹
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportedCollection {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub is_public: bool,
+    pub parent_id: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 导出的标签
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportedTag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 导出的资源 (包含全部字段和标签名称)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportedResource {
+    pub id: i64,
+    pub collection_id: Option<i64>,
+    pub title: String,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub favicon_url: Option<String>,
+    pub screenshot_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default)]
+    pub is_read: bool,
+    #[serde(default)]
+    pub visit_count: i64,
+    pub last_visited: Option<i64>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub resource_type: String,
+    pub content: Option<String>,
+    pub source: Option<String>,
+    pub mime_type: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
+    /// 上传文件的元数据 (仅 file 资源), 文档中不包含文件内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub file: Option<ExportedFile>,
+}
+
+/// 导出的上传文件元数据
+///
+/// 存储位置只用于核对来源, 导入时不会据此关联已有的存储对象
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportedFile {
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub checksum: String,
+    pub storage_backend: String,
+    pub storage_key: String,
+}
+
+/// 导出的资源引用关系
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportedReference {
+    pub source_id: i64,
+    pub target_id: i64,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub reference_type: String,
+    pub created_at: i64,
+}
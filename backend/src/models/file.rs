@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::Resource;
+
+/// File 资源的上传文件信息
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResourceFile {
+    pub resource_id: i64,
+    /// 上传时的原始文件名
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    /// SHA-256 (十六进制)
+    pub checksum: String,
+    pub created_at: i64,
+    /// 正文提取完成时间, 未提取或格式不支持时为 None
+    pub extracted_at: Option<i64>,
+    /// 写入时使用的存储后端
+    #[serde(skip)]
+    pub storage_backend: String,
+    /// 对象在存储后端中的键
+    #[serde(skip)]
+    pub storage_key: String,
+}
+
+/// 上传结果: 新建的资源及其文件信息
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadedResource {
+    #[serde(flatten)]
+    pub resource: Resource,
+    pub file: ResourceFile,
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// 导入文件格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Netscape, // 浏览器导出的 bookmarks.html
+    Json,     // 本系统导出的无损 JSON 文档
+    Pocket,   // Pocket HTML/CSV 导出
+    Pinboard, // Pinboard JSON 导出
+    Raindrop, // Raindrop.io CSV 导出
+}
+
+impl ImportFormat {
+    /// 从字符串解析导入格式
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "netscape" | "html" => Ok(ImportFormat::Netscape),
+            "json" => Ok(ImportFormat::Json),
+            "pocket" => Ok(ImportFormat::Pocket),
+            "pinboard" => Ok(ImportFormat::Pinboard),
+            "raindrop" => Ok(ImportFormat::Raindrop),
+            _ => Err(format!("Unsupported import format: {}", s)),
+        }
+    }
+}
+
+/// 导入冲突处理策略
+///
+/// 冲突指同名收藏夹、同名标签 (唯一约束) 以及已存在的相同 URL 资源
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    #[default]
+    Skip, // 保留已有数据, 不导入冲突项
+    Merge,     // 保留已有数据, 只补充缺失的字段和标签
+    Rename,    // 以新名称创建收藏夹/标签, 重复 URL 的资源作为新资源导入
+    Overwrite, // 用导入的数据覆盖已有数据
+}
+
+impl ConflictPolicy {
+    /// 从字符串解析冲突策略
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ConflictPolicy::Skip),
+            "merge" => Ok(ConflictPolicy::Merge),
+            "rename" => Ok(ConflictPolicy::Rename),
+            "overwrite" => Ok(ConflictPolicy::Overwrite),
+            _ => Err(format!("Unsupported conflict policy: {}", s)),
+        }
+    }
+}
+
+/// 导入选项
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    pub collection_id: Option<i64>, // 顶层文件夹和书签的父收藏夹
+    pub conflict_policy: ConflictPolicy,
+    pub dry_run: bool, // 只报告将发生的变化, 不提交
+}
+
+/// 导入请求 (命令模式下通过 JSON 传入文件内容)
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    pub format: Option<String>,
+    pub content: String,
+    pub collection_id: Option<i64>, // 顶层文件夹和书签的父收藏夹
+    pub conflict_policy: Option<String>,
+    pub dry_run: Option<bool>,
+}
+
+/// 单条导入记录的处理状态
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportEntryStatus {
+    Imported,
+    Updated, // 按 merge/overwrite 策略更新了已有资源
+    Skipped, // 重复 URL
+    Failed,
+}
+
+/// 单条导入记录的处理结果
+#[derive(Debug, Serialize)]
+pub struct ImportEntryResult {
+    pub index: usize,
+    pub title: String,
+    pub url: Option<String>,
+    pub status: ImportEntryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// 导入结果
+#[derive(Debug, Serialize, Default)]
+pub struct ImportResult {
+    pub dry_run: bool,
+    pub imported: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub collections_created: usize,
+    pub collections_updated: usize,
+    pub tags_created: usize,
+    pub tags_updated: usize,
+    pub references_created: usize,
+    pub entries: Vec<ImportEntryResult>,
+}
+
+impl ImportResult {
+    /// 记录一条导入结果并更新计数
+    pub fn record(&mut self, entry: ImportEntryResult) {
+        match entry.status {
+            ImportEntryStatus::Imported => self.imported += 1,
+            ImportEntryStatus::Updated => self.updated += 1,
+            ImportEntryStatus::Skipped => self.skipped += 1,
+            ImportEntryStatus::Failed => self.failed += 1,
+        }
+        self.entries.push(entry);
+    }
+}
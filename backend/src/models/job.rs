@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 后台任务类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    FetchMetadata, // 抓取链接页面的标题、描述、图标等元数据
+    ExtractText,   // 提取上传文件的正文用于全文搜索
+}
+
+impl JobKind {
+    /// 从字符串解析任务类型
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s {
+            "fetch_metadata" => Ok(JobKind::FetchMetadata),
+            "extract_text" => Ok(JobKind::ExtractText),
+            _ => Err(format!("Unknown job kind: {}", s)),
+        }
+    }
+
+    /// 转换为数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::FetchMetadata => "fetch_metadata",
+            JobKind::ExtractText => "extract_text",
+        }
+    }
+}
+
+/// 后台任务状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending, // 等待执行 (包括等待重试)
+    Running, // 已被 worker 领取
+    Failed,  // 超过最大尝试次数
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 后台任务
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackgroundJob {
+    pub id: i64,
+    pub kind: String,
+    pub user_id: i64,
+    pub resource_id: Option<i64>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub run_after: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
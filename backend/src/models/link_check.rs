@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 链接健康状态, 用于资源列表过滤
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStatus {
+    Ok,         // 最近一次检查成功, 未发生重定向
+    Redirected, // 最近一次检查成功, 但重定向到了其他地址
+    Broken,     // 最近一次检查失败 (网络错误或 4xx/5xx)
+    Unchecked,  // 尚未检查
+}
+
+impl LinkStatus {
+    /// 从字符串解析链接状态
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "ok" => Ok(LinkStatus::Ok),
+            "redirected" => Ok(LinkStatus::Redirected),
+            "broken" => Ok(LinkStatus::Broken),
+            "unchecked" => Ok(LinkStatus::Unchecked),
+            _ => Err(format!("Invalid link status: {}", s)),
+        }
+    }
+}
+
+/// 资源的链接检查结果
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LinkCheck {
+    pub resource_id: i64,
+    /// ok / redirected / broken / unchecked
+    pub status: String,
+    pub status_code: Option<i64>,
+    pub final_url: Option<String>,
+    pub error: Option<String>,
+    pub consecutive_failures: i64,
+    pub last_checked_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+}
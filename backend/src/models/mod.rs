@@ -1,15 +1,43 @@
+pub mod access_token;
+pub mod archive;
 pub mod collection;
 pub mod command;
+pub mod export;
+pub mod file;
+pub mod import;
+pub mod job;
+pub mod link_check;
+pub mod pagination;
+pub mod related;
 pub mod resource;
+pub mod saved_search;
 pub mod search;
+pub mod search_history;
+#[cfg(feature = "embeddings")]
+pub mod semantic;
+pub mod session;
 pub mod stats;
 pub mod tag;
 pub mod user;
 
+pub use access_token::*;
+pub use archive::*;
 pub use collection::*;
 pub use command::*;
+pub use export::*;
+pub use file::*;
+pub use import::*;
+pub use job::*;
+pub use link_check::*;
+pub use pagination::*;
+pub use related::*;
 pub use resource::*;
+pub use saved_search::*;
 pub use search::*;
+pub use search_history::*;
+#[cfg(feature = "embeddings")]
+pub use semantic::*;
+pub use session::*;
 pub use stats::*;
 pub use tag::*;
 pub use user::*;
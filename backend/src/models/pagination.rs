@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use serde::{Deserialize, Serialize};
 
 #[allow(dead_code)]
@@ -23,3 +24,79 @@ impl<T> PaginatedResponse<T> {
         }
     }
 }
+
+/// 游标分页的一页结果
+#[derive(Debug, Serialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub limit: i64,
+    /// 下一页的游标, 没有更多数据时为 None
+    pub next_cursor: Option<String>,
+    /// 上一页的游标, 第一页为 None
+    pub prev_cursor: Option<String>,
+}
+
+/// 游标中保存的排序列取值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SortValue {
+    #[serde(rename = "i")]
+    Int(i64),
+    /// 以二进制位保存, JSON 的十进制表示无法保证往返后与数据库中的值完全相等
+    #[serde(rename = "f", with = "f64_bits")]
+    Float(f64),
+    #[serde(rename = "t")]
+    Text(String),
+}
+
+mod f64_bits {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.to_bits())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        u64::deserialize(deserializer).map(f64::from_bits)
+    }
+}
+
+/// 分页游标: 排序列取值加资源 ID, 编码为 base64url 的 JSON, 对客户端不透明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// 生成游标时的排序方式, 例如 `created_at:desc`
+    #[serde(rename = "s")]
+    pub sort: String,
+    #[serde(rename = "v")]
+    pub value: SortValue,
+    #[serde(rename = "i")]
+    pub id: i64,
+    /// 为 true 时取游标之前的一页
+    #[serde(rename = "b", default)]
+    pub backward: bool,
+    /// 按相关度排序时新近度加成的参考时间, 翻页期间保持不变以免得分变化
+    #[serde(rename = "t", default, skip_serializing_if = "Option::is_none")]
+    pub ranked_at: Option<i64>,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, String> {
+        let json = URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| "Invalid cursor".to_string())?;
+        serde_json::from_slice(&json).map_err(|_| "Invalid cursor".to_string())
+    }
+
+    /// 解析请求中的游标参数, 空字符串表示以游标模式请求第一页
+    pub fn from_param(cursor: &str) -> Result<Option<Self>, String> {
+        if cursor.is_empty() {
+            Ok(None)
+        } else {
+            Self::decode(cursor).map(Some)
+        }
+    }
+}
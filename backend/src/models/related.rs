@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::ResourceWithTags;
+
+/// 相关资源查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct RelatedQuery {
+    /// 返回条数, 默认 10, 最大 50
+    pub limit: Option<i64>,
+    /// 只返回得分不低于该值的推荐 (0 ~ 1)
+    pub min_score: Option<f64>,
+}
+
+/// 推荐依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelatedSignal {
+    /// 有相同的标签
+    SharedTags,
+    /// URL 的主机名相同
+    SameDomain,
+    /// 在同一个收藏夹中
+    SameCollection,
+    /// 全文索引中有相同的词
+    TermOverlap,
+    /// 通过一个中间资源间接引用
+    ReferenceGraph,
+}
+
+/// 一条推荐依据及其贡献的得分
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedReason {
+    pub signal: RelatedSignal,
+    pub score: f64,
+    /// 可读的说明, 例如相同的标签、域名或中间资源的标题
+    pub detail: String,
+}
+
+/// 推荐的相关资源, 得分为各项依据之和
+#[derive(Debug, Serialize)]
+pub struct RelatedResource {
+    #[serde(flatten)]
+    pub resource: ResourceWithTags,
+    pub score: f64,
+    pub reasons: Vec<RelatedReason>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RelatedResourceList {
+    pub items: Vec<RelatedResource>,
+}
+
+/// 采纳推荐的请求体, 可省略
+#[derive(Debug, Deserialize, Default)]
+pub struct AcceptRelatedResource {
+    #[serde(rename = "type")]
+    pub reference_type: Option<String>, // 默认 "related"
+}
+
+/// 采纳推荐后创建的引用
+#[derive(Debug, Serialize)]
+pub struct AcceptedRelatedResource {
+    pub id: i64,
+    pub source_id: i64,
+    pub target_id: i64,
+    #[serde(rename = "type")]
+    pub reference_type: String,
+    pub score: f64,
+    pub reasons: Vec<RelatedReason>,
+}
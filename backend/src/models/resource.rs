@@ -195,6 +195,15 @@ pub struct ResourceQuery {
     // 新增: 资源类型过滤
     #[serde(rename = "type")]
     pub resource_type: Option<String>,
+
+    // 链接健康状态过滤: ok / redirected / broken / unchecked
+    pub link_status: Option<String>,
+
+    // 保存的搜索: 按其条件计算资源, 忽略其他过滤和排序参数
+    pub saved_search_id: Option<i64>,
+
+    // 游标分页: 设置后 (空字符串表示第一页) 忽略 offset, 返回 next_cursor / prev_cursor
+    pub cursor: Option<String>,
 }
 
 /// 批量操作动作
@@ -269,6 +278,7 @@ pub struct ResourceReferenceQuery {
     #[serde(rename = "type")]
     pub reference_type: Option<String>, // 过滤引用类型
     pub direction: Option<String>, // "source" | "target" | "both"
+    pub cursor: Option<String>,    // 游标分页, 设置后忽略 offset
 }
 
 /// 资源引用列表响应
@@ -278,4 +288,8 @@ pub struct ResourceReferenceList {
     pub limit: i64,
     pub offset: i64,
     pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
 }
@@ -303,7 +303,7 @@ mod resource_query_tests {
 
         let query: ResourceQuery = serde_json::from_str(json).unwrap();
         assert_eq!(query.collection_id.unwrap(), 10);
-        assert_eq!(query.is_favorite.unwrap(), true);
+        assert!(query.is_favorite.unwrap());
         assert_eq!(query.limit.unwrap(), 20);
         assert_eq!(query.resource_type.unwrap(), "link");
     }
@@ -363,6 +363,8 @@ mod resource_reference_tests {
             limit: 10,
             offset: 0,
             has_more: false,
+            next_cursor: None,
+            prev_cursor: None,
         };
 
         let json = serde_json::to_value(&list).unwrap();
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row};
+
+/// 保存的搜索, 作为智能收藏夹使用, 成员在读取时按条件计算
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// 查询语言文本, 为空时只按过滤条件筛选
+    pub query: String,
+    pub search_type: String,
+    pub collection_id: Option<i64>,
+    pub tags: Vec<String>,
+    pub resource_type: Option<String>,
+    pub is_favorite: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+    pub is_read: Option<bool>,
+    pub date_from: Option<i64>,
+    pub date_to: Option<i64>,
+    pub sort_by: String,
+    pub sort_order: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// 标签以 JSON 数组保存
+impl<'r> FromRow<'r, sqlx::sqlite::SqliteRow> for SavedSearch {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        let tags_json: String = row.try_get("tags")?;
+
+        Ok(SavedSearch {
+            id: row.try_get("id")?,
+            user_id: row.try_get("user_id")?,
+            name: row.try_get("name")?,
+            query: row.try_get("query")?,
+            search_type: row.try_get("search_type")?,
+            collection_id: row.try_get("collection_id")?,
+            tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+            resource_type: row.try_get("resource_type")?,
+            is_favorite: row.try_get("is_favorite")?,
+            is_archived: row.try_get("is_archived")?,
+            is_private: row.try_get("is_private")?,
+            is_read: row.try_get("is_read")?,
+            date_from: row.try_get("date_from")?,
+            date_to: row.try_get("date_to")?,
+            sort_by: row.try_get("sort_by")?,
+            sort_order: row.try_get("sort_order")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// 保存的搜索及当前匹配的资源数量
+#[derive(Debug, Serialize)]
+pub struct SavedSearchWithCount {
+    #[serde(flatten)]
+    pub saved_search: SavedSearch,
+    pub resource_count: i64,
+}
+
+/// 创建或更新保存的搜索, 更新时整体替换搜索条件
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveSearchRequest {
+    pub name: String,
+    pub query: Option<String>,
+    /// all / title / content / url, 默认 all
+    pub search_type: Option<String>,
+    pub collection_id: Option<i64>,
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "type")]
+    pub resource_type: Option<String>,
+    pub is_favorite: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+    pub is_read: Option<bool>,
+    pub date_from: Option<i64>,
+    pub date_to: Option<i64>,
+    /// created_at / updated_at / title / visit_count / last_visited / rank, 默认 created_at
+    pub sort_by: Option<String>,
+    /// asc / desc, 默认 desc
+    pub sort_order: Option<String>,
+}
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::ResourceWithTags;
+use super::{LinkStatus, ResourceWithTags};
+use crate::config::RankingConfig;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -13,6 +16,49 @@ pub enum SearchType {
     Url,
 }
 
+impl SearchType {
+    /// 从字符串解析搜索类型
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(SearchType::All),
+            "title" => Ok(SearchType::Title),
+            "content" => Ok(SearchType::Content),
+            "url" => Ok(SearchType::Url),
+            _ => Err(format!("Invalid search type: {}", s)),
+        }
+    }
+
+    /// 转换为字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchType::All => "all",
+            SearchType::Title => "title",
+            SearchType::Content => "content",
+            SearchType::Url => "url",
+        }
+    }
+}
+
+/// 搜索模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// 全文搜索
+    #[default]
+    Keyword,
+    /// 融合全文搜索和语义搜索的排名, 需要启用 embeddings feature
+    Hybrid,
+}
+
+impl SearchMode {
+    pub fn from(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "keyword" => Ok(SearchMode::Keyword),
+            "hybrid" => Ok(SearchMode::Hybrid),
+            _ => Err(format!("Invalid search mode: {}", s)),
+        }
+    }
+}
+
 /// 分页参数
 #[derive(Debug, Clone)]
 pub struct PaginationParams {
@@ -33,51 +79,138 @@ impl PaginationParams {
     }
 }
 
-/// 过滤条件
-#[derive(Debug, Clone, Default)]
+/// 过滤条件, 与资源列表的过滤参数一致; 查询语言中的同类条件优先
+///
+/// 序列化时省略未设置的条件, 用于记录搜索历史。
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct FilterCriteria {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub collection_id: Option<i64>,
+    /// 资源类型, 多个类型之间为 OR
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub resource_types: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// 排除带有任一标签的资源
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_private: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_read: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_status: Option<LinkStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub date_from: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub date_to: Option<i64>,
 }
 
+/// 高亮参数, 对应 FTS5 snippet() 的标记和片段长度
+#[derive(Debug, Clone)]
+pub struct HighlightOptions {
+    pub start_marker: String,
+    pub end_marker: String,
+    /// 每个片段的最大词数 (1-64)
+    pub snippet_tokens: i64,
+}
+
 /// 搜索参数（组合了查询文本、搜索类型、过滤条件和分页）
 #[derive(Debug)]
 pub struct SearchFilters {
     pub query: String,
     pub search_type: SearchType,
+    pub mode: SearchMode,
     pub filters: FilterCriteria,
     pub pagination: PaginationParams,
+    /// 为 None 时不生成高亮片段
+    pub highlight: Option<HighlightOptions>,
+    /// 标签、收藏夹和域名分面返回的最大项数, 为 None 时不统计分面
+    pub facet_limit: Option<i64>,
+    /// 游标分页, 设置后 (空字符串表示第一页) 忽略页码
+    pub cursor: Option<String>,
+    /// 相关度排序的权重和加成
+    pub ranking: RankingConfig,
+    /// 是否返回每个结果的相关度得分, 用于调试排序
+    pub include_scores: bool,
 }
 
+/// 单个资源的高亮片段: 字段名 -> 片段
+pub type FieldHighlights = HashMap<String, Vec<String>>;
+
 #[derive(Debug, Serialize)]
 pub struct SearchResponse {
     pub items: Vec<ResourceWithTags>,
     pub pagination: SearchPagination,
     pub search_time: f64,
-    pub highlights:
-        Option<std::collections::HashMap<i64, std::collections::HashMap<String, Vec<String>>>>, // resource_id -> field -> snippets
+    pub highlights: Option<HashMap<i64, FieldHighlights>>, // resource_id -> field -> snippets
+    pub facets: Option<SearchFacets>,
+    /// 没有结果时对拼写错误的纠正, 例如 "rust asnyc" -> "rust async"
+    pub did_you_mean: Option<String>,
+    pub scores: Option<HashMap<i64, RelevanceScore>>, // resource_id -> score
+}
+
+/// 单个结果的相关度得分, score = text_score * boost
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RelevanceScore {
+    pub score: f64,
+    /// 按列加权的 bm25 得分 (已取反, 越大越相关)
+    pub text_score: f64,
+    /// 收藏、访问次数和新近度的加成系数
+    pub boost: f64,
+}
+
+/// 分面计数, 基于全部匹配结果而非当前页
+#[derive(Debug, Default, Serialize)]
+pub struct SearchFacets {
+    pub types: Vec<FacetCount>,
+    pub tags: Vec<FacetCount>,
+    pub collections: Vec<CollectionFacet>,
+    pub domains: Vec<FacetCount>,
+    pub flags: FlagFacets,
+    /// 按创建月份 (YYYY-MM, UTC) 分组, 时间倒序
+    pub created_at: Vec<FacetCount>,
 }
 
-/// 带高亮的搜索结果项
-/// 保留此结构以备将来实现 FTS5 snippet() 高亮功能
-#[allow(dead_code)]
 #[derive(Debug, Serialize)]
-pub struct ResourceWithHighlights {
-    #[serde(flatten)]
-    pub resource: ResourceWithTags,
-    pub highlights: std::collections::HashMap<String, Vec<String>>, // field -> snippets
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CollectionFacet {
+    pub id: i64,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FlagFacets {
+    pub favorite: i64,
+    pub read: i64,
+    pub unread: i64,
+    pub archived: i64,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SearchPagination {
-    pub page: i64,
+    /// 游标分页时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
     pub limit: i64,
     pub total: i64,
     pub total_pages: i64,
     pub has_next: bool,
     pub has_prev: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
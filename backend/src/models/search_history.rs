@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Row};
+
+/// 一次执行过的搜索
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHistoryEntry {
+    pub id: i64,
+    pub query: String,
+    pub search_type: String,
+    /// 请求中设置了的过滤条件
+    pub filters: serde_json::Value,
+    pub result_count: i64,
+    /// 搜索耗时 (秒)
+    pub search_time: f64,
+    pub created_at: i64,
+}
+
+// 过滤条件以 JSON 对象保存
+impl<'r> FromRow<'r, sqlx::sqlite::SqliteRow> for SearchHistoryEntry {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        let filters_json: String = row.try_get("filters")?;
+
+        Ok(SearchHistoryEntry {
+            id: row.try_get("id")?,
+            query: row.try_get("query")?,
+            search_type: row.try_get("search_type")?,
+            filters: serde_json::from_str(&filters_json).unwrap_or_default(),
+            result_count: row.try_get("result_count")?,
+            search_time: row.try_get("search_time")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+/// 按查询文本 (忽略大小写) 汇总的搜索次数
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PopularSearch {
+    pub query: String,
+    pub search_count: i64,
+    pub last_searched_at: i64,
+}
+
+/// 搜索历史查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct SearchHistoryQuery {
+    /// 返回条数, 默认 20, 最大 100
+    pub limit: Option<i64>,
+    /// 热门搜索只统计最近 N 天, 默认不限
+    pub days: Option<i64>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use super::ResourceWithTags;
+
+/// 语义搜索请求参数
+#[derive(Debug, Deserialize)]
+pub struct SemanticSearchQuery {
+    pub q: String,
+    pub collection_id: Option<i64>,
+    /// 资源类型 (link/note/snippet/file)
+    #[serde(rename = "type")]
+    pub resource_type: Option<String>,
+    pub limit: Option<i64>,
+    /// 覆盖配置中的最低相似度
+    pub min_similarity: Option<f64>,
+}
+
+/// 语义搜索结果, 按相似度倒序
+#[derive(Debug, Serialize)]
+pub struct SemanticMatch {
+    #[serde(flatten)]
+    pub resource: ResourceWithTags,
+    /// 与查询的余弦相似度
+    pub similarity: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SemanticSearchResponse {
+    pub items: Vec<SemanticMatch>,
+    /// 计算向量的模型名称
+    pub model: String,
+    pub search_time: f64,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 发起请求的客户端信息, 登录和刷新时记录到会话
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// 登录会话 (设备)
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct UserSession {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: i64,
+    pub last_used_at: i64,
+    pub expires_at: i64,
+    /// 是否为当前请求所用的会话
+    pub current: bool,
+}
+
+/// 撤销全部会话的查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct RevokeSessionsQuery {
+    /// 保留当前会话, 只登出其他设备
+    #[serde(default)]
+    pub keep_current: bool,
+}
@@ -59,6 +59,14 @@ impl From<User> for UserResponse {
     }
 }
 
+/// 登录、注册和刷新返回的令牌
+#[derive(Debug, Serialize)]
+pub struct AuthTokens {
+    pub access_token: String,
+    /// 每次刷新都会轮换, 旧的刷新令牌随即失效
+    pub refresh_token: String,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct UpdateUser {
@@ -71,3 +79,22 @@ pub struct ChangePassword {
     pub current_password: String,
     pub new_password: String,
 }
+
+/// 申请重置密码
+#[derive(Debug, Deserialize)]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+/// 使用邮件中的令牌设置新密码
+#[derive(Debug, Deserialize)]
+pub struct ResetPassword {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// 使用邮件中的令牌验证邮箱
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmail {
+    pub token: String,
+}
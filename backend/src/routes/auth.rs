@@ -1,11 +1,13 @@
 use crate::state::AppState;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 
 use crate::handlers::auth::{
-    change_password, get_current_user, login, logout, refresh_token, register,
+    change_password, create_access_token, get_access_tokens, get_current_user, get_sessions, login,
+    logout, refresh_token, register, request_password_reset, resend_verification_email,
+    reset_password, revoke_access_token, revoke_all_sessions, revoke_session, verify_email,
 };
 
 pub fn auth_routes() -> Router<AppState> {
@@ -13,6 +15,15 @@ pub fn auth_routes() -> Router<AppState> {
         .route("/logout", post(logout))
         .route("/me", get(get_current_user))
         .route("/change-password", post(change_password))
+        .route("/verify-email/resend", post(resend_verification_email))
+        // 会话管理
+        .route("/sessions", get(get_sessions))
+        .route("/sessions", delete(revoke_all_sessions))
+        .route("/sessions/{:id}", delete(revoke_session))
+        // 个人访问令牌
+        .route("/tokens", get(get_access_tokens))
+        .route("/tokens", post(create_access_token))
+        .route("/tokens/{:id}", delete(revoke_access_token))
 }
 
 pub fn ano_routes() -> Router<AppState> {
@@ -20,4 +31,8 @@ pub fn ano_routes() -> Router<AppState> {
         .route("/refresh", post(refresh_token))
         .route("/register", post(register))
         .route("/login", post(login))
+        .route("/verify-email", post(verify_email))
+        // 重置密码
+        .route("/password-reset/request", post(request_password_reset))
+        .route("/password-reset/confirm", post(reset_password))
 }
@@ -2,6 +2,7 @@ pub mod auth;
 pub mod collections;
 pub mod command;
 pub mod resources;
+pub mod saved_searches;
 pub mod search;
 pub mod stats;
 pub mod tags;
@@ -10,6 +11,7 @@ pub use auth::*;
 pub use collections::*;
 pub use command::*;
 pub use resources::*;
+pub use saved_searches::*;
 pub use search::*;
 pub use stats::*;
 pub use tags::*;
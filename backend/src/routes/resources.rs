@@ -1,26 +1,46 @@
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{delete, get, post, put},
     Router,
 };
 
 use crate::handlers::resources::{
-    batch_update_resources, create_resource, create_resource_reference, delete_resource,
-    delete_resource_reference, get_resource, get_resource_references, get_resources,
-    update_resource,
+    accept_related_resource, batch_update_resources, create_resource, create_resource_reference,
+    delete_resource, delete_resource_reference, download_resource_file, export_resources,
+    get_related_resources, get_resource, get_resource_archive, get_resource_link_status,
+    get_resource_references, get_resources, import_resources, update_resource,
+    upload_resource_file, MAX_IMPORT_FILE_SIZE,
 };
 use crate::state::AppState;
 
 /// 配置资源相关的路由
-/// 包括基本的 CRUD 操作和资源引用管理
+/// 包括基本的 CRUD 操作、资源引用管理和相关资源推荐
 pub fn resource_routes() -> Router<AppState> {
     Router::new()
         // 基础资源操作
         .route("/", get(get_resources))
         .route("/", post(create_resource))
         .route("/batch", post(batch_update_resources))
+        // 导入导出
+        .route(
+            "/import",
+            post(import_resources).layer(DefaultBodyLimit::max(MAX_IMPORT_FILE_SIZE)),
+        )
+        .route("/export", get(export_resources))
+        // 文件上传, 大小上限由 storage.max_upload_bytes 在 handler 中检查
+        .route(
+            "/upload",
+            post(upload_resource_file).layer(DefaultBodyLimit::disable()),
+        )
         .route("/{:id}", get(get_resource))
         .route("/{:id}", put(update_resource))
         .route("/{:id}", delete(delete_resource))
+        // 链接健康状态
+        .route("/{:id}/link-status", get(get_resource_link_status))
+        // 离线存档
+        .route("/{:id}/archive", get(get_resource_archive))
+        // 上传文件下载
+        .route("/{:id}/file", get(download_resource_file))
         // 资源引用管理
         .route("/{:id}/references", post(create_resource_reference))
         .route("/{:id}/references", get(get_resource_references))
@@ -28,4 +48,7 @@ pub fn resource_routes() -> Router<AppState> {
             "/{:id}/references/{:target_id}",
             delete(delete_resource_reference),
         )
+        // 相关资源推荐
+        .route("/{:id}/related", get(get_related_resources))
+        .route("/{:id}/related/{:target_id}", post(accept_related_resource))
 }
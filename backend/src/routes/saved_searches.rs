@@ -0,0 +1,19 @@
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+
+use crate::handlers::saved_searches::{
+    create_saved_search, delete_saved_search, get_saved_search, get_saved_searches,
+    update_saved_search,
+};
+use crate::state::AppState;
+
+pub fn saved_search_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_saved_searches))
+        .route("/", post(create_saved_search))
+        .route("/{:id}", get(get_saved_search))
+        .route("/{:id}", put(update_saved_search))
+        .route("/{:id}", delete(delete_saved_search))
+}
@@ -1,10 +1,31 @@
 use axum::{routing::get, Router};
 
-use crate::handlers::search::{get_search_suggestions, search_resources};
+#[cfg(feature = "embeddings")]
+use crate::handlers::search::semantic_search;
+use crate::handlers::search::{
+    clear_search_history, get_popular_searches, get_search_history, get_search_suggestions,
+    search_resources,
+};
 use crate::state::AppState;
 
 pub fn search_routes() -> Router<AppState> {
     Router::new()
         .route("/resources", get(search_resources))
         .route("/suggestions", get(get_search_suggestions))
+        .route(
+            "/history",
+            get(get_search_history).delete(clear_search_history),
+        )
+        .route("/history/popular", get(get_popular_searches))
+        .merge(semantic_routes())
+}
+
+#[cfg(feature = "embeddings")]
+fn semantic_routes() -> Router<AppState> {
+    Router::new().route("/semantic", get(semantic_search))
+}
+
+#[cfg(not(feature = "embeddings"))]
+fn semantic_routes() -> Router<AppState> {
+    Router::new()
 }
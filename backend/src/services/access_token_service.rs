@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+
+use axum::http::Method;
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+
+use crate::models::{
+    CollectionScopedRequest, CreatePersonalAccessToken, CreatedPersonalAccessToken,
+    PersonalAccessToken, TokenAccess, TokenScope,
+};
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::token::{generate_token, hash_token};
+
+/// 个人访问令牌的前缀, 用于和 JWT 区分
+pub const ACCESS_TOKEN_PREFIX: &str = "pat_";
+/// 列表中展示的令牌开头字符数 (包括前缀)
+const TOKEN_PREFIX_DISPLAY_LENGTH: usize = 12;
+/// 令牌名称的最大长度
+const MAX_TOKEN_NAME_LENGTH: usize = 100;
+/// 有效天数上限
+const MAX_EXPIRES_IN_DAYS: i64 = 3650;
+/// 令牌的使用记录的最短间隔 (秒)
+const TOKEN_TOUCH_INTERVAL_SECS: i64 = 60;
+
+pub struct AccessTokenService;
+
+impl AccessTokenService {
+    /// 创建个人访问令牌, 返回的完整令牌只有这一次可以获取
+    pub async fn create_token(
+        user_id: i64,
+        data: CreatePersonalAccessToken,
+        db_pool: &SqlitePool,
+    ) -> AppResult<CreatedPersonalAccessToken> {
+        let name = data.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Token name must be 1-{} characters",
+                MAX_TOKEN_NAME_LENGTH
+            )));
+        }
+
+        let expires_at = match data.expires_in_days {
+            Some(days) if !(1..=MAX_EXPIRES_IN_DAYS).contains(&days) => {
+                return Err(AppError::BadRequest(format!(
+                    "expires_in_days must be between 1 and {}",
+                    MAX_EXPIRES_IN_DAYS
+                )));
+            }
+            Some(days) => Some(Utc::now().timestamp() + days * 24 * 60 * 60),
+            None => None,
+        };
+
+        let collection_ids = match data.collection_ids {
+            Some(mut ids) => {
+                ids.sort_unstable();
+                ids.dedup();
+                if ids.is_empty() {
+                    return Err(AppError::BadRequest(
+                        "collection_ids must not be empty".to_string(),
+                    ));
+                }
+                Some(ids)
+            }
+            None => None,
+        };
+
+        let mut tx = db_pool.begin().await?;
+
+        for collection_id in collection_ids.iter().flatten() {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM collections WHERE id = $1 AND user_id = $2)",
+            )
+            .bind(collection_id)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if !exists {
+                return Err(AppError::NotFound(format!(
+                    "Collection {} not found",
+                    collection_id
+                )));
+            }
+        }
+
+        let secret = format!("{}{}", ACCESS_TOKEN_PREFIX, generate_token());
+        let token_prefix: String = secret.chars().take(TOKEN_PREFIX_DISPLAY_LENGTH).collect();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO personal_access_tokens
+                (user_id, name, token_hash, token_prefix, scope, collection_limited, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(hash_token(&secret))
+        .bind(&token_prefix)
+        .bind(data.scope.as_str())
+        .bind(collection_ids.is_some())
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await?;
+        let token_id: i64 = row.get("id");
+
+        for collection_id in collection_ids.iter().flatten() {
+            sqlx::query(
+                "INSERT INTO personal_access_token_collections (token_id, collection_id) VALUES ($1, $2)",
+            )
+            .bind(token_id)
+            .bind(collection_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(CreatedPersonalAccessToken {
+            token: PersonalAccessToken {
+                id: token_id,
+                name,
+                token_prefix,
+                scope: data.scope,
+                collection_ids,
+                expires_at,
+                last_used_at: None,
+                last_used_ip: None,
+                created_at: row.get("created_at"),
+            },
+            secret,
+        })
+    }
+
+    /// 列出未撤销的令牌 (包括已过期的), 按创建时间倒序
+    pub async fn list_tokens(
+        user_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<PersonalAccessToken>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, token_prefix, scope, collection_limited, expires_at,
+                   last_used_at, last_used_ip, created_at
+            FROM personal_access_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        let links: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT c.token_id, c.collection_id
+            FROM personal_access_token_collections c
+            JOIN personal_access_tokens t ON t.id = c.token_id
+            WHERE t.user_id = $1 AND t.revoked_at IS NULL
+            ORDER BY c.collection_id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+        let mut collections: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (token_id, collection_id) in links {
+            collections.entry(token_id).or_default().push(collection_id);
+        }
+
+        rows.into_iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                let scope: String = row.get("scope");
+                let collection_limited: bool = row.get("collection_limited");
+                Ok(PersonalAccessToken {
+                    id,
+                    name: row.get("name"),
+                    token_prefix: row.get("token_prefix"),
+                    scope: TokenScope::from(&scope).map_err(AppError::Internal)?,
+                    collection_ids: collection_limited
+                        .then(|| collections.remove(&id).unwrap_or_default()),
+                    expires_at: row.get("expires_at"),
+                    last_used_at: row.get("last_used_at"),
+                    last_used_ip: row.get("last_used_ip"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect()
+    }
+
+    /// 撤销令牌, 返回是否有令牌被撤销
+    pub async fn revoke_token(
+        user_id: i64,
+        token_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE personal_access_tokens SET revoked_at = $1
+            WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(Utc::now().timestamp())
+        .bind(token_id)
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 撤销用户的全部令牌, 返回撤销的数量
+    pub async fn revoke_all_tokens(user_id: i64, db_pool: &SqlitePool) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE personal_access_tokens SET revoked_at = $1
+            WHERE user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(Utc::now().timestamp())
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 验证令牌, 返回用户 ID 和令牌的权限, 并记录最近使用时间和地址
+    pub async fn authenticate(
+        token: &str,
+        ip_address: Option<&str>,
+        db_pool: &SqlitePool,
+    ) -> AppResult<(i64, TokenAccess)> {
+        let now = Utc::now().timestamp();
+        // 按摘要查找, 数据库中没有可供逐字节比较的明文
+        let row = sqlx::query(
+            r#"
+            SELECT t.id, t.user_id, t.scope, t.collection_limited
+            FROM personal_access_tokens t
+            JOIN users u ON u.id = t.user_id
+            WHERE t.token_hash = $1 AND t.revoked_at IS NULL
+              AND (t.expires_at IS NULL OR t.expires_at > $2)
+              AND u.is_active = TRUE
+            "#,
+        )
+        .bind(hash_token(token))
+        .bind(now)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::Unauthorized("Invalid, expired or revoked access token".to_string())
+        })?;
+
+        let token_id: i64 = row.get("id");
+        let user_id: i64 = row.get("user_id");
+        let scope: String = row.get("scope");
+        let collection_limited: bool = row.get("collection_limited");
+
+        let collection_ids = if collection_limited {
+            Some(
+                sqlx::query_scalar(
+                    "SELECT collection_id FROM personal_access_token_collections WHERE token_id = $1",
+                )
+                .bind(token_id)
+                .fetch_all(db_pool)
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE personal_access_tokens SET last_used_at = $1, last_used_ip = $2
+            WHERE id = $3 AND (last_used_at IS NULL OR last_used_at < $4)
+            "#,
+        )
+        .bind(now)
+        .bind(ip_address)
+        .bind(token_id)
+        .bind(now - TOKEN_TOUCH_INTERVAL_SECS)
+        .execute(db_pool)
+        .await?;
+
+        Ok((
+            user_id,
+            TokenAccess {
+                scope: TokenScope::from(&scope).map_err(AppError::Internal)?,
+                collection_ids,
+            },
+        ))
+    }
+
+    /// 按权限范围检查请求: read 只能使用 GET, 账户管理接口 (/api/auth) 需要 admin
+    ///
+    /// 任何令牌都可以通过 GET /api/auth/me 获取所属用户。
+    pub fn check_scope(access: &TokenAccess, method: &Method, path: &str) -> AppResult<()> {
+        let is_read = matches!(*method, Method::GET | Method::HEAD);
+        let required = if path.starts_with("/api/auth/") {
+            if is_read && path == "/api/auth/me" {
+                TokenScope::Read
+            } else {
+                TokenScope::Admin
+            }
+        } else if is_read {
+            TokenScope::Read
+        } else {
+            TokenScope::Write
+        };
+
+        if access.scope < required {
+            return Err(AppError::Forbidden(format!(
+                "This access token requires the {} scope",
+                required.as_str()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// 检查限定收藏夹的令牌能否发起该请求, 不限收藏夹的令牌总是允许
+    pub async fn check_collection_access(
+        user_id: i64,
+        access: &TokenAccess,
+        request: CollectionScopedRequest,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let Some(allowed) = &access.collection_ids else {
+            return Ok(());
+        };
+        let denied = || {
+            AppError::Forbidden("This access token is limited to specific collections".to_string())
+        };
+        let is_allowed = |collection_id: Option<i64>| {
+            collection_id.is_some_and(|collection_id| allowed.contains(&collection_id))
+        };
+
+        let permitted = match request {
+            CollectionScopedRequest::Collection(collection_id) => is_allowed(Some(collection_id)),
+            CollectionScopedRequest::ListResources { collection_id }
+            | CollectionScopedRequest::CreateResource { collection_id } => {
+                is_allowed(collection_id)
+            }
+            CollectionScopedRequest::Resource {
+                id,
+                move_to,
+                clear_collection,
+            } => {
+                let collection_id: Option<Option<i64>> = sqlx::query_scalar(
+                    "SELECT collection_id FROM resources WHERE id = $1 AND user_id = $2",
+                )
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(db_pool)
+                .await?;
+                // 资源不存在时交给 handler 返回 404
+                let in_scope = collection_id.is_none_or(is_allowed);
+                in_scope && !clear_collection && move_to.is_none_or(|id| is_allowed(Some(id)))
+            }
+            CollectionScopedRequest::Other => false,
+        };
+
+        if permitted {
+            Ok(())
+        } else {
+            Err(denied())
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use axum::http::Method;
+use sqlx::SqlitePool;
+
+use crate::models::{
+    CollectionScopedRequest, CreateCollection, CreatePersonalAccessToken, TokenAccess, TokenScope,
+};
+use crate::services::access_token_service::{AccessTokenService, ACCESS_TOKEN_PREFIX};
+use crate::services::collection_service::CollectionService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+async fn create_collection(pool: &SqlitePool, user_id: i64, name: &str) -> i64 {
+    CollectionService::create_collection(
+        user_id,
+        CreateCollection {
+            name: name.to_string(),
+            description: None,
+            color: None,
+            icon: None,
+            parent_id: None,
+        },
+        pool,
+    )
+    .await
+    .unwrap()
+    .id
+}
+
+async fn create_resource(pool: &SqlitePool, user_id: i64, collection_id: Option<i64>) -> i64 {
+    sqlx::query_scalar(
+        "INSERT INTO resources (user_id, collection_id, title) VALUES ($1, $2, 'Note') RETURNING id",
+    )
+    .bind(user_id)
+    .bind(collection_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+fn token_data(scope: TokenScope) -> CreatePersonalAccessToken {
+    CreatePersonalAccessToken {
+        name: "Browser extension".to_string(),
+        scope,
+        collection_ids: None,
+        expires_in_days: None,
+    }
+}
+
+#[tokio::test]
+async fn test_create_authenticate_and_revoke_token() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "tokens").await;
+
+    let created = AccessTokenService::create_token(user_id, token_data(TokenScope::Write), &pool)
+        .await
+        .unwrap();
+    assert!(created.secret.starts_with(ACCESS_TOKEN_PREFIX));
+    assert!(created.secret.starts_with(&created.token.token_prefix));
+
+    // 数据库中只保存摘要
+    let stored: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM personal_access_tokens WHERE token_hash = $1")
+            .bind(&created.secret)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(stored, 0);
+
+    let (authenticated_user, access) =
+        AccessTokenService::authenticate(&created.secret, Some("192.0.2.1"), &pool)
+            .await
+            .unwrap();
+    assert_eq!(authenticated_user, user_id);
+    assert_eq!(access.scope, TokenScope::Write);
+    assert!(access.collection_ids.is_none());
+
+    let tokens = AccessTokenService::list_tokens(user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].id, created.token.id);
+    assert!(tokens[0].last_used_at.is_some());
+    assert_eq!(tokens[0].last_used_ip.as_deref(), Some("192.0.2.1"));
+
+    // 其他用户不能撤销
+    let other_user = create_test_user(&pool, "other").await;
+    assert!(
+        !AccessTokenService::revoke_token(other_user, created.token.id, &pool)
+            .await
+            .unwrap()
+    );
+    assert!(
+        AccessTokenService::revoke_token(user_id, created.token.id, &pool)
+            .await
+            .unwrap()
+    );
+    assert!(AccessTokenService::list_tokens(user_id, &pool)
+        .await
+        .unwrap()
+        .is_empty());
+    let result = AccessTokenService::authenticate(&created.secret, None, &pool).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+    // 过期的令牌不能使用
+    let created = AccessTokenService::create_token(
+        user_id,
+        CreatePersonalAccessToken {
+            expires_in_days: Some(30),
+            ..token_data(TokenScope::Read)
+        },
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(created.token.expires_at.is_some());
+    sqlx::query("UPDATE personal_access_tokens SET expires_at = expires_at - 31 * 86400")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let result = AccessTokenService::authenticate(&created.secret, None, &pool).await;
+    assert!(matches!(result, Err(AppError::Unauthorized(_))));
+}
+
+#[tokio::test]
+async fn test_create_token_validation() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "tokens").await;
+    let other_user = create_test_user(&pool, "other").await;
+    let other_collection = create_collection(&pool, other_user, "Private").await;
+
+    let cases = [
+        CreatePersonalAccessToken {
+            name: "  ".to_string(),
+            ..token_data(TokenScope::Read)
+        },
+        CreatePersonalAccessToken {
+            expires_in_days: Some(0),
+            ..token_data(TokenScope::Read)
+        },
+        CreatePersonalAccessToken {
+            collection_ids: Some(Vec::new()),
+            ..token_data(TokenScope::Read)
+        },
+    ];
+    for data in cases {
+        let result = AccessTokenService::create_token(user_id, data, &pool).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    let result = AccessTokenService::create_token(
+        user_id,
+        CreatePersonalAccessToken {
+            collection_ids: Some(vec![other_collection]),
+            ..token_data(TokenScope::Read)
+        },
+        &pool,
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+}
+
+#[test]
+fn test_check_scope() {
+    let access = |scope| TokenAccess {
+        scope,
+        collection_ids: None,
+    };
+    let allowed = |scope, method: Method, path: &str| {
+        AccessTokenService::check_scope(&access(scope), &method, path).is_ok()
+    };
+
+    assert!(allowed(TokenScope::Read, Method::GET, "/api/resources"));
+    assert!(allowed(TokenScope::Read, Method::GET, "/api/auth/me"));
+    assert!(!allowed(TokenScope::Read, Method::POST, "/api/resources"));
+    assert!(!allowed(TokenScope::Read, Method::GET, "/api/auth/tokens"));
+
+    assert!(allowed(
+        TokenScope::Write,
+        Method::DELETE,
+        "/api/resources/1"
+    ));
+    assert!(!allowed(
+        TokenScope::Write,
+        Method::POST,
+        "/api/auth/tokens"
+    ));
+    assert!(!allowed(
+        TokenScope::Write,
+        Method::GET,
+        "/api/auth/sessions"
+    ));
+
+    assert!(allowed(TokenScope::Admin, Method::POST, "/api/auth/tokens"));
+    assert!(allowed(
+        TokenScope::Admin,
+        Method::POST,
+        "/api/auth/change-password"
+    ));
+}
+
+#[tokio::test]
+async fn test_collection_limited_token() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "tokens").await;
+    let allowed_id = create_collection(&pool, user_id, "Inbox").await;
+    let other_id = create_collection(&pool, user_id, "Private").await;
+    let inside = create_resource(&pool, user_id, Some(allowed_id)).await;
+    let outside = create_resource(&pool, user_id, Some(other_id)).await;
+    let uncollected = create_resource(&pool, user_id, None).await;
+
+    let created = AccessTokenService::create_token(
+        user_id,
+        CreatePersonalAccessToken {
+            collection_ids: Some(vec![allowed_id, allowed_id]),
+            ..token_data(TokenScope::Write)
+        },
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(created.token.collection_ids, Some(vec![allowed_id]));
+    let (_, access) = AccessTokenService::authenticate(&created.secret, None, &pool)
+        .await
+        .unwrap();
+
+    let resource = |id, move_to, clear_collection| CollectionScopedRequest::Resource {
+        id,
+        move_to,
+        clear_collection,
+    };
+    let cases = [
+        (CollectionScopedRequest::Collection(allowed_id), true),
+        (CollectionScopedRequest::Collection(other_id), false),
+        (
+            CollectionScopedRequest::ListResources {
+                collection_id: Some(allowed_id),
+            },
+            true,
+        ),
+        (
+            CollectionScopedRequest::ListResources {
+                collection_id: None,
+            },
+            false,
+        ),
+        (
+            CollectionScopedRequest::CreateResource {
+                collection_id: Some(allowed_id),
+            },
+            true,
+        ),
+        (
+            CollectionScopedRequest::CreateResource {
+                collection_id: None,
+            },
+            false,
+        ),
+        (resource(inside, None, false), true),
+        (resource(inside, Some(other_id), false), false),
+        (resource(inside, None, true), false),
+        (resource(outside, None, false), false),
+        (resource(uncollected, None, false), false),
+        (CollectionScopedRequest::Other, false),
+    ];
+    for (request, expected) in cases {
+        let result =
+            AccessTokenService::check_collection_access(user_id, &access, request.clone(), &pool)
+                .await;
+        assert_eq!(result.is_ok(), expected, "{:?}", request);
+        if !expected {
+            assert!(matches!(result, Err(AppError::Forbidden(_))));
+        }
+    }
+
+    // 收藏夹删除后令牌不能访问任何资源, 而不是变为不受限
+    CollectionService::delete_collection(user_id, allowed_id, &pool)
+        .await
+        .unwrap();
+    let tokens = AccessTokenService::list_tokens(user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(tokens[0].collection_ids, Some(Vec::new()));
+    let (_, access) = AccessTokenService::authenticate(&created.secret, None, &pool)
+        .await
+        .unwrap();
+    let result = AccessTokenService::check_collection_access(
+        user_id,
+        &access,
+        resource(inside, None, false),
+        &pool,
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::Forbidden(_))));
+}
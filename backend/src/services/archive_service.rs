@@ -0,0 +1,191 @@
+//! 网页离线存档服务
+//!
+//! 由元数据抓取任务调用: 将抓取到的页面转换为可读快照 (清理后的 HTML 和纯文本)。
+//! HTML 按配置保存在数据库或存储目录中, 纯文本保存在 resource_archives 表,
+//! 并由 IndexerService 写入 resources_fts 的 extracted_text 列。
+use std::path::Path;
+
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use crate::config::ArchiveConfig;
+use crate::models::ResourceArchive;
+use crate::services::IndexerService;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::http_client::FetchedPage;
+use crate::utils::readability::make_readable;
+
+pub struct ArchiveService;
+
+impl ArchiveService {
+    /// 获取资源的离线存档, 资源不存在或尚未存档时返回 None
+    pub async fn get_archive(
+        user_id: i64,
+        resource_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<ResourceArchive>> {
+        let archive = sqlx::query_as::<_, ResourceArchive>(
+            r#"
+            SELECT a.resource_id, a.url, a.title, a.text, a.size, a.archived_at,
+                   a.html, a.storage_path
+            FROM resource_archives a
+            JOIN resources r ON r.id = a.resource_id
+            WHERE a.resource_id = $1 AND r.user_id = $2
+            "#,
+        )
+        .bind(resource_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(archive)
+    }
+
+    /// 读取存档的 HTML
+    pub async fn load_html(archive: &ResourceArchive) -> AppResult<String> {
+        if let Some(html) = &archive.html {
+            return Ok(html.clone());
+        }
+
+        let path = archive
+            .storage_path
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("Archive has no HTML content".to_string()))?;
+        tokio::fs::read_to_string(path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AppError::NotFound("Archive file not found".to_string())
+            } else {
+                AppError::Internal(format!("Failed to read archive file: {}", e))
+            }
+        })
+    }
+
+    /// 保存页面快照并重建索引
+    ///
+    /// 以抓取时的 URL 作为条件, 资源已删除或 URL 已修改时放弃本次快照, 返回 false
+    pub async fn store_page(
+        resource_id: i64,
+        user_id: i64,
+        url: &str,
+        page: &FetchedPage,
+        config: &ArchiveConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<bool> {
+        let readable = make_readable(&page.body, &page.final_url);
+        let archived_at = chrono::Utc::now();
+
+        // 每次存档写入新文件, 数据库更新成功后再删除旧文件, 避免存档记录指向不完整的文件
+        let storage_path = match &config.storage_dir {
+            Some(dir) => {
+                let path = Path::new(dir).join(user_id.to_string()).join(format!(
+                    "{}-{}.html",
+                    resource_id,
+                    archived_at.timestamp_millis()
+                ));
+                write_file(&path, &readable.html).await?;
+                Some(path.to_string_lossy().into_owned())
+            }
+            None => None,
+        };
+
+        let previous_path: Option<String> =
+            sqlx::query_scalar("SELECT storage_path FROM resource_archives WHERE resource_id = $1")
+                .bind(resource_id)
+                .fetch_optional(db_pool)
+                .await?
+                .flatten();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO resource_archives (
+                resource_id, url, title, html, storage_path, text, size, archived_at
+            )
+            SELECT id, $1, $2, $3, $4, $5, $6, $7
+            FROM resources
+            WHERE id = $8 AND user_id = $9 AND url = $10
+            ON CONFLICT(resource_id) DO UPDATE SET
+                url = excluded.url,
+                title = excluded.title,
+                html = excluded.html,
+                storage_path = excluded.storage_path,
+                text = excluded.text,
+                size = excluded.size,
+                archived_at = excluded.archived_at
+            "#,
+        )
+        .bind(page.final_url.as_str())
+        .bind(readable.title.as_ref())
+        .bind(storage_path.is_none().then_some(readable.html.as_str()))
+        .bind(storage_path.as_ref())
+        .bind(&readable.text)
+        .bind(readable.html.len() as i64)
+        .bind(archived_at.timestamp())
+        .bind(resource_id)
+        .bind(user_id)
+        .bind(url)
+        .execute(db_pool)
+        .await;
+
+        let stored = match result {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                Self::remove_files(storage_path.as_slice()).await;
+                return Err(e.into());
+            }
+        };
+
+        if !stored {
+            Self::remove_files(storage_path.as_slice()).await;
+            return Ok(false);
+        }
+
+        if previous_path.is_some() && previous_path != storage_path {
+            Self::remove_files(previous_path.as_slice()).await;
+        }
+
+        IndexerService::index_resource_with_pool(db_pool, resource_id, user_id).await?;
+
+        Ok(true)
+    }
+
+    /// 查询资源存档文件的路径, 用于删除资源后清理文件
+    pub async fn storage_paths(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        resource_id: i64,
+    ) -> AppResult<Vec<String>> {
+        let paths = sqlx::query_scalar(
+            r#"
+            SELECT storage_path FROM resource_archives
+            WHERE resource_id = $1 AND storage_path IS NOT NULL
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(paths)
+    }
+
+    /// 删除存档文件, 失败时只记录日志
+    pub async fn remove_files(paths: &[String]) {
+        for path in paths {
+            if let Err(e) = tokio::fs::remove_file(path).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("删除存档文件 {} 失败: {}", path, e);
+                }
+            }
+        }
+    }
+}
+
+async fn write_file(path: &Path, content: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| {
+            AppError::Internal(format!("Failed to create archive directory: {}", e))
+        })?;
+    }
+
+    tokio::fs::write(path, content)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write archive file: {}", e)))
+}
@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use axum::{http::header, response::Html, routing::get, Router};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::config::ArchiveConfig;
+use crate::models::{CreateResource, ResourceQuery, UpdateResource};
+use crate::services::archive_service::ArchiveService;
+use crate::services::resource_service::ResourceService;
+use crate::services::test_support::{
+    create_test_pool, create_test_user, default_storage, spawn_stub_server, test_worker,
+};
+
+const ARTICLE_HTML: &str = r#"<!DOCTYPE html>
+<html><head>
+<title>Stub Article</title>
+<script>window.tracker = true;</script>
+</head><body>
+<nav>Site navigation</nav>
+<article>
+<h1>Offline reading</h1>
+<p>The zephyrology chapter <a href="/next" onclick="track()">continues</a>.</p>
+</article>
+</body></html>"#;
+
+const UPDATED_HTML: &str =
+    "<html><body><main><p>Updated borealology notes</p></main></body></html>";
+
+/// 模拟网站的路由
+fn stub_site() -> Router {
+    Router::new()
+        .route("/article", get(|| async { Html(ARTICLE_HTML) }))
+        .route("/updated", get(|| async { Html(UPDATED_HTML) }))
+        .route(
+            "/image.png",
+            get(|| async { ([(header::CONTENT_TYPE, "image/png")], vec![0u8; 16]) }),
+        )
+}
+
+async fn create_link(pool: &SqlitePool, user_id: i64, url: &str) -> i64 {
+    let resource_data = CreateResource {
+        title: "Archived link".to_string(),
+        url: Some(url.to_string()),
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "link".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    };
+
+    ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn search_ids(pool: &SqlitePool, user_id: i64, term: &str) -> Vec<i64> {
+    let query = ResourceQuery {
+        search: Some(term.to_string()),
+        ..Default::default()
+    };
+
+    ResourceService::get_resources(user_id, query, pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|resource| resource.resource.id)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_archive_stored_in_database_and_indexed() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "archiver").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(
+        &pool,
+        1,
+        ArchiveConfig {
+            enabled: true,
+            storage_dir: None,
+        },
+        default_storage(),
+    );
+
+    let resource_id = create_link(&pool, user_id, &format!("{}/article", base)).await;
+    assert!(search_ids(&pool, user_id, "zephyrology").await.is_empty());
+    assert!(worker.process_next().await.unwrap());
+
+    let archive = ArchiveService::get_archive(user_id, resource_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(archive.url, format!("{}/article", base));
+    assert_eq!(archive.title.as_deref(), Some("Stub Article"));
+    assert_eq!(
+        archive.text,
+        "Offline reading\nThe zephyrology chapter continues."
+    );
+    assert!(archive.storage_path.is_none());
+
+    let html = ArchiveService::load_html(&archive).await.unwrap();
+    assert_eq!(archive.size, html.len() as i64);
+    assert!(html.contains(r#"<a href="/next">continues</a>"#));
+    for removed in ["<script", "tracker", "onclick", "Site navigation"] {
+        assert!(!html.contains(removed), "{} should be removed", removed);
+    }
+
+    // 存档正文可被全文搜索
+    assert_eq!(
+        search_ids(&pool, user_id, "zephyrology").await,
+        vec![resource_id]
+    );
+
+    // 其他用户无法读取存档
+    assert!(ArchiveService::get_archive(user_id + 1, resource_id, &pool)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_archive_stored_in_directory() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "archiver").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let storage_dir = tempfile::tempdir().unwrap();
+    let worker = test_worker(
+        &pool,
+        1,
+        ArchiveConfig {
+            enabled: true,
+            storage_dir: Some(storage_dir.path().to_string_lossy().into_owned()),
+        },
+        default_storage(),
+    );
+
+    let resource_id = create_link(&pool, user_id, &format!("{}/article", base)).await;
+    assert!(worker.process_next().await.unwrap());
+
+    let archive = ArchiveService::get_archive(user_id, resource_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(archive.html.is_none());
+    let first_path = archive.storage_path.clone().unwrap();
+    assert!(first_path.starts_with(storage_dir.path().to_str().unwrap()));
+    assert!(ArchiveService::load_html(&archive)
+        .await
+        .unwrap()
+        .contains("zephyrology"));
+
+    // URL 变更后重新抓取, 新快照替换旧文件和旧索引
+    let update: UpdateResource =
+        serde_json::from_value(json!({ "url": format!("{}/updated", base) })).unwrap();
+    ResourceService::update_resource(user_id, resource_id, update, &pool)
+        .await
+        .unwrap();
+    assert!(worker.process_next().await.unwrap());
+
+    let archive = ArchiveService::get_archive(user_id, resource_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    let second_path = archive.storage_path.clone().unwrap();
+    assert_ne!(first_path, second_path);
+    assert!(!Path::new(&first_path).exists());
+    assert_eq!(archive.text, "Updated borealology notes");
+    assert!(search_ids(&pool, user_id, "zephyrology").await.is_empty());
+    assert_eq!(
+        search_ids(&pool, user_id, "borealology").await,
+        vec![resource_id]
+    );
+
+    // 删除资源时一并删除存档文件
+    assert!(
+        ResourceService::delete_resource(user_id, resource_id, &pool)
+            .await
+            .unwrap()
+    );
+    assert!(!Path::new(&second_path).exists());
+}
+
+#[tokio::test]
+async fn test_archive_skipped_when_disabled_or_not_html() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "archiver").await;
+    let base = spawn_stub_server(stub_site()).await;
+
+    let disabled = test_worker(&pool, 1, ArchiveConfig::default(), default_storage());
+    let article_id = create_link(&pool, user_id, &format!("{}/article", base)).await;
+    assert!(disabled.process_next().await.unwrap());
+    assert!(ArchiveService::get_archive(user_id, article_id, &pool)
+        .await
+        .unwrap()
+        .is_none());
+
+    let enabled = test_worker(
+        &pool,
+        1,
+        ArchiveConfig {
+            enabled: true,
+            storage_dir: None,
+        },
+        default_storage(),
+    );
+    let image_id = create_link(&pool, user_id, &format!("{}/image.png", base)).await;
+    assert!(enabled.process_next().await.unwrap());
+    assert!(ArchiveService::get_archive(user_id, image_id, &pool)
+        .await
+        .unwrap()
+        .is_none());
+}
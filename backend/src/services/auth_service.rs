@@ -1,9 +1,16 @@
 use bcrypt::{hash, verify, DEFAULT_COST};
-use sqlx::SqlitePool;
-
-use crate::models::{CreateUser, LoginUser, User};
+use chrono::Utc;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::AuthConfig;
+use crate::models::{AuthTokens, ClientInfo, CreateUser, LoginUser, User, UserSession};
+use crate::services::mailer::{Email, Mailer};
+use crate::services::AccessTokenService;
 use crate::utils::error::{AppError, AppResult};
-use crate::utils::jwt::JWTService;
+use crate::utils::jwt::{JWTService, JwtClaims, TokenType};
+use crate::utils::token::{constant_time_eq, generate_token, hash_token};
 use crate::utils::validation::{validate_email, validate_password, validate_username};
 
 pub struct AuthService {
@@ -11,12 +18,20 @@ pub struct AuthService {
 }
 
 impl AuthService {
+    #[allow(dead_code)]
     pub fn new(jwt_secret: String) -> Self {
         Self {
             jwt_service: JWTService::new(jwt_secret),
         }
     }
 
+    /// 使用配置中的 JWT 密钥和令牌有效期
+    pub fn from_config(config: &AuthConfig) -> Self {
+        Self {
+            jwt_service: JWTService::from_config(config),
+        }
+    }
+
     pub async fn register(&self, user_data: CreateUser, db_pool: &SqlitePool) -> AppResult<User> {
         // Validate input
         validate_username(&user_data.username).map_err(AppError::BadRequest)?;
@@ -104,16 +119,291 @@ impl AuthService {
         Ok(user)
     }
 
-    pub fn generate_access_token(&self, user_id: i64) -> AppResult<String> {
-        self.jwt_service.generate_access_token(user_id)
+    pub fn generate_access_token(&self, user_id: i64, session_id: &str) -> AppResult<String> {
+        self.jwt_service.generate_access_token(user_id, session_id)
+    }
+
+    pub fn verify_token(&self, token: &str, expected: TokenType) -> AppResult<JwtClaims> {
+        self.jwt_service.verify_token(token, expected)
+    }
+
+    /// 登录或注册成功后创建新会话 (刷新令牌家族), 返回访问令牌和刷新令牌
+    pub async fn create_session(
+        &self,
+        user_id: i64,
+        client: &ClientInfo,
+        db_pool: &SqlitePool,
+    ) -> AppResult<AuthTokens> {
+        // 顺便清理该用户已过期的会话和刷新令牌
+        let now = Utc::now().timestamp();
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1 AND expires_at < $2")
+            .bind(user_id)
+            .bind(now)
+            .execute(db_pool)
+            .await?;
+        sqlx::query("DELETE FROM user_sessions WHERE user_id = $1 AND expires_at < $2")
+            .bind(user_id)
+            .bind(now)
+            .execute(db_pool)
+            .await?;
+
+        let session_id = Uuid::new_v4().to_string();
+        let mut tx = db_pool.begin().await?;
+        let (refresh_token, expires_at) = self
+            .issue_refresh_token(user_id, &session_id, &mut tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_sessions (id, user_id, user_agent, ip_address, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&session_id)
+        .bind(user_id)
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        let access_token = self.generate_access_token(user_id, &session_id)?;
+
+        Ok(AuthTokens {
+            access_token,
+            refresh_token,
+        })
+    }
+
+    /// 轮换刷新令牌: 旧令牌标记为已使用, 签发同一家族的新令牌
+    ///
+    /// 已使用过的令牌再次出现说明令牌可能被窃取, 此时撤销整个家族,
+    /// 合法客户端和攻击者持有的令牌都会失效, 需要重新登录。
+    pub async fn refresh_session(
+        &self,
+        refresh_token: &str,
+        client: &ClientInfo,
+        db_pool: &SqlitePool,
+    ) -> AppResult<AuthTokens> {
+        let claims = self.verify_token(refresh_token, TokenType::Refresh)?;
+        let user_id = claims.user_id()?;
+        let token_id = claims
+            .jti
+            .ok_or_else(|| AppError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        // 标记旧令牌、签发新令牌和更新会话在同一事务中完成, 避免中途失败后旧令牌已失效却没有新令牌
+        let mut tx = db_pool.begin().await?;
+        // 条件更新保证同一个令牌只能轮换一次, 并发刷新时只有一个请求成功
+        let family_id: Option<String> = sqlx::query_scalar(
+            r#"
+            UPDATE refresh_tokens SET used_at = $1
+            WHERE id = $2 AND user_id = $3 AND used_at IS NULL AND revoked_at IS NULL
+            RETURNING family_id
+            "#,
+        )
+        .bind(Utc::now().timestamp())
+        .bind(&token_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(family_id) = family_id else {
+            tx.rollback().await?;
+            let row = sqlx::query(
+                "SELECT family_id, revoked_at FROM refresh_tokens WHERE id = $1 AND user_id = $2",
+            )
+            .bind(&token_id)
+            .bind(user_id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Invalid refresh token".to_string()))?;
+
+            if row.get::<Option<i64>, _>("revoked_at").is_some() {
+                return Err(AppError::Unauthorized(
+                    "Session has been revoked".to_string(),
+                ));
+            }
+
+            let family_id: String = row.get("family_id");
+            warn!(
+                "刷新令牌被重复使用, 撤销会话: user_id={}, family_id={}",
+                user_id, family_id
+            );
+            self.revoke_session(user_id, &family_id, db_pool).await?;
+            return Err(AppError::Unauthorized(
+                "Refresh token reuse detected, session revoked".to_string(),
+            ));
+        };
+
+        // 用户被停用后不能继续刷新
+        let user_active: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND is_active = TRUE)",
+        )
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !user_active {
+            return Err(AppError::Unauthorized("User not found".to_string()));
+        }
+
+        let (refresh_token, expires_at) = self
+            .issue_refresh_token(user_id, &family_id, &mut tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE user_sessions
+            SET last_used_at = $1,
+                expires_at = $2,
+                user_agent = COALESCE($3, user_agent),
+                ip_address = COALESCE($4, ip_address)
+            WHERE id = $5
+            "#,
+        )
+        .bind(Utc::now().timestamp())
+        .bind(expires_at)
+        .bind(&client.user_agent)
+        .bind(&client.ip_address)
+        .bind(&family_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        let access_token = self.generate_access_token(user_id, &family_id)?;
+
+        Ok(AuthTokens {
+            access_token,
+            refresh_token,
+        })
+    }
+
+    /// 用户未过期、未撤销的会话, 按最近使用时间倒序
+    pub async fn list_sessions(
+        &self,
+        user_id: i64,
+        current_session_id: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<UserSession>> {
+        let sessions = sqlx::query_as::<_, UserSession>(
+            r#"
+            SELECT id, user_agent, ip_address, created_at, last_used_at, expires_at,
+                   id = $1 as current
+            FROM user_sessions
+            WHERE user_id = $2 AND revoked_at IS NULL AND expires_at >= $3
+            ORDER BY last_used_at DESC, created_at DESC
+            "#,
+        )
+        .bind(current_session_id)
+        .bind(user_id)
+        .bind(Utc::now().timestamp())
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// 撤销用户的一个会话, 会话不存在或已撤销时返回 false
+    pub async fn revoke_session(
+        &self,
+        user_id: i64,
+        session_id: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<bool> {
+        let now = Utc::now().timestamp();
+        // 会话和刷新令牌在同一事务中撤销, 避免会话已撤销而令牌家族仍可刷新
+        let mut tx = db_pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions SET revoked_at = $1
+            WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(session_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = $1
+            WHERE family_id = $2 AND user_id = $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(session_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
-    pub fn generate_refresh_token(&self, user_id: i64) -> AppResult<String> {
-        self.jwt_service.generate_refresh_token(user_id)
+    /// 撤销用户的全部会话 (在所有设备上登出), 返回撤销的会话数
+    pub async fn revoke_all_sessions(
+        &self,
+        user_id: i64,
+        keep_session_id: Option<&str>,
+        db_pool: &SqlitePool,
+    ) -> AppResult<u64> {
+        let now = Utc::now().timestamp();
+        let mut tx = db_pool.begin().await?;
+        // 不保留任何会话时绑定空字符串, 会话 ID 不会为空
+        let keep_session_id = keep_session_id.unwrap_or_default();
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions SET revoked_at = $1
+            WHERE user_id = $2 AND id != $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(keep_session_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = $1
+            WHERE user_id = $2 AND family_id != $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(keep_session_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
     }
 
-    pub fn verify_token(&self, token: &str) -> AppResult<i64> {
-        self.jwt_service.verify_token(token)
+    /// 签发刷新令牌并保存, 返回令牌和过期时间
+    async fn issue_refresh_token(
+        &self,
+        user_id: i64,
+        family_id: &str,
+        conn: &mut SqliteConnection,
+    ) -> AppResult<(String, i64)> {
+        let token_id = Uuid::new_v4().to_string();
+        let (token, expires_at) = self
+            .jwt_service
+            .generate_refresh_token(user_id, family_id, &token_id)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, family_id, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(&token_id)
+        .bind(user_id)
+        .bind(family_id)
+        .bind(expires_at)
+        .execute(conn)
+        .await?;
+
+        Ok((token, expires_at))
     }
 
     pub async fn get_user_by_id(
@@ -186,11 +476,325 @@ impl AuthService {
 
         Ok(())
     }
+
+    /// 申请重置密码, 向邮箱发送一次性的重置链接
+    ///
+    /// 邮箱未注册、距上次发送不足 password_reset_resend_interval 秒或发送失败时同样返回成功,
+    /// 不向调用方透露邮箱是否存在。重新申请会使之前发出的链接失效。
+    /// 耗时随邮箱是否注册而不同, handler 在后台调用, 不等待结果。
+    pub async fn request_password_reset(
+        &self,
+        email: &str,
+        config: &AuthConfig,
+        mailer: &dyn Mailer,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let user_id: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM users WHERE email = $1 AND is_active = TRUE")
+                .bind(email.trim())
+                .fetch_optional(db_pool)
+                .await?;
+        let Some(user_id) = user_id else {
+            return Ok(());
+        };
+
+        // 令牌带上用户 ID, 确认时按用户取出摘要后再比较, 数据库只保存摘要;
+        // 条件更新限制发送频率, 间隔内的重复申请不生成新令牌也不发送邮件
+        let now = Utc::now().timestamp();
+        let secret = generate_token();
+        let expires_at = now + config.password_reset_expires_in as i64 * 60;
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET password_reset_token = $1, password_reset_expires_at = $2,
+                password_reset_sent_at = $3
+            WHERE id = $4
+              AND (password_reset_sent_at IS NULL OR password_reset_sent_at <= $5)
+            "#,
+        )
+        .bind(hash_token(&secret))
+        .bind(expires_at)
+        .bind(now)
+        .bind(user_id)
+        .bind(now - config.password_reset_resend_interval as i64)
+        .execute(db_pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let token = format!("{}.{}", user_id, secret);
+        let email = Email {
+            to: email.trim().to_string(),
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "We received a request to reset your password.\n\n\
+                 Open the link below within {} minutes to choose a new password:\n\n\
+                 {}/reset-password?token={}\n\n\
+                 If you did not request this, you can ignore this email.",
+                config.password_reset_expires_in,
+                config.public_url.trim_end_matches('/'),
+                token
+            ),
+        };
+        if let Err(e) = mailer.send(&email).await {
+            warn!(
+                "Failed to send password reset mail via {}: {}",
+                mailer.name(),
+                e
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 使用重置令牌设置新密码, 令牌只能使用一次; 成功后撤销该用户的全部会话和个人访问令牌
+    pub async fn reset_password(
+        &self,
+        token: &str,
+        new_password: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let invalid = || AppError::BadRequest("Invalid or expired reset token".to_string());
+
+        let (user_id, secret) = split_user_token(token).ok_or_else(invalid)?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT password_reset_token, password_reset_expires_at
+            FROM users
+            WHERE id = $1 AND is_active = TRUE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(invalid)?;
+        let stored_hash: Option<String> = row.get("password_reset_token");
+        let expires_at: Option<i64> = row.get("password_reset_expires_at");
+
+        let now = Utc::now().timestamp();
+        let stored_hash = stored_hash.ok_or_else(invalid)?;
+        if !constant_time_eq(hash_token(secret).as_bytes(), stored_hash.as_bytes())
+            || expires_at.is_none_or(|expires_at| expires_at <= now)
+        {
+            return Err(invalid());
+        }
+
+        validate_password(new_password).map_err(AppError::BadRequest)?;
+        let new_password_hash = hash(new_password, DEFAULT_COST)?;
+
+        // 条件更新保证并发确认同一个令牌时只有一次成功
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET password_hash = $1, password_reset_token = NULL,
+                password_reset_expires_at = NULL, updated_at = $2
+            WHERE id = $3 AND password_reset_token = $4
+            "#,
+        )
+        .bind(new_password_hash)
+        .bind(now)
+        .bind(user_id)
+        .bind(&stored_hash)
+        .execute(db_pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(invalid());
+        }
+
+        // 密码可能已经泄露, 所有设备都需要重新登录, 用旧凭据创建的访问令牌也不再可信
+        self.revoke_all_sessions(user_id, None, db_pool).await?;
+        AccessTokenService::revoke_all_tokens(user_id, db_pool).await?;
+
+        Ok(())
+    }
+
+    /// 生成新的邮箱验证令牌并发送验证邮件, 之前发出的链接随即失效
+    pub async fn send_verification_email(
+        &self,
+        user: &User,
+        config: &AuthConfig,
+        mailer: &dyn Mailer,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let token = self
+            .issue_verification_token(user.id, 0, db_pool)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Email is already verified".to_string()))?;
+
+        mailer.send(&verification_email(user, &token, config)).await
+    }
+
+    /// 重新发送验证邮件, 距上次发送不足 email_verification_resend_interval 秒时拒绝
+    pub async fn resend_verification_email(
+        &self,
+        user_id: i64,
+        config: &AuthConfig,
+        mailer: &dyn Mailer,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let user = self
+            .get_user_by_id(user_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        if user.email_verified {
+            return Err(AppError::BadRequest(
+                "Email is already verified".to_string(),
+            ));
+        }
+
+        let interval = config.email_verification_resend_interval as i64;
+        let token = self
+            .issue_verification_token(user_id, interval, db_pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::TooManyRequests(format!(
+                    "Please wait {} seconds before requesting another verification email",
+                    interval
+                ))
+            })?;
+
+        mailer
+            .send(&verification_email(&user, &token, config))
+            .await
+    }
+
+    /// 使用邮件中的令牌验证邮箱, 令牌只能使用一次
+    pub async fn verify_email(
+        &self,
+        token: &str,
+        config: &AuthConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let invalid = || AppError::BadRequest("Invalid or expired verification token".to_string());
+
+        let (user_id, secret) = split_user_token(token).ok_or_else(invalid)?;
+        let row = sqlx::query(
+            r#"
+            SELECT email_verification_token, email_verification_sent_at
+            FROM users
+            WHERE id = $1 AND is_active = TRUE AND email_verified = FALSE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(invalid)?;
+        let stored_hash: Option<String> = row.get("email_verification_token");
+        let sent_at: Option<i64> = row.get("email_verification_sent_at");
+
+        let now = Utc::now().timestamp();
+        let expires_in = config.email_verification_expires_in as i64 * 60;
+        let stored_hash = stored_hash.ok_or_else(invalid)?;
+        if !constant_time_eq(hash_token(secret).as_bytes(), stored_hash.as_bytes())
+            || sent_at.is_none_or(|sent_at| sent_at + expires_in <= now)
+        {
+            return Err(invalid());
+        }
+
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET email_verified = TRUE, email_verification_token = NULL, updated_at = $1
+            WHERE id = $2 AND email_verification_token = $3
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(&stored_hash)
+        .execute(db_pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(invalid());
+        }
+
+        Ok(())
+    }
+
+    /// 配置要求邮箱已验证时, 检查用户是否已验证邮箱
+    pub async fn ensure_email_verified(
+        user_id: i64,
+        config: &AuthConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        if !config.require_verified_email {
+            return Ok(());
+        }
+
+        let verified: Option<bool> =
+            sqlx::query_scalar("SELECT email_verified FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(db_pool)
+                .await?;
+        if verified != Some(true) {
+            return Err(AppError::Forbidden(
+                "Email verification required".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 为未验证邮箱的用户生成验证令牌并保存摘要, 返回 `{user_id}.{secret}` 形式的令牌
+    ///
+    /// 上次发送距今不足 min_interval 秒或邮箱已验证时返回 None。
+    /// 条件更新保证并发请求时只有一个成功。
+    async fn issue_verification_token(
+        &self,
+        user_id: i64,
+        min_interval: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<String>> {
+        let now = Utc::now().timestamp();
+        let secret = generate_token();
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET email_verification_token = $1, email_verification_sent_at = $2
+            WHERE id = $3 AND email_verified = FALSE
+              AND (email_verification_sent_at IS NULL OR email_verification_sent_at <= $4)
+            "#,
+        )
+        .bind(hash_token(&secret))
+        .bind(now)
+        .bind(user_id)
+        .bind(now - min_interval)
+        .execute(db_pool)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then(|| format!("{}.{}", user_id, secret)))
+    }
+}
+
+/// 验证邮件, 链接指向前端的验证页面
+fn verification_email(user: &User, token: &str, config: &AuthConfig) -> Email {
+    Email {
+        to: user.email.clone(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Welcome, {}!\n\n\
+             Open the link below within {} hours to verify your email address:\n\n\
+             {}/verify-email?token={}\n\n\
+             If you did not create an account, you can ignore this email.",
+            user.username,
+            config.email_verification_expires_in.div_ceil(60),
+            config.public_url.trim_end_matches('/'),
+            token
+        ),
+    }
+}
+
+/// 拆分 `{user_id}.{secret}` 形式的一次性令牌
+fn split_user_token(token: &str) -> Option<(i64, &str)> {
+    let (user_id, secret) = token.trim().split_once('.')?;
+    Some((user_id.parse().ok()?, secret))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::mailer::{MemoryMailer, OutboxMailer};
     use sqlx::SqlitePool;
 
     async fn create_test_pool() -> SqlitePool {
@@ -210,6 +814,8 @@ mod tests {
                 email_verification_token TEXT,
                 password_reset_token TEXT,
                 password_reset_expires_at INTEGER,
+                password_reset_sent_at INTEGER,
+                email_verification_sent_at INTEGER,
                 last_login_at INTEGER,
                 created_at INTEGER DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
                 updated_at INTEGER DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
@@ -220,9 +826,69 @@ mod tests {
         .await
         .unwrap();
 
+        sqlx::query(
+            r#"
+            CREATE TABLE refresh_tokens (
+                id TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                family_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                used_at INTEGER,
+                revoked_at INTEGER,
+                created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE user_sessions (
+                id TEXT PRIMARY KEY,
+                user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                user_agent TEXT,
+                ip_address TEXT,
+                created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+                last_used_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+                expires_at INTEGER NOT NULL,
+                revoked_at INTEGER
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE personal_access_tokens (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                name TEXT NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                revoked_at INTEGER
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
         pool
     }
 
+    async fn register_test_user(service: &AuthService, pool: &SqlitePool) -> User {
+        let user_data = CreateUser {
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password: "Password123".to_string(),
+        };
+
+        service.register(user_data, pool).await.unwrap()
+    }
+
     #[tokio::test]
     async fn test_auth_service_new() {
         let service = AuthService::new("test_secret".to_string());
@@ -383,17 +1049,201 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_and_verify_token() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
+
+        let tokens = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+
+        let access_claims = service
+            .verify_token(&tokens.access_token, TokenType::Access)
+            .unwrap();
+        let refresh_claims = service
+            .verify_token(&tokens.refresh_token, TokenType::Refresh)
+            .unwrap();
+
+        assert_eq!(access_claims.user_id().unwrap(), user.id);
+        assert_eq!(refresh_claims.user_id().unwrap(), user.id);
+        assert_eq!(access_claims.sid, refresh_claims.sid);
+
+        // 两种令牌不能互换使用
+        assert!(service
+            .verify_token(&tokens.refresh_token, TokenType::Access)
+            .is_err());
+        assert!(service
+            .refresh_session(&tokens.access_token, &ClientInfo::default(), &pool)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_token() {
+        let pool = create_test_pool().await;
         let service = AuthService::new("test_secret".to_string());
-        let user_id = 123;
+        let user = register_test_user(&service, &pool).await;
 
-        let access_token = service.generate_access_token(user_id).unwrap();
-        let refresh_token = service.generate_refresh_token(user_id).unwrap();
+        let tokens = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        let rotated = service
+            .refresh_session(&tokens.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        assert_ne!(rotated.refresh_token, tokens.refresh_token);
+
+        // 轮换后的令牌属于同一会话, 可以继续刷新
+        let session_id = service
+            .verify_token(&tokens.access_token, TokenType::Access)
+            .unwrap()
+            .sid;
+        let rotated_session_id = service
+            .verify_token(&rotated.refresh_token, TokenType::Refresh)
+            .unwrap()
+            .sid;
+        assert_eq!(rotated_session_id, session_id);
+        assert!(service
+            .refresh_session(&rotated.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_reuse_revokes_family() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
+
+        let tokens = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        let other_session = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        let rotated = service
+            .refresh_session(&tokens.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+
+        // 重放已轮换的令牌, 整个家族被撤销, 包括最新签发的令牌
+        let result = service
+            .refresh_session(&tokens.refresh_token, &ClientInfo::default(), &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        let result = service
+            .refresh_session(&rotated.refresh_token, &ClientInfo::default(), &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+
+        // 其他会话不受影响
+        assert!(service
+            .refresh_session(&other_session.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .is_ok());
+    }
 
-        let verified_access_id = service.verify_token(&access_token).unwrap();
-        let verified_refresh_id = service.verify_token(&refresh_token).unwrap();
+    #[tokio::test]
+    async fn test_revoke_session() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
 
-        assert_eq!(verified_access_id, user_id);
-        assert_eq!(verified_refresh_id, user_id);
+        let tokens = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        let session_id = service
+            .verify_token(&tokens.access_token, TokenType::Access)
+            .unwrap()
+            .sid;
+
+        assert!(!service
+            .revoke_session(user.id + 1, &session_id, &pool)
+            .await
+            .unwrap());
+        assert!(service
+            .revoke_session(user.id, &session_id, &pool)
+            .await
+            .unwrap());
+        assert!(!service
+            .revoke_session(user.id, &session_id, &pool)
+            .await
+            .unwrap());
+
+        let result = service
+            .refresh_session(&tokens.refresh_token, &ClientInfo::default(), &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_and_revoke_all_sessions() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
+
+        let laptop = ClientInfo {
+            user_agent: Some("Firefox".to_string()),
+            ip_address: Some("192.0.2.1".to_string()),
+        };
+        let current = service
+            .create_session(user.id, &laptop, &pool)
+            .await
+            .unwrap();
+        let other = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        let current_id = service
+            .verify_token(&current.access_token, TokenType::Access)
+            .unwrap()
+            .sid;
+
+        let sessions = service
+            .list_sessions(user.id, &current_id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 2);
+        let session = sessions.iter().find(|session| session.current).unwrap();
+        assert_eq!(session.id, current_id);
+        assert_eq!(session.user_agent.as_deref(), Some("Firefox"));
+        assert_eq!(session.ip_address.as_deref(), Some("192.0.2.1"));
+
+        // 只登出其他设备
+        assert_eq!(
+            service
+                .revoke_all_sessions(user.id, Some(&current_id), &pool)
+                .await
+                .unwrap(),
+            1
+        );
+        let sessions = service
+            .list_sessions(user.id, &current_id, &pool)
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(service
+            .refresh_session(&other.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .is_err());
+
+        assert_eq!(
+            service
+                .revoke_all_sessions(user.id, None, &pool)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(service
+            .list_sessions(user.id, &current_id, &pool)
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
@@ -478,4 +1328,301 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    /// 发送重置邮件并从 outbox 目录中取出链接里的令牌 (不限制发送间隔)
+    async fn request_reset_token(service: &AuthService, pool: &SqlitePool) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let mailer = OutboxMailer::new("noreply@example.com", dir.path().to_str());
+        let config = AuthConfig {
+            password_reset_resend_interval: 0,
+            ..Default::default()
+        };
+        service
+            .request_password_reset("test@example.com", &config, &mailer, pool)
+            .await
+            .unwrap();
+
+        let path = std::fs::read_dir(dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let message = std::fs::read_to_string(path).unwrap();
+        message
+            .split("reset-password?token=")
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_success() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
+        let tokens = service
+            .create_session(user.id, &ClientInfo::default(), &pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO personal_access_tokens (user_id, name, token_hash) VALUES ($1, 'cli', 'hash')",
+        )
+        .bind(user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let token = request_reset_token(&service, &pool).await;
+        // 数据库中只保存令牌摘要
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT password_reset_token FROM users WHERE id = $1")
+                .bind(user.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(!token.contains(stored.as_deref().unwrap()));
+
+        service
+            .reset_password(&token, "NewPassword456", &pool)
+            .await
+            .unwrap();
+
+        let login_data = LoginUser {
+            email: "test@example.com".to_string(),
+            password: "NewPassword456".to_string(),
+        };
+        assert!(service.login(login_data, &pool).await.is_ok());
+
+        // 重置后全部会话失效, 令牌不能再次使用
+        assert!(service
+            .list_sessions(user.id, "", &pool)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(service
+            .refresh_session(&tokens.refresh_token, &ClientInfo::default(), &pool)
+            .await
+            .is_err());
+        // 个人访问令牌同样被撤销
+        let active_tokens: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM personal_access_tokens WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(active_tokens, 0);
+        let result = service
+            .reset_password(&token, "OtherPassword789", &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_rejects_invalid_tokens() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let user = register_test_user(&service, &pool).await;
+
+        // 未注册的邮箱同样返回成功, 且不发送邮件
+        let dir = tempfile::tempdir().unwrap();
+        let mailer = OutboxMailer::new("noreply@example.com", dir.path().to_str());
+        service
+            .request_password_reset("nobody@example.com", &AuthConfig::default(), &mailer, &pool)
+            .await
+            .unwrap();
+        assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+
+        let token = request_reset_token(&service, &pool).await;
+        let (user_part, secret) = token.split_once('.').unwrap();
+        let mut tampered = secret.to_string();
+        tampered.replace_range(0..1, if secret.starts_with('0') { "1" } else { "0" });
+        for bad in [
+            String::new(),
+            secret.to_string(),
+            format!("{}.{}", user_part, tampered),
+            format!("{}.{}", user.id + 1, secret),
+        ] {
+            let result = service.reset_password(&bad, "NewPassword456", &pool).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+
+        // 新密码不符合要求时令牌保留
+        assert!(service
+            .reset_password(&token, "short", &pool)
+            .await
+            .is_err());
+
+        // 重新申请后旧令牌失效
+        let latest = request_reset_token(&service, &pool).await;
+        let result = service
+            .reset_password(&token, "NewPassword456", &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        // 过期的令牌不能使用
+        sqlx::query("UPDATE users SET password_reset_expires_at = $1 WHERE id = $2")
+            .bind(Utc::now().timestamp() - 1)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let result = service
+            .reset_password(&latest, "NewPassword456", &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_resend_interval() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        register_test_user(&service, &pool).await;
+        let mailer = MemoryMailer::default();
+        let config = AuthConfig::default();
+
+        for _ in 0..3 {
+            service
+                .request_password_reset("test@example.com", &config, &mailer, &pool)
+                .await
+                .unwrap();
+        }
+        // 间隔内的重复申请不发送邮件, 第一封邮件中的链接仍然有效
+        let sent = mailer.sent();
+        assert_eq!(sent.len(), 1);
+        let token = sent[0]
+            .body
+            .split("reset-password?token=")
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .to_string();
+        service
+            .reset_password(&token, "NewPassword456", &pool)
+            .await
+            .unwrap();
+    }
+
+    /// 取出验证邮件链接里的令牌
+    fn verification_token(mailer: &MemoryMailer) -> String {
+        let email = mailer.sent().pop().unwrap();
+        assert_eq!(email.to, "test@example.com");
+        email
+            .body
+            .split("verify-email?token=")
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_verify_email() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let config = AuthConfig {
+            require_verified_email: true,
+            ..AuthConfig::default()
+        };
+        let mailer = MemoryMailer::new();
+        let user = register_test_user(&service, &pool).await;
+        assert!(!user.email_verified);
+
+        service
+            .send_verification_email(&user, &config, &mailer, &pool)
+            .await
+            .unwrap();
+        let token = verification_token(&mailer);
+        let result = AuthService::ensure_email_verified(user.id, &config, &pool).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+        // 未开启 require_verified_email 时不检查
+        assert!(
+            AuthService::ensure_email_verified(user.id, &AuthConfig::default(), &pool)
+                .await
+                .is_ok()
+        );
+
+        let (_, secret) = token.split_once('.').unwrap();
+        let result = service
+            .verify_email(&format!("{}.{}", user.id + 1, secret), &config, &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        service.verify_email(&token, &config, &pool).await.unwrap();
+        let user = service
+            .get_user_by_id(user.id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(user.email_verified);
+        assert!(user.email_verification_token.is_none());
+        assert!(AuthService::ensure_email_verified(user.id, &config, &pool)
+            .await
+            .is_ok());
+
+        // 令牌只能使用一次, 已验证后不再发送验证邮件
+        let result = service.verify_email(&token, &config, &pool).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let result = service
+            .resend_verification_email(user.id, &config, &mailer, &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resend_verification_email_is_throttled() {
+        let pool = create_test_pool().await;
+        let service = AuthService::new("test_secret".to_string());
+        let config = AuthConfig::default();
+        let mailer = MemoryMailer::new();
+        let user = register_test_user(&service, &pool).await;
+
+        service
+            .send_verification_email(&user, &config, &mailer, &pool)
+            .await
+            .unwrap();
+        let first = verification_token(&mailer);
+
+        let result = service
+            .resend_verification_email(user.id, &config, &mailer, &pool)
+            .await;
+        assert!(matches!(result, Err(AppError::TooManyRequests(_))));
+        assert_eq!(mailer.sent().len(), 1);
+
+        // 超过发送间隔后可以重新发送, 之前的链接失效
+        sqlx::query(
+            "UPDATE users SET email_verification_sent_at = email_verification_sent_at - $1",
+        )
+        .bind(config.email_verification_resend_interval as i64)
+        .execute(&pool)
+        .await
+        .unwrap();
+        service
+            .resend_verification_email(user.id, &config, &mailer, &pool)
+            .await
+            .unwrap();
+        let second = verification_token(&mailer);
+        assert_ne!(first, second);
+        let result = service.verify_email(&first, &config, &pool).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        // 过期的链接不能使用
+        sqlx::query(
+            "UPDATE users SET email_verification_sent_at = email_verification_sent_at - $1",
+        )
+        .bind(config.email_verification_expires_in as i64 * 60)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let result = service.verify_email(&second, &config, &pool).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
 }
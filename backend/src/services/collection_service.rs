@@ -127,6 +127,7 @@ impl CollectionService {
             && update_data.parent_id.is_none()
             && update_data.clear_parent_id.is_none()
             && update_data.sort_order.is_none()
+            && update_data.is_public.is_none()
         {
             return Err(AppError::BadRequest(
                 "No update fields provided".to_string(),
@@ -143,8 +144,9 @@ impl CollectionService {
                 icon = COALESCE($4, icon),
                 parent_id = CASE WHEN $5 THEN NULL ELSE COALESCE($6, parent_id) END,
                 sort_order = COALESCE($7, sort_order),
+                is_public = COALESCE($8, is_public),
                 updated_at = CAST(strftime('%s', 'now') AS INTEGER)
-            WHERE id = $8 AND user_id = $9
+            WHERE id = $9 AND user_id = $10
             RETURNING id, user_id, name, description,
                       color, icon, sort_order,
                       is_default, is_public, parent_id,
@@ -159,6 +161,7 @@ impl CollectionService {
         .bind(update_data.clear_parent_id.unwrap_or(false))
         .bind(update_data.parent_id)
         .bind(update_data.sort_order)
+        .bind(update_data.is_public)
         .bind(collection_id)
         .bind(user_id)
         .fetch_optional(db_pool)
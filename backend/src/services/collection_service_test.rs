@@ -249,6 +249,7 @@ async fn test_update_collection_success() {
         parent_id: None,
         clear_parent_id: None,
         sort_order: None,
+        is_public: None,
     };
 
     let result =
@@ -287,6 +288,7 @@ async fn test_update_collection_partial() {
         parent_id: None,
         clear_parent_id: None,
         sort_order: None,
+        is_public: None,
     };
 
     let result =
@@ -301,6 +303,26 @@ async fn test_update_collection_partial() {
     );
     assert_eq!(updated_collection.color, "#000000");
     assert_eq!(updated_collection.icon, "original-icon");
+    assert!(!updated_collection.is_public);
+
+    // 只修改公开状态
+    let update_data = UpdateCollection {
+        name: None,
+        description: None,
+        color: None,
+        icon: None,
+        parent_id: None,
+        clear_parent_id: None,
+        sort_order: None,
+        is_public: Some(true),
+    };
+    let updated_collection =
+        CollectionService::update_collection(user_id, collection.id, update_data, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+    assert!(updated_collection.is_public);
+    assert_eq!(updated_collection.name, "Updated Name");
 }
 
 #[tokio::test]
@@ -316,6 +338,7 @@ async fn test_update_collection_not_found() {
         parent_id: None,
         clear_parent_id: None,
         sort_order: None,
+        is_public: None,
     };
 
     let result = CollectionService::update_collection(user_id, 999, update_data, &pool).await;
@@ -0,0 +1,104 @@
+//! 特征哈希向量化
+//!
+//! 把词语和词内的字符三元组哈希到固定维度, 不需要模型文件, 结果在任何平台上都一致。
+//! 能匹配词形变化和拼写相近的词 (runtime / runtimes), 但不理解同义词;
+//! 需要真正的语义相似度时应替换为基于模型的 Embedder。
+use super::{normalize, Embedder};
+
+/// 字符三元组相对整个词语的权重
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+pub struct HashingEmbedder {
+    dimensions: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        let dimensions = dimensions.max(1);
+        Self {
+            dimensions,
+            model: format!("hashing-v1-{}", dimensions),
+        }
+    }
+
+    /// 按特征的哈希值累加到对应维度, 用哈希的最高位决定符号以减少冲突带来的偏差
+    fn add_feature(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = fnv1a(feature.as_bytes());
+        let index = (hash % self.dimensions as u64) as usize;
+        if hash >> 63 == 0 {
+            vector[index] += weight;
+        } else {
+            vector[index] -= weight;
+        }
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            let word = word.to_lowercase();
+            self.add_feature(&mut vector, &word, 1.0);
+
+            // 加上边界标记后取字符三元组, 例如 #go# -> #go, go#
+            let chars: Vec<char> = format!("#{}#", word).chars().collect();
+            for trigram in chars.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&mut vector, &format!("3:{}", trigram), TRIGRAM_WEIGHT);
+            }
+        }
+
+        normalize(&mut vector);
+        vector
+    }
+}
+
+/// FNV-1a 64 位哈希, 与标准库的 Hasher 不同, 结果不随 Rust 版本变化
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::embedding::cosine_similarity;
+
+    #[test]
+    fn test_embedding_is_deterministic_and_normalized() {
+        let embedder = HashingEmbedder::new(64);
+        let vector = embedder.embed("Async runtime for Rust");
+        assert_eq!(vector.len(), 64);
+        assert_eq!(vector, embedder.embed("async RUNTIME, for rust!"));
+
+        let norm: f32 = vector.iter().map(|value| value * value).sum();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(embedder.embed("  ...  ").iter().all(|value| *value == 0.0));
+    }
+
+    #[test]
+    fn test_similar_texts_are_closer() {
+        let embedder = HashingEmbedder::new(256);
+        let query = embedder.embed("async runtimes");
+        let related = embedder.embed("Tokio is an async runtime");
+        let unrelated = embedder.embed("Sourdough bread baking");
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+}
@@ -0,0 +1,82 @@
+//! 文本向量化, 用于语义搜索 (embeddings feature)
+//!
+//! 每种模型实现 `Embedder`, 只负责把文本转换为归一化的向量;
+//! 向量的保存和相似度检索由 SemanticSearchService 负责。
+//! 默认使用不依赖模型文件的 HashingEmbedder, 本地模型可以实现同一个 trait 后替换 AppState 中的实例。
+use std::sync::Arc;
+
+use crate::config::search::SemanticConfig;
+
+pub mod hashing;
+
+pub use hashing::HashingEmbedder;
+
+/// 文本向量化模型
+pub trait Embedder: Send + Sync {
+    /// 模型名称, 与向量一起保存, 变化后旧向量会被重新计算
+    fn model(&self) -> &str;
+
+    /// 向量维度
+    fn dimensions(&self) -> usize;
+
+    /// 计算文本的向量, 返回长度为 1 的向量; 文本没有可用内容时返回全 0 向量
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// 根据配置创建向量化模型
+pub fn build_embedder(config: &SemanticConfig) -> Arc<dyn Embedder> {
+    Arc::new(HashingEmbedder::new(config.dimensions))
+}
+
+/// 向量的存储格式: 小端序 f32 数组
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// 余弦相似度, 向量已归一化时即为点积; 维度不同时返回 0
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// 归一化为长度 1, 全 0 向量保持不变
+pub fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vector = vec![0.5, -0.25, 1.0, f32::MIN_POSITIVE];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        let mut a = vec![3.0, 4.0];
+        normalize(&mut a);
+        assert!((cosine_similarity(&a, &a) - 1.0).abs() < 1e-6);
+        assert!((cosine_similarity(&a, &[-0.6, -0.8]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&a, &[1.0]), 0.0);
+    }
+}
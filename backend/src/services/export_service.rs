@@ -0,0 +1,424 @@
+use std::collections::HashSet;
+
+use futures_util::stream::{self, Stream};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::models::{
+    ExportedCollection, ExportedFile, ExportedReference, ExportedResource, ExportedTag,
+    LibraryExport, EXPORT_FORMAT_VERSION,
+};
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::netscape::{render_bookmarks, NetscapeFolder, NetscapeLink};
+
+/// 每页读取的资源和引用关系数量
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// 资源是否在导出范围内: $1 = user_id, $2 = include_archived, $3 = 收藏夹范围 (JSON 数组或 NULL)
+fn scope_condition(alias: &str) -> String {
+    format!(
+        "{a}.user_id = $1 AND ($2 OR {a}.is_archived = 0) \
+         AND ($3 IS NULL OR {a}.collection_id IN (SELECT value FROM json_each($3)))",
+        a = alias
+    )
+}
+
+/// 带分页 id 的引用关系
+#[derive(FromRow)]
+struct ReferenceRow {
+    id: i64,
+    #[sqlx(flatten)]
+    reference: ExportedReference,
+}
+
+/// 带资源 id 的上传文件元数据
+#[derive(FromRow)]
+struct FileRow {
+    resource_id: i64,
+    #[sqlx(flatten)]
+    file: ExportedFile,
+}
+
+/// 一次导出的范围
+///
+/// 收藏夹和标签数量有限, 创建时一次读取; 资源和引用关系按 id 分页读取,
+/// 流式导出时不需要把整个资源库放在内存中
+pub struct LibraryExporter {
+    user_id: i64,
+    include_archived: bool,
+    /// 收藏夹范围的 id 列表 (JSON 数组), None 表示整个资源库
+    scope: Option<String>,
+    page_size: i64,
+    pub collections: Vec<ExportedCollection>,
+    pub tags: Vec<ExportedTag>,
+}
+
+impl LibraryExporter {
+    /// 确定导出范围并读取收藏夹和标签
+    ///
+    /// - 指定 collection_id 时只导出该收藏夹及其所有子收藏夹中的资源
+    /// - 标签只包含被导出资源使用到的标签 (导出整个资源库时包含全部标签)
+    pub async fn new(
+        user_id: i64,
+        collection_id: Option<i64>,
+        include_archived: bool,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Self> {
+        let mut collections = sqlx::query_as::<_, ExportedCollection>(
+            r#"
+            SELECT id, name, description, color, icon, sort_order, is_default, is_public,
+                   parent_id, created_at, updated_at
+            FROM collections
+            WHERE user_id = $1
+            ORDER BY sort_order ASC, id ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        // 收藏夹范围: 指定收藏夹及其子树
+        let scope = match collection_id {
+            Some(root_id) => {
+                if !collections.iter().any(|c| c.id == root_id) {
+                    return Err(AppError::NotFound("Collection not found".to_string()));
+                }
+                let subtree = Self::collect_subtree(&collections, root_id);
+                collections.retain(|c| subtree.contains(&c.id));
+                Some(serde_json::to_string(&subtree)?)
+            }
+            None => None,
+        };
+
+        let tags = sqlx::query_as::<_, ExportedTag>(&format!(
+            r#"
+            SELECT id, name, color, description, created_at, updated_at
+            FROM tags t
+            WHERE t.user_id = $1
+              AND ($3 IS NULL OR EXISTS (
+                  SELECT 1 FROM resource_tags rt
+                  JOIN resources r ON r.id = rt.resource_id
+                  WHERE rt.tag_id = t.id AND {}
+              ))
+            ORDER BY name ASC
+            "#,
+            scope_condition("r")
+        ))
+        .bind(user_id)
+        .bind(include_archived)
+        .bind(&scope)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(Self {
+            user_id,
+            include_archived,
+            scope,
+            page_size: EXPORT_PAGE_SIZE,
+            collections,
+            tags,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn with_page_size(mut self, page_size: i64) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// 读取 id 大于 after_id 的一页资源 (包含标签名称和上传文件元数据), 按 id 升序
+    pub async fn resources_page(
+        &self,
+        after_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<ExportedResource>> {
+        let mut resources = sqlx::query_as::<_, ExportedResource>(&format!(
+            r#"
+            SELECT id, collection_id, title, url, description, favicon_url, screenshot_url,
+                   thumbnail_url, is_favorite, is_archived, is_private, is_read, visit_count,
+                   last_visited, metadata, type, content, source, mime_type,
+                   created_at, updated_at
+            FROM resources r
+            WHERE {} AND r.id > $4
+            ORDER BY r.id ASC
+            LIMIT $5
+            "#,
+            scope_condition("r")
+        ))
+        .bind(self.user_id)
+        .bind(self.include_archived)
+        .bind(&self.scope)
+        .bind(after_id)
+        .bind(self.page_size)
+        .fetch_all(db_pool)
+        .await?;
+
+        if resources.is_empty() {
+            return Ok(resources);
+        }
+
+        let ids = serde_json::to_string(&resources.iter().map(|r| r.id).collect::<Vec<_>>())?;
+        let resource_tags: Vec<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT rt.resource_id, t.name
+            FROM resource_tags rt
+            JOIN tags t ON t.id = rt.tag_id
+            WHERE rt.resource_id IN (SELECT value FROM json_each($1))
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(db_pool)
+        .await?;
+
+        for (resource_id, name) in resource_tags {
+            if let Some(resource) = resources.iter_mut().find(|r| r.id == resource_id) {
+                resource.tags.push(name);
+            }
+        }
+
+        let files = sqlx::query_as::<_, FileRow>(
+            r#"
+            SELECT resource_id, file_name, content_type, size, checksum,
+                   storage_backend, storage_key
+            FROM resource_files
+            WHERE resource_id IN (SELECT value FROM json_each($1))
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(db_pool)
+        .await?;
+
+        for row in files {
+            if let Some(resource) = resources.iter_mut().find(|r| r.id == row.resource_id) {
+                resource.file = Some(row.file);
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// 读取 id 大于 after_id 的一页引用关系, 只包含两端都被导出的记录
+    ///
+    /// 返回值中的 i64 为引用关系的 id, 用于读取下一页
+    pub async fn references_page(
+        &self,
+        after_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, ExportedReference)>> {
+        let rows = sqlx::query_as::<_, ReferenceRow>(&format!(
+            r#"
+            SELECT rr.id, rr.source_id, rr.target_id,
+                   COALESCE(rr.type, 'related') AS type,
+                   COALESCE(rr.created_at, 0) AS created_at
+            FROM resource_references rr
+            JOIN resources s ON s.id = rr.source_id
+            JOIN resources t ON t.id = rr.target_id
+            WHERE {} AND {} AND rr.id > $4
+            ORDER BY rr.id ASC
+            LIMIT $5
+            "#,
+            scope_condition("s"),
+            scope_condition("t")
+        ))
+        .bind(self.user_id)
+        .bind(self.include_archived)
+        .bind(&self.scope)
+        .bind(after_id)
+        .bind(self.page_size)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.id, row.reference))
+            .collect())
+    }
+
+    /// 收集指定收藏夹及其所有子收藏夹的 id
+    fn collect_subtree(collections: &[ExportedCollection], root_id: i64) -> HashSet<i64> {
+        let mut subtree = HashSet::from([root_id]);
+        let mut frontier = vec![root_id];
+
+        while let Some(parent_id) = frontier.pop() {
+            for collection in collections {
+                if collection.parent_id == Some(parent_id) && subtree.insert(collection.id) {
+                    frontier.push(collection.id);
+                }
+            }
+        }
+
+        subtree
+    }
+}
+
+/// 流式 JSON 导出的进度
+enum JsonExportStage {
+    Header,
+    Resources { after_id: i64, first: bool },
+    References { after_id: i64, first: bool },
+    Done,
+}
+
+pub struct ExportService;
+
+impl ExportService {
+    /// 导出用户的资源库, 范围见 LibraryExporter
+    ///
+    /// 整个文档在内存中构建, 用于命令模式和 Netscape 书签文件;
+    /// 下载 JSON 文档时使用 export_json_stream
+    pub async fn export_library(
+        user_id: i64,
+        collection_id: Option<i64>,
+        include_archived: bool,
+        db_pool: &SqlitePool,
+    ) -> AppResult<LibraryExport> {
+        let exporter =
+            LibraryExporter::new(user_id, collection_id, include_archived, db_pool).await?;
+
+        let mut resources = Vec::new();
+        loop {
+            let after_id = resources.last().map_or(0, |r: &ExportedResource| r.id);
+            let page = exporter.resources_page(after_id, db_pool).await?;
+            if page.is_empty() {
+                break;
+            }
+            resources.extend(page);
+        }
+
+        let mut references = Vec::new();
+        let mut after_id = 0;
+        loop {
+            let page = exporter.references_page(after_id, db_pool).await?;
+            let Some((last_id, _)) = page.last() else {
+                break;
+            };
+            after_id = *last_id;
+            references.extend(page.into_iter().map(|(_, reference)| reference));
+        }
+
+        Ok(LibraryExport {
+            version: EXPORT_FORMAT_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            collections: exporter.collections,
+            tags: exporter.tags,
+            resources,
+            references,
+        })
+    }
+
+    /// 以 JSON 文档的形式流式导出资源库
+    ///
+    /// 输出与 export_library 的结果序列化后等价; 资源和引用关系逐页读取并写出,
+    /// 内存占用与资源库大小无关。读取出错时响应被中断, 客户端得到不完整的文档。
+    pub fn export_json_stream(
+        exporter: LibraryExporter,
+        db_pool: SqlitePool,
+    ) -> impl Stream<Item = AppResult<String>> + Send + 'static {
+        stream::try_unfold(
+            (JsonExportStage::Header, exporter, db_pool),
+            |(stage, exporter, db_pool)| async move {
+                let (chunk, next) = match stage {
+                    JsonExportStage::Header => {
+                        let chunk = format!(
+                            r#"{{"version":{},"exported_at":{},"collections":{},"tags":{},"resources":["#,
+                            EXPORT_FORMAT_VERSION,
+                            chrono::Utc::now().timestamp(),
+                            serde_json::to_string(&exporter.collections)?,
+                            serde_json::to_string(&exporter.tags)?
+                        );
+                        let next = JsonExportStage::Resources {
+                            after_id: 0,
+                            first: true,
+                        };
+                        (chunk, next)
+                    }
+                    JsonExportStage::Resources { after_id, first } => {
+                        let page = exporter.resources_page(after_id, &db_pool).await?;
+                        match page.last() {
+                            Some(last) => {
+                                let next = JsonExportStage::Resources {
+                                    after_id: last.id,
+                                    first: false,
+                                };
+                                (json_items(&page, first)?, next)
+                            }
+                            None => {
+                                let next = JsonExportStage::References {
+                                    after_id: 0,
+                                    first: true,
+                                };
+                                (r#"],"references":["#.to_string(), next)
+                            }
+                        }
+                    }
+                    JsonExportStage::References { after_id, first } => {
+                        let page = exporter.references_page(after_id, &db_pool).await?;
+                        match page.last() {
+                            Some((last_id, _)) => {
+                                let next = JsonExportStage::References {
+                                    after_id: *last_id,
+                                    first: false,
+                                };
+                                let references: Vec<&ExportedReference> =
+                                    page.iter().map(|(_, reference)| reference).collect();
+                                (json_items(&references, first)?, next)
+                            }
+                            None => ("]}".to_string(), JsonExportStage::Done),
+                        }
+                    }
+                    JsonExportStage::Done => return Ok(None),
+                };
+
+                Ok(Some((chunk, (next, exporter, db_pool))))
+            },
+        )
+    }
+
+    /// 将导出文档渲染为 Netscape 书签文件
+    ///
+    /// 浏览器书签只能表示链接, 因此只输出带 URL 的 link 资源
+    pub fn render_netscape(export: &LibraryExport) -> String {
+        let folders: Vec<NetscapeFolder> = export
+            .collections
+            .iter()
+            .map(|c| NetscapeFolder {
+                id: c.id,
+                name: c.name.clone(),
+                parent_id: c.parent_id,
+                add_date: Some(c.created_at),
+            })
+            .collect();
+
+        let links: Vec<NetscapeLink> = export
+            .resources
+            .iter()
+            .filter(|r| r.resource_type == "link")
+            .filter_map(|r| {
+                let url = r.url.clone().filter(|url| !url.is_empty())?;
+                Some(NetscapeLink {
+                    title: r.title.clone(),
+                    url,
+                    description: r.description.clone(),
+                    tags: r.tags.clone(),
+                    add_date: Some(r.created_at),
+                    last_modified: Some(r.updated_at),
+                    folder_id: r.collection_id,
+                })
+            })
+            .collect();
+
+        render_bookmarks(&folders, &links)
+    }
+}
+
+/// 将一页记录序列化为 JSON 数组中的元素, 不是第一页时以逗号开头
+fn json_items<T: serde::Serialize>(items: &[T], first: bool) -> AppResult<String> {
+    let mut chunk = String::new();
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 || !first {
+            chunk.push(',');
+        }
+        chunk.push_str(&serde_json::to_string(item)?);
+    }
+    Ok(chunk)
+}
@@ -0,0 +1,360 @@
+use futures_util::TryStreamExt;
+use sqlx::SqlitePool;
+
+use crate::config::AuthConfig;
+use crate::models::{ImportFormat, ImportOptions, LibraryExport, EXPORT_FORMAT_VERSION};
+use crate::services::export_service::{ExportService, LibraryExporter};
+use crate::services::import_service::ImportService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::netscape::parse_bookmarks;
+
+async fn create_collection(
+    pool: &SqlitePool,
+    user_id: i64,
+    name: &str,
+    parent_id: Option<i64>,
+) -> i64 {
+    sqlx::query_scalar(
+        "INSERT INTO collections (user_id, name, parent_id) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(parent_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn create_resource(
+    pool: &SqlitePool,
+    user_id: i64,
+    collection_id: Option<i64>,
+    title: &str,
+    url: Option<&str>,
+    resource_type: &str,
+    content: Option<&str>,
+    is_archived: bool,
+) -> i64 {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO resources (user_id, collection_id, title, url, type, content, is_archived, metadata)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, '{"lang":"en"}')
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(collection_id)
+    .bind(title)
+    .bind(url)
+    .bind(resource_type)
+    .bind(content)
+    .bind(is_archived)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn tag_resource(pool: &SqlitePool, user_id: i64, resource_id: i64, name: &str) {
+    sqlx::query("INSERT OR IGNORE INTO tags (user_id, name) VALUES ($1, $2)")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO resource_tags (resource_id, tag_id)
+        SELECT $1, id FROM tags WHERE user_id = $2 AND name = $3
+        "#,
+    )
+    .bind(resource_id)
+    .bind(user_id)
+    .bind(name)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// 构造测试资源库:
+/// Dev (Tools) 两级收藏夹, 一个 link, 一个 note, 一个已归档 link, 一个未归类 link,
+/// 以及另一个用户的资源
+async fn seed_library(pool: &SqlitePool) -> (i64, i64, i64) {
+    let user_id = create_test_user(pool, "exporter").await;
+    let other_user = create_test_user(pool, "other").await;
+
+    let dev = create_collection(pool, user_id, "Dev", None).await;
+    let tools = create_collection(pool, user_id, "Tools", Some(dev)).await;
+    create_collection(pool, user_id, "Reading", None).await;
+
+    let rust = create_resource(
+        pool,
+        user_id,
+        Some(tools),
+        "Rust",
+        Some("https://www.rust-lang.org/"),
+        "link",
+        None,
+        false,
+    )
+    .await;
+    let note = create_resource(
+        pool,
+        user_id,
+        Some(dev),
+        "Ownership notes",
+        None,
+        "note",
+        Some("Borrowing rules"),
+        false,
+    )
+    .await;
+    create_resource(
+        pool,
+        user_id,
+        Some(dev),
+        "Old docs",
+        Some("https://old.example.com/"),
+        "link",
+        None,
+        true,
+    )
+    .await;
+    create_resource(
+        pool,
+        user_id,
+        None,
+        "Unsorted",
+        Some("https://example.com/"),
+        "link",
+        None,
+        false,
+    )
+    .await;
+    create_resource(
+        pool,
+        other_user,
+        None,
+        "Not mine",
+        Some("https://other.example.com/"),
+        "link",
+        None,
+        false,
+    )
+    .await;
+
+    tag_resource(pool, user_id, rust, "rust").await;
+    tag_resource(pool, user_id, note, "notes").await;
+
+    sqlx::query(
+        "INSERT INTO resource_references (source_id, target_id, type) VALUES ($1, $2, 'related')",
+    )
+    .bind(note)
+    .bind(rust)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    (user_id, dev, note)
+}
+
+#[tokio::test]
+async fn test_export_library_json_is_lossless() {
+    let pool = create_test_pool().await;
+    let (user_id, _, note) = seed_library(&pool).await;
+
+    let export = ExportService::export_library(user_id, None, true, &pool)
+        .await
+        .unwrap();
+
+    assert_eq!(export.version, EXPORT_FORMAT_VERSION);
+    assert_eq!(export.collections.len(), 3);
+    assert_eq!(export.resources.len(), 4);
+    assert_eq!(export.tags.len(), 2);
+    assert_eq!(export.references.len(), 1);
+
+    let note = export.resources.iter().find(|r| r.id == note).unwrap();
+    assert_eq!(note.resource_type, "note");
+    assert_eq!(note.content.as_deref(), Some("Borrowing rules"));
+    assert_eq!(note.tags, vec!["notes"]);
+    assert_eq!(note.metadata["lang"], "en");
+
+    // JSON 文档可以完整地反序列化
+    let json = serde_json::to_string(&export).unwrap();
+    let parsed: LibraryExport = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.resources.len(), export.resources.len());
+    assert_eq!(parsed.references[0].reference_type, "related");
+}
+
+#[tokio::test]
+async fn test_export_library_filters_archived_and_collection_subtree() {
+    let pool = create_test_pool().await;
+    let (user_id, dev, _) = seed_library(&pool).await;
+
+    let without_archived = ExportService::export_library(user_id, None, false, &pool)
+        .await
+        .unwrap();
+    assert_eq!(without_archived.resources.len(), 3);
+    assert!(without_archived.resources.iter().all(|r| !r.is_archived));
+
+    let subtree = ExportService::export_library(user_id, Some(dev), true, &pool)
+        .await
+        .unwrap();
+    let names: Vec<&str> = subtree
+        .collections
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Dev", "Tools"]);
+    assert_eq!(subtree.resources.len(), 3);
+    assert_eq!(subtree.tags.len(), 2);
+    assert_eq!(subtree.references.len(), 1);
+
+    let missing = ExportService::export_library(user_id, Some(999_999), true, &pool).await;
+    assert!(missing.is_err());
+}
+
+#[tokio::test]
+async fn test_export_json_stream_matches_library_export() {
+    let pool = create_test_pool().await;
+    let (user_id, dev, _) = seed_library(&pool).await;
+
+    for collection_id in [None, Some(dev)] {
+        let expected = ExportService::export_library(user_id, collection_id, true, &pool)
+            .await
+            .unwrap();
+
+        // 每页一条记录, 覆盖多页拼接
+        let exporter = LibraryExporter::new(user_id, collection_id, true, &pool)
+            .await
+            .unwrap()
+            .with_page_size(1);
+        let chunks: Vec<String> = ExportService::export_json_stream(exporter, pool.clone())
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(chunks.len() > expected.resources.len());
+        let streamed: LibraryExport = serde_json::from_str(&chunks.concat()).unwrap();
+
+        assert_eq!(streamed.version, EXPORT_FORMAT_VERSION);
+        let mut streamed = serde_json::to_value(streamed).unwrap();
+        let mut expected = serde_json::to_value(expected).unwrap();
+        streamed["exported_at"] = 0.into();
+        expected["exported_at"] = 0.into();
+        assert_eq!(streamed, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_export_file_metadata_and_import_reports_missing_content() {
+    let pool = create_test_pool().await;
+    let (user_id, _, note) = seed_library(&pool).await;
+    let report = create_resource(&pool, user_id, None, "Report", None, "file", None, false).await;
+    sqlx::query(
+        "UPDATE resources SET source = 'report.pdf', mime_type = 'application/pdf' WHERE id = $1",
+    )
+    .bind(report)
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO resource_files (
+            resource_id, storage_backend, storage_key, file_name, content_type, size, checksum
+        )
+        VALUES ($1, 'local', $2, 'report.pdf', 'application/pdf', 1024, 'abc123')
+        "#,
+    )
+    .bind(report)
+    .bind(format!("{}/report", user_id))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let export = ExportService::export_library(user_id, None, true, &pool)
+        .await
+        .unwrap();
+    let file = export
+        .resources
+        .iter()
+        .find(|r| r.id == report)
+        .and_then(|r| r.file.as_ref())
+        .unwrap();
+    assert_eq!(file.file_name, "report.pdf");
+    assert_eq!(file.content_type, "application/pdf");
+    assert_eq!(file.size, 1024);
+    assert_eq!(file.checksum, "abc123");
+    assert_eq!(file.storage_key, format!("{}/report", user_id));
+
+    // 非文件资源不输出 file 字段
+    let json = serde_json::to_value(&export).unwrap();
+    let note_json = json["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["id"] == note)
+        .unwrap();
+    assert!(note_json.get("file").is_none());
+
+    // 导入到另一个账号: 资源记录被恢复, 但不会关联导出方的存储对象
+    let importer = create_test_user(&pool, "importer").await;
+    let result = ImportService::import(
+        importer,
+        ImportFormat::Json,
+        &serde_json::to_string(&export).unwrap(),
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+    let entry = result.entries.iter().find(|e| e.title == "Report").unwrap();
+    assert!(entry.reason.as_deref().unwrap().contains("report.pdf"));
+    assert!(result
+        .entries
+        .iter()
+        .filter(|e| e.title != "Report")
+        .all(|e| e.reason.is_none()));
+
+    let imported_files: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM resource_files WHERE resource_id = $1")
+            .bind(entry.resource_id.unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(imported_files, 0);
+}
+
+#[tokio::test]
+async fn test_render_netscape_exports_links_with_folders() {
+    let pool = create_test_pool().await;
+    let (user_id, _, _) = seed_library(&pool).await;
+
+    let export = ExportService::export_library(user_id, None, true, &pool)
+        .await
+        .unwrap();
+    let html = ExportService::render_netscape(&export);
+    let document = parse_bookmarks(&html);
+
+    // note 资源不会出现在书签文件中
+    assert_eq!(document.bookmarks.len(), 3);
+    assert!(document
+        .folders
+        .contains(&vec!["Dev".to_string(), "Tools".to_string()]));
+    assert!(document.folders.contains(&vec!["Reading".to_string()]));
+
+    let rust = document
+        .bookmarks
+        .iter()
+        .find(|b| b.url == "https://www.rust-lang.org/")
+        .unwrap();
+    assert_eq!(rust.folder_path, vec!["Dev", "Tools"]);
+    assert_eq!(rust.tags, vec!["rust"]);
+
+    let unsorted = document
+        .bookmarks
+        .iter()
+        .find(|b| b.url == "https://example.com/")
+        .unwrap();
+    assert!(unsorted.folder_path.is_empty());
+}
@@ -0,0 +1,317 @@
+//! 文件上传服务
+//!
+//! 上传时先把文件写入存储后端, 再在同一事务中创建 File 资源和 resource_files 记录;
+//! 事务失败时删除刚写入的对象。支持的文档格式在同一事务中登记正文提取任务,
+//! 由后台任务 worker 调用 `extract_text` 提取正文并写入全文索引。
+//! 删除资源时对象登记到 storage_deletions,
+//! 由后台任务 worker 调用 `purge_deleted` 清理。
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use crate::models::{CreateResource, JobKind, ResourceFile, ResourceType, UploadedResource};
+use crate::services::storage::{ByteRange, Storage};
+use crate::services::{IndexerService, JobService, ResourceService};
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::text_extractor::{self, DocumentKind};
+
+const MAX_FILE_NAME_LENGTH: usize = 255;
+/// 每轮清理的对象数
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// 上传的文件内容
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    pub file_name: String,
+    /// 客户端声明的内容类型, 缺失时按扩展名推断
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+pub struct FileService;
+
+impl FileService {
+    /// 保存上传文件并创建 File 资源
+    ///
+    /// resource_data 中的类型、来源和 MIME 类型由上传文件决定
+    pub async fn upload(
+        user_id: i64,
+        mut resource_data: CreateResource,
+        file: UploadedFile,
+        storage: &dyn Storage,
+        db_pool: &SqlitePool,
+    ) -> AppResult<UploadedResource> {
+        if file.data.is_empty() {
+            return Err(AppError::BadRequest("Uploaded file is empty".to_string()));
+        }
+
+        let file_name = sanitize_file_name(&file.file_name);
+        let content_type = file
+            .content_type
+            .filter(|content_type| {
+                !content_type.is_empty() && content_type != "application/octet-stream"
+            })
+            .unwrap_or_else(|| {
+                mime_guess::from_path(&file_name)
+                    .first_or_octet_stream()
+                    .to_string()
+            });
+
+        if resource_data.title.trim().is_empty() {
+            resource_data.title = file_name.clone();
+        }
+        resource_data.resource_type = ResourceType::File.as_str().to_string();
+        resource_data.url = None;
+        resource_data.source = Some(file_name.clone());
+        resource_data.mime_type = Some(content_type.clone());
+        ResourceService::validate_create_resource(&resource_data)?;
+
+        let size = file.data.len() as i64;
+        let checksum = hex::encode(Sha256::digest(&file.data));
+        let storage_key = format!("{}/{}", user_id, uuid::Uuid::new_v4());
+
+        storage.put(&storage_key, file.data, &content_type).await?;
+
+        let result = Self::insert_file_resource(
+            user_id,
+            &resource_data,
+            storage.name(),
+            &storage_key,
+            size,
+            &checksum,
+            db_pool,
+        )
+        .await;
+
+        if result.is_err() {
+            if let Err(e) = storage.delete(&storage_key).await {
+                warn!("删除未登记的上传文件 {} 失败: {}", storage_key, e);
+            }
+        }
+
+        result
+    }
+
+    async fn insert_file_resource(
+        user_id: i64,
+        resource_data: &CreateResource,
+        storage_backend: &str,
+        storage_key: &str,
+        size: i64,
+        checksum: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<UploadedResource> {
+        let mut tx = db_pool.begin().await?;
+
+        let resource =
+            ResourceService::insert_resource(&mut tx, user_id, resource_data, None).await?;
+        if let Some(ref tags) = resource_data.tags {
+            ResourceService::attach_tags(&mut tx, user_id, resource.id, tags).await?;
+        }
+
+        let file = sqlx::query_as::<_, ResourceFile>(
+            r#"
+            INSERT INTO resource_files (
+                resource_id, storage_backend, storage_key, file_name, content_type, size, checksum
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING resource_id, file_name, content_type, size, checksum, created_at,
+                      extracted_at, storage_backend, storage_key
+            "#,
+        )
+        .bind(resource.id)
+        .bind(storage_backend)
+        .bind(storage_key)
+        .bind(resource.source.as_deref().unwrap_or_default())
+        .bind(resource.mime_type.as_deref().unwrap_or_default())
+        .bind(size)
+        .bind(checksum)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        IndexerService::index_resource(&mut tx, resource.id, user_id).await?;
+
+        // 支持的格式由后台任务提取正文后再次索引
+        if DocumentKind::detect(&file.content_type, &file.file_name).is_some() {
+            JobService::enqueue(&mut tx, JobKind::ExtractText, user_id, resource.id).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(UploadedResource { resource, file })
+    }
+
+    /// 获取资源的上传文件信息, 资源不存在或不是上传文件时返回 None
+    pub async fn get_file(
+        user_id: i64,
+        resource_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<ResourceFile>> {
+        let file = sqlx::query_as::<_, ResourceFile>(
+            r#"
+            SELECT f.resource_id, f.file_name, f.content_type, f.size, f.checksum, f.created_at,
+                   f.extracted_at, f.storage_backend, f.storage_key
+            FROM resource_files f
+            JOIN resources r ON r.id = f.resource_id
+            WHERE f.resource_id = $1 AND r.user_id = $2
+            "#,
+        )
+        .bind(resource_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(file)
+    }
+
+    /// 读取文件内容
+    pub async fn read(
+        file: &ResourceFile,
+        range: Option<ByteRange>,
+        storage: &dyn Storage,
+    ) -> AppResult<Vec<u8>> {
+        if file.storage_backend != storage.name() {
+            return Err(AppError::Internal(format!(
+                "File is stored in {} storage, but {} storage is configured",
+                file.storage_backend,
+                storage.name()
+            )));
+        }
+
+        storage.get(&file.storage_key, range).await
+    }
+
+    /// 提取上传文件的正文并更新全文索引
+    ///
+    /// 格式不支持或文件无法解析时只记录日志, 不作为任务失败重试
+    pub async fn extract_text(
+        user_id: i64,
+        resource_id: i64,
+        storage: &dyn Storage,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        // 任务执行前资源可能已被删除
+        let Some(file) = Self::get_file(user_id, resource_id, db_pool).await? else {
+            return Ok(());
+        };
+        let Some(kind) = DocumentKind::detect(&file.content_type, &file.file_name) else {
+            return Ok(());
+        };
+
+        let data = Self::read(&file, None, storage).await?;
+        let text =
+            match tokio::task::spawn_blocking(move || text_extractor::extract_text(kind, &data))
+                .await
+            {
+                Ok(Ok(text)) => text,
+                Ok(Err(e)) => {
+                    warn!("提取文件 {} 的正文失败: {}", resource_id, e);
+                    return Ok(());
+                }
+                // 解析器遇到异常文件时可能 panic
+                Err(e) => {
+                    warn!("提取文件 {} 的正文时解析器异常退出: {}", resource_id, e);
+                    return Ok(());
+                }
+            };
+
+        let mut tx = db_pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE resource_files
+            SET extracted_text = $1, extracted_at = CAST(strftime('%s', 'now') AS INTEGER)
+            WHERE resource_id = $2
+            "#,
+        )
+        .bind(&text)
+        .bind(resource_id)
+        .execute(&mut *tx)
+        .await?;
+
+        IndexerService::index_resource(&mut tx, resource_id, user_id).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 在删除资源的事务中登记待删除的存储对象
+    pub async fn queue_deletion(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        resource_id: i64,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO storage_deletions (storage_backend, storage_key)
+            SELECT f.storage_backend, f.storage_key
+            FROM resource_files f
+            JOIN resources r ON r.id = f.resource_id
+            WHERE f.resource_id = $1 AND r.user_id = $2
+            "#,
+        )
+        .bind(resource_id)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 删除已登记的存储对象, 返回删除数量
+    ///
+    /// 只处理当前后端的对象, 删除失败的记录保留到下一轮
+    pub async fn purge_deleted(storage: &dyn Storage, db_pool: &SqlitePool) -> AppResult<usize> {
+        let pending: Vec<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT id, storage_key FROM storage_deletions
+            WHERE storage_backend = $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(storage.name())
+        .bind(PURGE_BATCH_SIZE)
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut purged = 0;
+        for (id, storage_key) in pending {
+            if let Err(e) = storage.delete(&storage_key).await {
+                warn!("删除存储对象 {} 失败: {}", storage_key, e);
+                continue;
+            }
+
+            sqlx::query("DELETE FROM storage_deletions WHERE id = $1")
+                .bind(id)
+                .execute(db_pool)
+                .await?;
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+}
+
+/// 只保留文件名部分 (部分浏览器会上传完整路径), 并限制长度
+fn sanitize_file_name(raw: &str) -> String {
+    let name = raw
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+
+    if name.is_empty() {
+        return "file".to_string();
+    }
+
+    let mut end = name.len().min(MAX_FILE_NAME_LENGTH);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    name[..end].to_string()
+}
@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::put,
+    Router,
+};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+use crate::config::{ArchiveConfig, S3Config};
+use crate::models::{CreateResource, ResourceQuery};
+use crate::services::file_service::{FileService, UploadedFile};
+use crate::services::resource_service::ResourceService;
+use crate::services::storage::{ByteRange, LocalStorage, S3Storage, Storage};
+use crate::services::test_support::{
+    create_test_pool, create_test_user, spawn_stub_server, test_worker,
+};
+use crate::utils::error::AppError;
+
+fn file_resource(title: &str) -> CreateResource {
+    CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: Some(vec!["papers".to_string()]),
+        is_favorite: None,
+        is_private: None,
+        resource_type: "file".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    }
+}
+
+fn uploaded_file(file_name: &str, data: &[u8]) -> UploadedFile {
+    UploadedFile {
+        file_name: file_name.to_string(),
+        content_type: None,
+        data: data.to_vec(),
+    }
+}
+
+async fn search_ids(pool: &SqlitePool, user_id: i64, term: &str) -> Vec<i64> {
+    let query = ResourceQuery {
+        search: Some(term.to_string()),
+        ..Default::default()
+    };
+
+    ResourceService::get_resources(user_id, query, pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|resource| resource.resource.id)
+        .collect()
+}
+
+/// 生成只有一页文本的最小 PDF 文件
+fn minimal_pdf(text: &str) -> Vec<u8> {
+    let stream = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+         /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            stream.len(),
+            stream
+        ),
+    ];
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+    }
+
+    let xref_offset = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// 模拟 S3 兼容服务: 只检查签名格式和负载哈希, 对象保存在内存中
+async fn spawn_s3_stub() -> (String, Objects) {
+    async fn put_object(
+        State(objects): State<Objects>,
+        Path((bucket, key)): Path<(String, String)>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let payload_hash = headers
+            .get("x-amz-content-sha256")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !is_signed(&headers) || payload_hash != hex::encode(Sha256::digest(&body)) {
+            return StatusCode::FORBIDDEN;
+        }
+        objects
+            .lock()
+            .unwrap()
+            .insert(format!("{}/{}", bucket, key), body.to_vec());
+        StatusCode::OK
+    }
+
+    async fn get_object(
+        State(objects): State<Objects>,
+        Path((bucket, key)): Path<(String, String)>,
+        headers: HeaderMap,
+    ) -> axum::response::Response {
+        if !is_signed(&headers) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        let Some(data) = objects
+            .lock()
+            .unwrap()
+            .get(&format!("{}/{}", bucket, key))
+            .cloned()
+        else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+
+        let range = headers
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("bytes="))
+            .and_then(|value| value.split_once('-'))
+            .map(|(start, end)| {
+                (
+                    start.parse::<usize>().unwrap(),
+                    end.parse::<usize>().unwrap(),
+                )
+            });
+        match range {
+            Some((start, end)) => {
+                (StatusCode::PARTIAL_CONTENT, data[start..=end].to_vec()).into_response()
+            }
+            None => data.into_response(),
+        }
+    }
+
+    async fn delete_object(
+        State(objects): State<Objects>,
+        Path((bucket, key)): Path<(String, String)>,
+        headers: HeaderMap,
+    ) -> StatusCode {
+        if !is_signed(&headers) {
+            return StatusCode::FORBIDDEN;
+        }
+        objects
+            .lock()
+            .unwrap()
+            .remove(&format!("{}/{}", bucket, key));
+        StatusCode::NO_CONTENT
+    }
+
+    fn is_signed(headers: &HeaderMap) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 Credential=minio/"))
+    }
+
+    let objects = Objects::default();
+    let app = Router::new()
+        .route(
+            "/{bucket}/{*key}",
+            put(put_object).get(get_object).delete(delete_object),
+        )
+        .with_state(objects.clone());
+
+    (spawn_stub_server(app).await, objects)
+}
+
+#[tokio::test]
+async fn test_upload_and_read_local_file() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let dir = tempfile::tempdir().unwrap();
+    let storage = LocalStorage::new(dir.path());
+    let data = b"%PDF-1.4 quarterly report";
+
+    let uploaded = FileService::upload(
+        user_id,
+        file_resource(""),
+        uploaded_file("C:\\Users\\me\\report.pdf", data),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    // 标题默认使用文件名, 内容类型按扩展名推断
+    assert_eq!(uploaded.resource.title, "report.pdf");
+    assert_eq!(uploaded.resource.resource_type, "file");
+    assert_eq!(uploaded.resource.source.as_deref(), Some("report.pdf"));
+    assert_eq!(
+        uploaded.resource.mime_type.as_deref(),
+        Some("application/pdf")
+    );
+    assert_eq!(uploaded.file.file_name, "report.pdf");
+    assert_eq!(uploaded.file.content_type, "application/pdf");
+    assert_eq!(uploaded.file.size, data.len() as i64);
+    assert_eq!(uploaded.file.checksum, hex::encode(Sha256::digest(data)));
+    assert_eq!(uploaded.file.storage_backend, "local");
+    assert!(dir.path().join(&uploaded.file.storage_key).exists());
+
+    let file = FileService::get_file(user_id, uploaded.resource.id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        FileService::read(&file, None, &storage).await.unwrap(),
+        data
+    );
+    assert_eq!(
+        FileService::read(&file, Some(ByteRange { start: 0, end: 3 }), &storage)
+            .await
+            .unwrap(),
+        b"%PDF"
+    );
+
+    // 其他用户无法访问
+    assert!(
+        FileService::get_file(user_id + 1, uploaded.resource.id, &pool)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_deleted_resource_file_is_purged() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let dir = tempfile::tempdir().unwrap();
+    let storage = LocalStorage::new(dir.path());
+
+    let uploaded = FileService::upload(
+        user_id,
+        file_resource("Notes"),
+        uploaded_file("notes.txt", b"plain notes"),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    let path = dir.path().join(&uploaded.file.storage_key);
+    assert_eq!(uploaded.file.content_type, "text/plain");
+
+    // 其他用户删除失败时不会登记对象
+    assert!(
+        !ResourceService::delete_resource(user_id + 1, uploaded.resource.id, &pool)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        FileService::purge_deleted(&storage, &pool).await.unwrap(),
+        0
+    );
+    assert!(path.exists());
+
+    assert!(
+        ResourceService::delete_resource(user_id, uploaded.resource.id, &pool)
+            .await
+            .unwrap()
+    );
+    assert!(path.exists());
+    assert_eq!(
+        FileService::purge_deleted(&storage, &pool).await.unwrap(),
+        1
+    );
+    assert!(!path.exists());
+    assert_eq!(
+        FileService::purge_deleted(&storage, &pool).await.unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_upload_rejects_invalid_files() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let dir = tempfile::tempdir().unwrap();
+    let storage = LocalStorage::new(dir.path());
+
+    let result = FileService::upload(
+        user_id,
+        file_resource("Empty"),
+        uploaded_file("empty.txt", b""),
+        &storage,
+        &pool,
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    // 数据库写入失败时删除已写入的对象
+    let mut resource_data = file_resource("Missing collection");
+    resource_data.collection_id = Some(9999);
+    let result = FileService::upload(
+        user_id,
+        resource_data,
+        uploaded_file("orphan.txt", b"orphan"),
+        &storage,
+        &pool,
+    )
+    .await;
+    assert!(result.is_err());
+    assert!(std::fs::read_dir(dir.path().join(user_id.to_string()))
+        .map(|entries| entries.count() == 0)
+        .unwrap_or(true));
+}
+
+#[tokio::test]
+async fn test_upload_to_s3_compatible_storage() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let (endpoint, objects) = spawn_s3_stub().await;
+    let storage = S3Storage::new(S3Config {
+        endpoint,
+        bucket: "files".to_string(),
+        access_key_id: "minio".to_string(),
+        secret_access_key: "minio-secret".to_string(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let mut file = uploaded_file("photo.png", b"\x89PNG fake image");
+    file.content_type = Some("image/png".to_string());
+    let uploaded = FileService::upload(user_id, file_resource("Photo"), file, &storage, &pool)
+        .await
+        .unwrap();
+    assert_eq!(uploaded.file.storage_backend, "s3");
+    assert!(objects
+        .lock()
+        .unwrap()
+        .contains_key(&format!("files/{}", uploaded.file.storage_key)));
+
+    assert_eq!(
+        FileService::read(&uploaded.file, None, &storage)
+            .await
+            .unwrap(),
+        b"\x89PNG fake image"
+    );
+    assert_eq!(
+        FileService::read(
+            &uploaded.file,
+            Some(ByteRange { start: 1, end: 3 }),
+            &storage
+        )
+        .await
+        .unwrap(),
+        b"PNG"
+    );
+
+    // 本地存储无法读取 S3 中的文件
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        FileService::read(&uploaded.file, None, &LocalStorage::new(dir.path())).await,
+        Err(AppError::Internal(_))
+    ));
+
+    ResourceService::delete_resource(user_id, uploaded.resource.id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        FileService::purge_deleted(&storage, &pool).await.unwrap(),
+        1
+    );
+    assert!(objects.lock().unwrap().is_empty());
+    assert!(matches!(
+        storage.get(&uploaded.file.storage_key, None).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_uploaded_text_is_extracted_and_indexed() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let dir = tempfile::tempdir().unwrap();
+    let storage = LocalStorage::new(dir.path());
+    let worker = test_worker(
+        &pool,
+        1,
+        ArchiveConfig::default(),
+        Arc::new(LocalStorage::new(dir.path())),
+    );
+
+    let markdown = FileService::upload(
+        user_id,
+        file_resource("Notes"),
+        uploaded_file("notes.md", b"# Heading\n\nThe quokkanomics chapter"),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    let html = FileService::upload(
+        user_id,
+        file_resource("Page"),
+        uploaded_file(
+            "page.html",
+            b"<html><script>var hidden = 'scriptonly';</script><body><p>Saved marsupialist page</p></body></html>",
+        ),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    let pdf = FileService::upload(
+        user_id,
+        file_resource("Paper"),
+        uploaded_file("paper.pdf", &minimal_pdf("Wombatology findings")),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(markdown.file.extracted_at.is_none());
+
+    // 正文在后台任务中提取
+    assert!(search_ids(&pool, user_id, "quokkanomics").await.is_empty());
+    for _ in 0..3 {
+        assert!(worker.process_next().await.unwrap());
+    }
+    assert!(!worker.process_next().await.unwrap());
+
+    assert_eq!(
+        search_ids(&pool, user_id, "quokkanomics").await,
+        vec![markdown.resource.id]
+    );
+    assert_eq!(
+        search_ids(&pool, user_id, "marsupialist").await,
+        vec![html.resource.id]
+    );
+    assert!(search_ids(&pool, user_id, "scriptonly").await.is_empty());
+    assert_eq!(
+        search_ids(&pool, user_id, "wombatology").await,
+        vec![pdf.resource.id]
+    );
+
+    let file = FileService::get_file(user_id, pdf.resource.id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(file.extracted_at.is_some());
+
+    // 修改资源后重新索引仍保留提取的正文
+    let update = serde_json::from_value(serde_json::json!({ "title": "Renamed paper" })).unwrap();
+    ResourceService::update_resource(user_id, pdf.resource.id, update, &pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        search_ids(&pool, user_id, "wombatology").await,
+        vec![pdf.resource.id]
+    );
+}
+
+#[tokio::test]
+async fn test_unsupported_or_corrupt_files_are_skipped() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "uploader").await;
+    let dir = tempfile::tempdir().unwrap();
+    let storage = LocalStorage::new(dir.path());
+    let worker = test_worker(
+        &pool,
+        1,
+        ArchiveConfig::default(),
+        Arc::new(LocalStorage::new(dir.path())),
+    );
+
+    // 不支持的格式不会登记提取任务
+    FileService::upload(
+        user_id,
+        file_resource("Photo"),
+        uploaded_file("photo.png", b"\x89PNG fake image"),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(!worker.process_next().await.unwrap());
+
+    // 无法解析的文件只记录日志, 任务不会失败重试
+    let corrupt = FileService::upload(
+        user_id,
+        file_resource("Broken"),
+        uploaded_file("broken.docx", b"not a zip archive"),
+        &storage,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(worker.process_next().await.unwrap());
+    assert!(!worker.process_next().await.unwrap());
+
+    let failed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM background_jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(failed, 0);
+    assert!(FileService::get_file(user_id, corrupt.resource.id, &pool)
+        .await
+        .unwrap()
+        .unwrap()
+        .extracted_at
+        .is_none());
+}
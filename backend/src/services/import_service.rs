@@ -0,0 +1,928 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::SqlitePool;
+
+use crate::config::AuthConfig;
+use crate::models::{
+    ConflictPolicy, CreateResource, ExportedCollection, ExportedResource, ExportedTag,
+    ImportEntryResult, ImportEntryStatus, ImportFormat, ImportOptions, ImportResult, JobKind,
+    LibraryExport, EXPORT_FORMAT_VERSION,
+};
+use crate::services::importers::{importer_for, ImportedBookmark, ImportedDocument};
+use crate::services::{AuthService, IndexerService, JobService, ResourceService};
+use crate::utils::error::{AppError, AppResult};
+
+// 单次导入的书签数量上限
+const MAX_IMPORT_ENTRIES: usize = 10_000;
+
+/// 收藏夹/标签在导入时的处理结果
+enum RestoreOutcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+pub struct ImportService;
+
+impl ImportService {
+    /// 按指定格式导入资源
+    ///
+    /// JSON 文档走无损恢复流程, 其余格式由 importers 中注册的适配器解析后统一写入
+    pub async fn import(
+        user_id: i64,
+        format: ImportFormat,
+        content: &str,
+        options: &ImportOptions,
+        auth_config: &AuthConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<ImportResult> {
+        if format == ImportFormat::Json {
+            return Self::import_json(user_id, content, options, auth_config, db_pool).await;
+        }
+
+        let importer = importer_for(format).ok_or_else(|| {
+            AppError::BadRequest(format!("No importer registered for format {:?}", format))
+        })?;
+        let document = importer.parse(content)?;
+
+        Self::import_document(user_id, document, options, db_pool).await
+    }
+
+    /// 导入适配器解析出的书签
+    ///
+    /// - 文件夹映射为 collections, 通过 parent_id 保留层级 (同名收藏夹会被复用)
+    /// - 标签映射为 tags/resource_tags
+    /// - 书签作为 link 资源创建, 与 ResourceService::create_resource 使用相同的验证
+    /// - 来源提供的已读/收藏/归档/私有状态和创建时间会被保留
+    /// - 已存在或文件内重复的 URL 会被跳过
+    /// - 所有写入和 FTS 索引在同一事务中完成
+    pub async fn import_document(
+        user_id: i64,
+        document: ImportedDocument,
+        options: &ImportOptions,
+        db_pool: &SqlitePool,
+    ) -> AppResult<ImportResult> {
+        let collection_id = options.collection_id;
+
+        if document.bookmarks.is_empty() && document.folders.is_empty() {
+            return Err(AppError::BadRequest(
+                "No bookmarks found in import file".to_string(),
+            ));
+        }
+
+        if document.bookmarks.len() > MAX_IMPORT_ENTRIES {
+            return Err(AppError::BadRequest(format!(
+                "Import file exceeds maximum of {} bookmarks",
+                MAX_IMPORT_ENTRIES
+            )));
+        }
+
+        let mut tx = db_pool.begin().await?;
+        Self::verify_collection(&mut tx, user_id, collection_id).await?;
+
+        let mut result = ImportResult {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+
+        // 1. 创建文件夹对应的收藏夹 (父文件夹总是先于子文件夹出现)
+        let mut folder_ids: HashMap<Vec<String>, i64> = HashMap::new();
+        for path in &document.folders {
+            let Some((name, parent_path)) = path.split_last() else {
+                continue;
+            };
+            if name.is_empty() || folder_ids.contains_key(path) {
+                continue;
+            }
+
+            let parent_id = if parent_path.is_empty() {
+                collection_id
+            } else {
+                folder_ids.get(parent_path).copied()
+            };
+
+            let (id, created) =
+                Self::find_or_create_collection(&mut tx, user_id, name, parent_id).await?;
+            if created {
+                result.collections_created += 1;
+            }
+            folder_ids.insert(path.clone(), id);
+        }
+
+        // 2. 创建书签资源
+        let mut seen_urls: HashSet<String> = HashSet::new();
+        let mut imported_ids = Vec::new();
+
+        for (index, bookmark) in document.bookmarks.into_iter().enumerate() {
+            let target_collection = if bookmark.folder_path.is_empty() {
+                collection_id
+            } else {
+                folder_ids.get(&bookmark.folder_path).copied()
+            };
+
+            let entry = Self::import_bookmark(
+                &mut tx,
+                user_id,
+                index,
+                bookmark,
+                target_collection,
+                &mut seen_urls,
+                &mut result,
+            )
+            .await?;
+
+            if let Some(resource_id) = entry.resource_id {
+                imported_ids.push(resource_id);
+            }
+            result.record(entry);
+        }
+
+        // 3. 一次性建立 FTS 索引
+        IndexerService::index_resources(&mut tx, &imported_ids, user_id).await?;
+
+        // 4. 由后台任务补全页面元数据
+        for resource_id in &imported_ids {
+            JobService::enqueue(&mut tx, JobKind::FetchMetadata, user_id, *resource_id).await?;
+        }
+
+        Self::finish(tx, options.dry_run).await?;
+
+        Ok(result)
+    }
+
+    /// 导入本系统导出的 JSON 文档
+    ///
+    /// - 收藏夹、标签、资源和引用关系都会重新分配 id, 文档中的原始 id 只用于建立映射
+    /// - 同名收藏夹/标签和重复 URL 按 conflict_policy 处理
+    /// - 无 URL 的资源 (笔记、代码片段等) 不做重复检测, 总是作为新资源导入
+    /// - 所有写入和 FTS 索引在同一事务中完成, dry_run 时最终回滚
+    /// - 恢复公开收藏夹与直接公开收藏夹一样, 需要满足邮箱验证要求
+    /// - 文档不包含上传文件的内容, file 资源只恢复资源记录, 并在导入结果中提示重新上传
+    pub async fn import_json(
+        user_id: i64,
+        content: &str,
+        options: &ImportOptions,
+        auth_config: &AuthConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<ImportResult> {
+        let document: LibraryExport = serde_json::from_str(content)
+            .map_err(|e| AppError::BadRequest(format!("Invalid export document: {}", e)))?;
+
+        if document.version == 0 || document.version > EXPORT_FORMAT_VERSION {
+            return Err(AppError::BadRequest(format!(
+                "Unsupported export format version: {}",
+                document.version
+            )));
+        }
+
+        if document.resources.len() > MAX_IMPORT_ENTRIES {
+            return Err(AppError::BadRequest(format!(
+                "Import file exceeds maximum of {} resources",
+                MAX_IMPORT_ENTRIES
+            )));
+        }
+
+        if document
+            .collections
+            .iter()
+            .any(|collection| collection.is_public)
+        {
+            AuthService::ensure_email_verified(user_id, auth_config, db_pool).await?;
+        }
+
+        let policy = options.conflict_policy;
+        let mut tx = db_pool.begin().await?;
+        Self::verify_collection(&mut tx, user_id, options.collection_id).await?;
+
+        let mut result = ImportResult {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+
+        // 1. 收藏夹: 先创建/匹配所有收藏夹, 再按映射后的 id 恢复层级
+        let mut collection_map: HashMap<i64, i64> = HashMap::new();
+        let mut reparent: Vec<(i64, Option<i64>)> = Vec::new();
+        for collection in &document.collections {
+            let (id, outcome) =
+                Self::restore_collection(&mut tx, user_id, collection, policy).await?;
+            match outcome {
+                RestoreOutcome::Created => {
+                    result.collections_created += 1;
+                    reparent.push((id, collection.parent_id));
+                }
+                RestoreOutcome::Updated => {
+                    result.collections_updated += 1;
+                    if policy == ConflictPolicy::Overwrite {
+                        reparent.push((id, collection.parent_id));
+                    }
+                }
+                RestoreOutcome::Unchanged => {}
+            }
+            collection_map.insert(collection.id, id);
+        }
+
+        for (id, old_parent_id) in reparent {
+            let parent_id = match old_parent_id {
+                Some(old_parent_id) => collection_map.get(&old_parent_id).copied(),
+                None => options.collection_id,
+            }
+            .filter(|parent_id| *parent_id != id);
+
+            sqlx::query("UPDATE collections SET parent_id = $1 WHERE id = $2 AND user_id = $3")
+                .bind(parent_id)
+                .bind(id)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // 2. 标签: 按名称映射到新 id
+        let mut tag_map: HashMap<String, i64> = HashMap::new();
+        for tag in &document.tags {
+            let (id, outcome) = Self::restore_tag(&mut tx, user_id, tag, policy).await?;
+            match outcome {
+                RestoreOutcome::Created => result.tags_created += 1,
+                RestoreOutcome::Updated => result.tags_updated += 1,
+                RestoreOutcome::Unchanged => {}
+            }
+            tag_map.insert(tag.name.clone(), id);
+        }
+
+        // 3. 资源
+        let mut resource_map: HashMap<i64, i64> = HashMap::new();
+        let mut touched_ids = Vec::new();
+
+        for (index, resource) in document.resources.iter().enumerate() {
+            let collection_id = resource
+                .collection_id
+                .and_then(|id| collection_map.get(&id).copied())
+                .or(options.collection_id);
+
+            let entry = Self::restore_resource(
+                &mut tx,
+                user_id,
+                index,
+                resource,
+                collection_id,
+                policy,
+                &mut tag_map,
+                &mut result,
+            )
+            .await?;
+
+            if let Some(resource_id) = entry.resource_id {
+                resource_map.insert(resource.id, resource_id);
+                if matches!(
+                    entry.status,
+                    ImportEntryStatus::Imported | ImportEntryStatus::Updated
+                ) {
+                    touched_ids.push(resource_id);
+                }
+            }
+            result.record(entry);
+        }
+
+        // 4. 引用关系: 两端都有映射时才恢复
+        for reference in &document.references {
+            let (Some(&source_id), Some(&target_id)) = (
+                resource_map.get(&reference.source_id),
+                resource_map.get(&reference.target_id),
+            ) else {
+                continue;
+            };
+            if source_id == target_id {
+                continue;
+            }
+
+            let inserted = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO resource_references (source_id, target_id, type, created_at)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(source_id)
+            .bind(target_id)
+            .bind(&reference.reference_type)
+            .bind(reference.created_at)
+            .execute(&mut *tx)
+            .await?;
+            result.references_created += inserted.rows_affected() as usize;
+        }
+
+        // 5. 一次性建立 FTS 索引
+        IndexerService::index_resources(&mut tx, &touched_ids, user_id).await?;
+
+        Self::finish(tx, options.dry_run).await?;
+
+        Ok(result)
+    }
+
+    /// 导入单个书签, 验证失败或重复时返回对应状态而不是中断整个导入
+    async fn import_bookmark(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        index: usize,
+        bookmark: ImportedBookmark,
+        collection_id: Option<i64>,
+        seen_urls: &mut HashSet<String>,
+        result: &mut ImportResult,
+    ) -> AppResult<ImportEntryResult> {
+        let ImportedBookmark {
+            title,
+            url,
+            description,
+            tags,
+            created_at,
+            is_read,
+            is_favorite,
+            is_archived,
+            is_private,
+            ..
+        } = bookmark;
+
+        // 没有标题的书签使用 URL 作为标题
+        let title = if title.is_empty() { url.clone() } else { title };
+
+        let mut entry = ImportEntryResult {
+            index,
+            title: title.clone(),
+            url: Some(url.clone()),
+            status: ImportEntryStatus::Failed,
+            resource_id: None,
+            reason: None,
+        };
+
+        let resource_data = CreateResource {
+            title,
+            url: Some(url.clone()),
+            description,
+            collection_id,
+            tags: if tags.is_empty() { None } else { Some(tags) },
+            is_favorite,
+            is_private,
+            resource_type: "link".to_string(),
+            content: None,
+            source: None,
+            mime_type: None,
+        };
+
+        if let Err(err) = ResourceService::validate_create_resource(&resource_data) {
+            entry.reason = Some(err.to_string());
+            return Ok(entry);
+        }
+
+        // 重复检测: 文件内重复或数据库中已存在
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM resources WHERE user_id = $1 AND url = $2)",
+        )
+        .bind(user_id)
+        .bind(&url)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if exists || !seen_urls.insert(url) {
+            entry.status = ImportEntryStatus::Skipped;
+            entry.reason = Some("Duplicate URL".to_string());
+            return Ok(entry);
+        }
+
+        let resource =
+            ResourceService::insert_resource(tx, user_id, &resource_data, created_at).await?;
+
+        // CreateResource 不包含阅读/归档状态, 由来源提供时单独写入
+        if is_read.is_some() || is_archived.is_some() {
+            sqlx::query(
+                r#"
+                UPDATE resources
+                SET is_read = COALESCE($1, is_read), is_archived = COALESCE($2, is_archived)
+                WHERE id = $3
+                "#,
+            )
+            .bind(is_read)
+            .bind(is_archived)
+            .bind(resource.id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if let Some(ref tags) = resource_data.tags {
+            result.tags_created +=
+                ResourceService::attach_tags(tx, user_id, resource.id, tags).await?;
+        }
+
+        entry.status = ImportEntryStatus::Imported;
+        entry.resource_id = Some(resource.id);
+        Ok(entry)
+    }
+
+    /// 提交事务, dry_run 时回滚
+    async fn finish(tx: sqlx::Transaction<'_, sqlx::Sqlite>, dry_run: bool) -> AppResult<()> {
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+
+    /// 验证目标收藏夹归属
+    async fn verify_collection(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        collection_id: Option<i64>,
+    ) -> AppResult<()> {
+        let Some(collection_id) = collection_id else {
+            return Ok(());
+        };
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM collections WHERE id = $1 AND user_id = $2)",
+        )
+        .bind(collection_id)
+        .bind(user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if !exists {
+            return Err(AppError::NotFound("Collection not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// 恢复单个收藏夹 (不处理 parent_id)
+    async fn restore_collection(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        collection: &ExportedCollection,
+        policy: ConflictPolicy,
+    ) -> AppResult<(i64, RestoreOutcome)> {
+        let existing: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM collections WHERE user_id = $1 AND name = $2")
+                .bind(user_id)
+                .bind(&collection.name)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+        let name = match (existing, policy) {
+            (None, _) => collection.name.clone(),
+            (Some(id), ConflictPolicy::Skip) => return Ok((id, RestoreOutcome::Unchanged)),
+            (Some(id), ConflictPolicy::Merge) => {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE collections
+                    SET description = $1, updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $2 AND description IS NULL AND $1 IS NOT NULL
+                    "#,
+                )
+                .bind(&collection.description)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+
+                let outcome = if updated.rows_affected() > 0 {
+                    RestoreOutcome::Updated
+                } else {
+                    RestoreOutcome::Unchanged
+                };
+                return Ok((id, outcome));
+            }
+            (Some(id), ConflictPolicy::Overwrite) => {
+                sqlx::query(
+                    r#"
+                    UPDATE collections
+                    SET description = $1, color = COALESCE($2, color), icon = COALESCE($3, icon),
+                        sort_order = $4, is_public = $5,
+                        updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $6
+                    "#,
+                )
+                .bind(&collection.description)
+                .bind(&collection.color)
+                .bind(&collection.icon)
+                .bind(collection.sort_order)
+                .bind(collection.is_public)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+                return Ok((id, RestoreOutcome::Updated));
+            }
+            (Some(_), ConflictPolicy::Rename) => {
+                Self::unique_name(tx, "collections", user_id, &collection.name).await?
+            }
+        };
+
+        // 每个用户只有一个默认收藏夹, 已有默认收藏夹时导入的收藏夹不再作为默认
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO collections (user_id, name, description, color, icon, sort_order,
+                                     is_default, is_public, created_at, updated_at)
+            VALUES ($1, $2, $3, COALESCE($4, '#3b82f6'), COALESCE($5, 'folder'), $6,
+                    $7 AND NOT EXISTS (
+                        SELECT 1 FROM collections WHERE user_id = $1 AND is_default = TRUE
+                    ),
+                    $8, $9, $10)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(&collection.description)
+        .bind(&collection.color)
+        .bind(&collection.icon)
+        .bind(collection.sort_order)
+        .bind(collection.is_default)
+        .bind(collection.is_public)
+        .bind(collection.created_at)
+        .bind(collection.updated_at)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok((id, RestoreOutcome::Created))
+    }
+
+    /// 恢复单个标签
+    async fn restore_tag(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        tag: &ExportedTag,
+        policy: ConflictPolicy,
+    ) -> AppResult<(i64, RestoreOutcome)> {
+        let existing: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM tags WHERE user_id = $1 AND name = $2")
+                .bind(user_id)
+                .bind(&tag.name)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+        let name = match (existing, policy) {
+            (None, _) => tag.name.clone(),
+            (Some(id), ConflictPolicy::Skip) => return Ok((id, RestoreOutcome::Unchanged)),
+            (Some(id), ConflictPolicy::Merge) => {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE tags
+                    SET description = $1, updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $2 AND description IS NULL AND $1 IS NOT NULL
+                    "#,
+                )
+                .bind(&tag.description)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+
+                let outcome = if updated.rows_affected() > 0 {
+                    RestoreOutcome::Updated
+                } else {
+                    RestoreOutcome::Unchanged
+                };
+                return Ok((id, outcome));
+            }
+            (Some(id), ConflictPolicy::Overwrite) => {
+                sqlx::query(
+                    r#"
+                    UPDATE tags
+                    SET color = COALESCE($1, color), description = $2,
+                        updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $3
+                    "#,
+                )
+                .bind(&tag.color)
+                .bind(&tag.description)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+                return Ok((id, RestoreOutcome::Updated));
+            }
+            (Some(_), ConflictPolicy::Rename) => {
+                Self::unique_name(tx, "tags", user_id, &tag.name).await?
+            }
+        };
+
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO tags (user_id, name, color, description, created_at, updated_at)
+            VALUES ($1, $2, COALESCE($3, '#64748b'), $4, $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(&tag.color)
+        .bind(&tag.description)
+        .bind(tag.created_at)
+        .bind(tag.updated_at)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok((id, RestoreOutcome::Created))
+    }
+
+    /// 恢复单个资源
+    ///
+    /// 重复 URL 的检测也会命中本次导入中先写入的资源, 因此文件内的重复项同样按策略处理
+    #[allow(clippy::too_many_arguments)]
+    async fn restore_resource(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        index: usize,
+        resource: &ExportedResource,
+        collection_id: Option<i64>,
+        policy: ConflictPolicy,
+        tag_map: &mut HashMap<String, i64>,
+        result: &mut ImportResult,
+    ) -> AppResult<ImportEntryResult> {
+        let mut entry = ImportEntryResult {
+            index,
+            title: resource.title.clone(),
+            url: resource.url.clone(),
+            status: ImportEntryStatus::Failed,
+            resource_id: None,
+            reason: None,
+        };
+
+        let resource_data = CreateResource {
+            title: resource.title.clone(),
+            url: resource.url.clone(),
+            description: resource.description.clone(),
+            collection_id,
+            tags: None,
+            is_favorite: Some(resource.is_favorite),
+            is_private: Some(resource.is_private),
+            resource_type: resource.resource_type.clone(),
+            content: resource.content.clone(),
+            source: resource.source.clone(),
+            mime_type: resource.mime_type.clone(),
+        };
+
+        if let Err(err) = ResourceService::validate_create_resource(&resource_data) {
+            entry.reason = Some(err.to_string());
+            return Ok(entry);
+        }
+
+        let existing: Option<i64> =
+            match resource.url.as_deref().filter(|url| !url.is_empty()) {
+                Some(url) => sqlx::query_scalar(
+                    "SELECT id FROM resources WHERE user_id = $1 AND url = $2 ORDER BY id LIMIT 1",
+                )
+                .bind(user_id)
+                .bind(url)
+                .fetch_optional(&mut **tx)
+                .await?,
+                None => None,
+            };
+
+        let metadata = if resource.metadata.is_null() {
+            "{}".to_string()
+        } else {
+            resource.metadata.to_string()
+        };
+
+        let (resource_id, status) = match (existing, policy) {
+            (Some(id), ConflictPolicy::Skip) => {
+                // 保留映射, 使引用关系仍能指向已有资源
+                entry.status = ImportEntryStatus::Skipped;
+                entry.resource_id = Some(id);
+                entry.reason = Some("Duplicate URL".to_string());
+                return Ok(entry);
+            }
+            (Some(id), ConflictPolicy::Merge) => {
+                // 只补充为空的字段, 标记位取并集, metadata 中已有的键优先
+                sqlx::query(
+                    r#"
+                    UPDATE resources
+                    SET description = COALESCE(description, $1),
+                        favicon_url = COALESCE(favicon_url, $2),
+                        screenshot_url = COALESCE(screenshot_url, $3),
+                        thumbnail_url = COALESCE(thumbnail_url, $4),
+                        content = COALESCE(content, $5),
+                        source = COALESCE(source, $6),
+                        mime_type = COALESCE(mime_type, $7),
+                        collection_id = COALESCE(collection_id, $8),
+                        is_favorite = is_favorite OR $9,
+                        is_read = is_read OR $10,
+                        visit_count = MAX(visit_count, $11),
+                        last_visited = MAX(COALESCE(last_visited, $12), COALESCE($12, last_visited)),
+                        metadata = json_patch($13, COALESCE(metadata, '{}')),
+                        updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $14
+                    "#,
+                )
+                .bind(&resource.description)
+                .bind(&resource.favicon_url)
+                .bind(&resource.screenshot_url)
+                .bind(&resource.thumbnail_url)
+                .bind(&resource.content)
+                .bind(&resource.source)
+                .bind(&resource.mime_type)
+                .bind(collection_id)
+                .bind(resource.is_favorite)
+                .bind(resource.is_read)
+                .bind(resource.visit_count)
+                .bind(resource.last_visited)
+                .bind(&metadata)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+                (id, ImportEntryStatus::Updated)
+            }
+            (Some(id), ConflictPolicy::Overwrite) => {
+                sqlx::query(
+                    r#"
+                    UPDATE resources
+                    SET collection_id = $1, title = $2, description = $3, favicon_url = $4,
+                        screenshot_url = $5, thumbnail_url = $6, is_favorite = $7,
+                        is_archived = $8, is_private = $9, is_read = $10, visit_count = $11,
+                        last_visited = $12, metadata = $13, type = $14, content = $15,
+                        source = $16, mime_type = $17,
+                        updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                    WHERE id = $18
+                    "#,
+                )
+                .bind(collection_id)
+                .bind(&resource.title)
+                .bind(&resource.description)
+                .bind(&resource.favicon_url)
+                .bind(&resource.screenshot_url)
+                .bind(&resource.thumbnail_url)
+                .bind(resource.is_favorite)
+                .bind(resource.is_archived)
+                .bind(resource.is_private)
+                .bind(resource.is_read)
+                .bind(resource.visit_count)
+                .bind(resource.last_visited)
+                .bind(&metadata)
+                .bind(&resource.resource_type)
+                .bind(&resource.content)
+                .bind(&resource.source)
+                .bind(&resource.mime_type)
+                .bind(id)
+                .execute(&mut **tx)
+                .await?;
+
+                sqlx::query("DELETE FROM resource_tags WHERE resource_id = $1")
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await?;
+                (id, ImportEntryStatus::Updated)
+            }
+            // 无冲突, 或 rename 策略下作为新资源导入
+            (None, _) | (Some(_), ConflictPolicy::Rename) => {
+                let id: i64 = sqlx::query_scalar(
+                    r#"
+                    INSERT INTO resources (user_id, collection_id, title, url, description,
+                                           favicon_url, screenshot_url, thumbnail_url,
+                                           is_favorite, is_archived, is_private, is_read,
+                                           visit_count, last_visited, metadata, type, content,
+                                           source, mime_type, created_at, updated_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                            $16, $17, $18, $19, $20, $21)
+                    RETURNING id
+                    "#,
+                )
+                .bind(user_id)
+                .bind(collection_id)
+                .bind(&resource.title)
+                .bind(&resource.url)
+                .bind(&resource.description)
+                .bind(&resource.favicon_url)
+                .bind(&resource.screenshot_url)
+                .bind(&resource.thumbnail_url)
+                .bind(resource.is_favorite)
+                .bind(resource.is_archived)
+                .bind(resource.is_private)
+                .bind(resource.is_read)
+                .bind(resource.visit_count)
+                .bind(resource.last_visited)
+                .bind(&metadata)
+                .bind(&resource.resource_type)
+                .bind(&resource.content)
+                .bind(&resource.source)
+                .bind(&resource.mime_type)
+                .bind(resource.created_at)
+                .bind(resource.updated_at)
+                .fetch_one(&mut **tx)
+                .await?;
+                (id, ImportEntryStatus::Imported)
+            }
+        };
+
+        // 关联标签, 文档 tags 列表中缺失的标签按名称创建
+        for name in &resource.tags {
+            let tag_id = match tag_map.get(name) {
+                Some(&tag_id) => tag_id,
+                None => {
+                    let inserted =
+                        sqlx::query("INSERT OR IGNORE INTO tags (user_id, name) VALUES ($1, $2)")
+                            .bind(user_id)
+                            .bind(name)
+                            .execute(&mut **tx)
+                            .await?;
+                    if inserted.rows_affected() > 0 {
+                        result.tags_created += 1;
+                    }
+
+                    let tag_id: i64 =
+                        sqlx::query_scalar("SELECT id FROM tags WHERE user_id = $1 AND name = $2")
+                            .bind(user_id)
+                            .bind(name)
+                            .fetch_one(&mut **tx)
+                            .await?;
+                    tag_map.insert(name.clone(), tag_id);
+                    tag_id
+                }
+            };
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO resource_tags (resource_id, tag_id) VALUES ($1, $2)",
+            )
+            .bind(resource_id)
+            .bind(tag_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        // 文档只包含文件元数据, 目标资源没有上传文件时提示重新上传
+        if let Some(file) = &resource.file {
+            let has_file: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM resource_files WHERE resource_id = $1)",
+            )
+            .bind(resource_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            if !has_file {
+                entry.reason = Some(format!(
+                    "File content of {} is not included in the export, upload it again",
+                    file.file_name
+                ));
+            }
+        }
+
+        entry.status = status;
+        entry.resource_id = Some(resource_id);
+        Ok(entry)
+    }
+
+    /// 为冲突的收藏夹/标签生成不重复的名称, 例如 `Dev (2)`
+    async fn unique_name(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        table: &str,
+        user_id: i64,
+        name: &str,
+    ) -> AppResult<String> {
+        let query = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE user_id = $1 AND name = $2)",
+            table
+        );
+
+        let mut suffix = 2;
+        loop {
+            let candidate = format!("{} ({})", name, suffix);
+            let exists: bool = sqlx::query_scalar(&query)
+                .bind(user_id)
+                .bind(&candidate)
+                .fetch_one(&mut **tx)
+                .await?;
+            if !exists {
+                return Ok(candidate);
+            }
+            suffix += 1;
+        }
+    }
+
+    /// 按名称查找收藏夹, 不存在时创建
+    ///
+    /// collections 表上 (user_id, name) 唯一, 因此不同层级下的同名文件夹会合并到同一个收藏夹
+    async fn find_or_create_collection(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        name: &str,
+        parent_id: Option<i64>,
+    ) -> AppResult<(i64, bool)> {
+        let existing: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM collections WHERE user_id = $1 AND name = $2")
+                .bind(user_id)
+                .bind(name)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+        if let Some(id) = existing {
+            return Ok((id, false));
+        }
+
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO collections (user_id, name, parent_id)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(parent_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok((id, true))
+    }
+}
@@ -0,0 +1,551 @@
+use sqlx::SqlitePool;
+
+use crate::config::AuthConfig;
+use crate::models::{ConflictPolicy, ImportEntryStatus, ImportFormat, ImportOptions, ImportResult};
+use crate::services::import_service::ImportService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+const BOOKMARKS_HTML: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3>Dev</H3>
+    <DL><p>
+        <DT><A HREF="https://www.rust-lang.org/" ADD_DATE="1600000000" TAGS="rust,lang">Rust</A>
+        <DD>A systems language
+        <DT><H3>Tools</H3>
+        <DL><p>
+            <DT><A HREF="https://crates.io/" TAGS="rust">crates.io</A>
+        </DL><p>
+    </DL><p>
+    <DT><A HREF="https://www.rust-lang.org/">Rust again</A>
+    <DT><A HREF="not-a-url">Broken</A>
+</DL><p>
+"#;
+
+#[tokio::test]
+async fn test_import_netscape_creates_resources_collections_and_tags() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    let result = ImportService::import(
+        user_id,
+        ImportFormat::Netscape,
+        BOOKMARKS_HTML,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.imported, 2);
+    assert_eq!(result.skipped, 1);
+    assert_eq!(result.failed, 1);
+    assert_eq!(result.collections_created, 2);
+    assert_eq!(result.tags_created, 2);
+    assert_eq!(result.entries.len(), 4);
+    assert_eq!(result.entries[2].status, ImportEntryStatus::Skipped);
+    assert_eq!(result.entries[3].status, ImportEntryStatus::Failed);
+
+    // 文件夹层级映射到 parent_id
+    let (dev_id, dev_parent): (i64, Option<i64>) =
+        sqlx::query_as("SELECT id, parent_id FROM collections WHERE user_id = $1 AND name = 'Dev'")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(dev_parent.is_none());
+
+    let tools_parent: Option<i64> = sqlx::query_scalar(
+        "SELECT parent_id FROM collections WHERE user_id = $1 AND name = 'Tools'",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(tools_parent, Some(dev_id));
+
+    // 书签保留描述、创建时间和所在收藏夹
+    let (collection_id, description, created_at): (Option<i64>, Option<String>, i64) =
+        sqlx::query_as(
+            "SELECT collection_id, description, created_at FROM resources WHERE user_id = $1 AND url = 'https://www.rust-lang.org/'",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(collection_id, Some(dev_id));
+    assert_eq!(description.as_deref(), Some("A systems language"));
+    assert_eq!(created_at, 1_600_000_000);
+
+    // 导入的资源已进入 FTS 索引
+    let indexed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM resources_fts WHERE resources_fts MATCH 'tags:rust'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(indexed, 2);
+}
+
+#[tokio::test]
+async fn test_import_netscape_skips_existing_urls_and_reuses_collections() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    ImportService::import(
+        user_id,
+        ImportFormat::Netscape,
+        BOOKMARKS_HTML,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    let second = ImportService::import(
+        user_id,
+        ImportFormat::Netscape,
+        BOOKMARKS_HTML,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(second.imported, 0);
+    assert_eq!(second.skipped, 3);
+    assert_eq!(second.collections_created, 0);
+    assert_eq!(second.tags_created, 0);
+}
+
+#[tokio::test]
+async fn test_import_netscape_rejects_empty_file_and_foreign_collection() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    let empty = ImportService::import(
+        user_id,
+        ImportFormat::Netscape,
+        "<html></html>",
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await;
+    assert!(empty.is_err());
+
+    let missing_collection = ImportService::import(
+        user_id,
+        ImportFormat::Netscape,
+        BOOKMARKS_HTML,
+        &ImportOptions {
+            collection_id: Some(999_999),
+            ..Default::default()
+        },
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await;
+    assert!(missing_collection.is_err());
+}
+
+const LIBRARY_JSON: &str = r##"{
+  "version": 1,
+  "exported_at": 1700000000,
+  "collections": [
+    {"id": 11, "name": "Tools", "description": "Daily tools", "color": "#ff0000", "icon": "wrench",
+     "sort_order": 1, "is_default": false, "is_public": false, "parent_id": 10,
+     "created_at": 1600000000, "updated_at": 1600000000},
+    {"id": 10, "name": "Dev", "description": null, "color": "#00ff00", "icon": "code",
+     "sort_order": 0, "is_default": false, "is_public": true, "parent_id": null,
+     "created_at": 1600000000, "updated_at": 1600000000}
+  ],
+  "tags": [
+    {"id": 5, "name": "rust", "color": "#dea584", "description": "Rust things",
+     "created_at": 1600000000, "updated_at": 1600000000}
+  ],
+  "resources": [
+    {"id": 100, "collection_id": 11, "title": "Rust", "url": "https://www.rust-lang.org/",
+     "description": "A systems language", "favicon_url": "https://www.rust-lang.org/favicon.ico",
+     "screenshot_url": null, "thumbnail_url": null, "is_favorite": true, "is_archived": false,
+     "is_private": false, "is_read": true, "visit_count": 7, "last_visited": 1650000000,
+     "metadata": {"lang": "en"}, "type": "link", "content": null, "source": null,
+     "mime_type": null, "created_at": 1600000001, "updated_at": 1600000002,
+     "tags": ["rust", "lang"]},
+    {"id": 101, "collection_id": 10, "title": "Ownership notes", "url": null,
+     "description": null, "favicon_url": null, "screenshot_url": null, "thumbnail_url": null,
+     "is_favorite": false, "is_archived": true, "is_private": true, "is_read": false,
+     "visit_count": 0, "last_visited": null, "metadata": {}, "type": "note",
+     "content": "Borrowing rules", "source": null, "mime_type": null,
+     "created_at": 1600000003, "updated_at": 1600000004, "tags": ["rust"]}
+  ],
+  "references": [
+    {"source_id": 101, "target_id": 100, "type": "references", "created_at": 1600000005},
+    {"source_id": 101, "target_id": 999, "type": "related", "created_at": 1600000005}
+  ]
+}"##;
+
+async fn import_json(
+    pool: &SqlitePool,
+    user_id: i64,
+    conflict_policy: ConflictPolicy,
+    dry_run: bool,
+) -> ImportResult {
+    let options = ImportOptions {
+        collection_id: None,
+        conflict_policy,
+        dry_run,
+    };
+    ImportService::import(
+        user_id,
+        ImportFormat::Json,
+        LIBRARY_JSON,
+        &options,
+        &AuthConfig::default(),
+        pool,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_import_json_restores_library_with_new_ids() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    let result = import_json(&pool, user_id, ConflictPolicy::Skip, false).await;
+
+    assert_eq!(result.imported, 2);
+    assert_eq!(result.collections_created, 2);
+    assert_eq!(result.tags_created, 2); // rust 来自 tags 列表, lang 只出现在资源上
+    assert_eq!(result.references_created, 1);
+
+    // 层级按新 id 恢复 (子收藏夹在文档中先于父收藏夹出现)
+    let (dev_id, dev_public): (i64, bool) =
+        sqlx::query_as("SELECT id, is_public FROM collections WHERE user_id = $1 AND name = 'Dev'")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(dev_public);
+    let (tools_id, tools_parent): (i64, Option<i64>) = sqlx::query_as(
+        "SELECT id, parent_id FROM collections WHERE user_id = $1 AND name = 'Tools'",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(tools_parent, Some(dev_id));
+
+    // 资源字段完整保留
+    let rust = sqlx::query_as::<_, crate::models::Resource>(
+        r#"
+        SELECT id, user_id, collection_id, title, url, description, favicon_url, screenshot_url,
+               thumbnail_url, is_favorite, is_archived, is_private, is_read, visit_count,
+               last_visited, metadata, type, content, source, mime_type, created_at, updated_at
+        FROM resources WHERE user_id = $1 AND type = 'link'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_ne!(rust.id, 100);
+    assert_eq!(rust.collection_id, Some(tools_id));
+    assert!(rust.is_favorite && rust.is_read);
+    assert_eq!(rust.visit_count, 7);
+    assert_eq!(rust.last_visited, Some(1_650_000_000));
+    assert_eq!(rust.metadata["lang"], "en");
+    assert_eq!(rust.created_at, 1_600_000_001);
+
+    let (note_id, content, archived): (i64, Option<String>, bool) = sqlx::query_as(
+        "SELECT id, content, is_archived FROM resources WHERE user_id = $1 AND type = 'note'",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(content.as_deref(), Some("Borrowing rules"));
+    assert!(archived);
+
+    // 引用关系映射到新 id, 指向文档外资源的引用被丢弃
+    let (source_id, target_id, reference_type): (i64, i64, String) =
+        sqlx::query_as("SELECT source_id, target_id, type FROM resource_references")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((source_id, target_id), (note_id, rust.id));
+    assert_eq!(reference_type, "references");
+
+    let indexed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM resources_fts WHERE resources_fts MATCH 'tags:rust'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(indexed, 2);
+}
+
+#[tokio::test]
+async fn test_import_json_conflict_policies() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+    import_json(&pool, user_id, ConflictPolicy::Skip, false).await;
+
+    // skip: 已有收藏夹、标签和 URL 保持不变, 笔记没有 URL 仍会导入
+    let skipped = import_json(&pool, user_id, ConflictPolicy::Skip, false).await;
+    assert_eq!(skipped.skipped, 1);
+    assert_eq!(skipped.imported, 1);
+    assert_eq!(skipped.collections_created, 0);
+    assert_eq!(skipped.tags_created, 0);
+    assert_eq!(skipped.references_created, 1);
+
+    // rename: 同名收藏夹和标签以新名称创建, 重复 URL 作为新资源导入
+    let renamed = import_json(&pool, user_id, ConflictPolicy::Rename, false).await;
+    assert_eq!(renamed.imported, 2);
+    assert_eq!(renamed.collections_created, 2);
+    let renamed_parent: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT p.name FROM collections c JOIN collections p ON p.id = c.parent_id
+        WHERE c.user_id = $1 AND c.name = 'Tools (2)'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&pool)
+    .await
+    .unwrap();
+    assert_eq!(renamed_parent.as_deref(), Some("Dev (2)"));
+    let renamed_tag: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM tags WHERE user_id = $1 AND name = 'rust (2)')",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(renamed_tag);
+}
+
+#[tokio::test]
+async fn test_import_json_merge_and_overwrite_existing_resource() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    sqlx::query(
+        r#"
+        INSERT INTO resources (user_id, title, url, type, description, visit_count, metadata)
+        VALUES ($1, 'My Rust', 'https://www.rust-lang.org/', 'link', NULL, 10, '{"lang":"zh","mine":true}')
+        "#,
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let merged = import_json(&pool, user_id, ConflictPolicy::Merge, false).await;
+    assert_eq!(merged.updated, 1);
+    assert_eq!(merged.imported, 1);
+
+    let (title, description, visit_count, metadata): (String, Option<String>, i64, String) =
+        sqlx::query_as(
+            "SELECT title, description, visit_count, metadata FROM resources WHERE user_id = $1 AND url = 'https://www.rust-lang.org/'",
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(title, "My Rust");
+    assert_eq!(description.as_deref(), Some("A systems language"));
+    assert_eq!(visit_count, 10);
+    let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+    assert_eq!(metadata["lang"], "zh");
+    assert_eq!(metadata["mine"], true);
+
+    let overwritten = import_json(&pool, user_id, ConflictPolicy::Overwrite, false).await;
+    assert_eq!(overwritten.updated, 1);
+    assert_eq!(overwritten.collections_updated, 2);
+
+    let (title, visit_count, tag_count): (String, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT r.title, r.visit_count,
+               (SELECT COUNT(*) FROM resource_tags rt WHERE rt.resource_id = r.id)
+        FROM resources r WHERE r.user_id = $1 AND r.url = 'https://www.rust-lang.org/'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(title, "Rust");
+    assert_eq!(visit_count, 7);
+    assert_eq!(tag_count, 2);
+}
+
+#[tokio::test]
+async fn test_import_json_dry_run_and_version_check() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    let preview = import_json(&pool, user_id, ConflictPolicy::Skip, true).await;
+    assert!(preview.dry_run);
+    assert_eq!(preview.imported, 2);
+    assert_eq!(preview.collections_created, 2);
+
+    let (resources, collections): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM resources WHERE user_id = $1), (SELECT COUNT(*) FROM collections WHERE user_id = $1)",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((resources, collections), (0, 0));
+
+    let future_version = LIBRARY_JSON.replace("\"version\": 1", "\"version\": 99");
+    let rejected = ImportService::import(
+        user_id,
+        ImportFormat::Json,
+        &future_version,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await;
+    assert!(rejected.is_err());
+}
+
+#[tokio::test]
+async fn test_import_json_keeps_single_default_collection() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+    sqlx::query("INSERT INTO collections (user_id, name, is_default) VALUES ($1, 'Inbox', TRUE)")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let document = LIBRARY_JSON.replacen("\"is_default\": false", "\"is_default\": true", 1);
+    ImportService::import(
+        user_id,
+        ImportFormat::Json,
+        &document,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    let defaults: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM collections WHERE user_id = $1 AND is_default = TRUE")
+            .bind(user_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(defaults, vec!["Inbox"]);
+}
+
+#[tokio::test]
+async fn test_import_json_public_collection_requires_verified_email() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+    let config = AuthConfig {
+        require_verified_email: true,
+        ..Default::default()
+    };
+
+    // 文档中的 Dev 收藏夹是公开的, 未验证邮箱时整个导入被拒绝
+    let rejected = ImportService::import(
+        user_id,
+        ImportFormat::Json,
+        LIBRARY_JSON,
+        &ImportOptions::default(),
+        &config,
+        &pool,
+    )
+    .await;
+    assert!(matches!(rejected, Err(AppError::Forbidden(_))));
+
+    let collections: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM collections WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(collections, 0);
+
+    sqlx::query("UPDATE users SET email_verified = 1 WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let result = ImportService::import(
+        user_id,
+        ImportFormat::Json,
+        LIBRARY_JSON,
+        &ImportOptions::default(),
+        &config,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.collections_created, 2);
+}
+
+#[tokio::test]
+async fn test_import_adapters_map_read_favorite_and_archived_state() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "importer").await;
+
+    let pocket_csv = "title,url,time_added,tags,status\n\
+                      Unread,https://pocket.example.com/a,1600000000,read-later,unread\n\
+                      Archived,https://pocket.example.com/b,1600000100,,archive\n";
+    let pocket = ImportService::import(
+        user_id,
+        ImportFormat::Pocket,
+        pocket_csv,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(pocket.imported, 2);
+
+    let raindrop_csv = "id,title,note,excerpt,url,folder,tags,created,cover,highlights,favorite\n\
+                        1,Fav,,,https://raindrop.example.com/,Reading,news,2020-09-13T12:26:40.000Z,,,true\n";
+    let raindrop = ImportService::import(
+        user_id,
+        ImportFormat::Raindrop,
+        raindrop_csv,
+        &ImportOptions::default(),
+        &AuthConfig::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(raindrop.imported, 1);
+    assert_eq!(raindrop.collections_created, 1);
+
+    let rows: Vec<(String, bool, bool, bool, i64)> = sqlx::query_as(
+        r#"
+        SELECT title, is_read, is_archived, is_favorite, created_at
+        FROM resources WHERE user_id = $1 ORDER BY id
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            ("Unread".to_string(), false, false, false, 1_600_000_000),
+            ("Archived".to_string(), true, true, false, 1_600_000_100),
+            ("Fav".to_string(), false, false, true, 1_600_000_000),
+        ]
+    );
+}
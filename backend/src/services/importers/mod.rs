@@ -0,0 +1,210 @@
+//! 第三方书签导入适配器
+//!
+//! 每种来源实现 `BookmarkImporter`, 只负责把文件内容解析为统一的 `ImportedDocument`,
+//! 写库、去重和 FTS 索引由 ImportService 统一处理。
+//! 新增来源时只需实现该 trait、在 ImportFormat 中增加对应格式并注册到 `IMPORTERS`,
+//! handler 和命令处理无需修改。
+use std::collections::HashMap;
+
+use crate::models::ImportFormat;
+use crate::utils::error::{AppError, AppResult};
+
+pub mod netscape;
+pub mod pinboard;
+pub mod pocket;
+pub mod raindrop;
+
+pub use netscape::NetscapeImporter;
+pub use pinboard::PinboardImporter;
+pub use pocket::PocketImporter;
+pub use raindrop::RaindropImporter;
+
+/// 解析出的单个书签
+///
+/// 状态字段为 None 表示来源不提供该信息, 使用数据库默认值
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedBookmark {
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// 创建时间 (Unix 时间戳, 秒)
+    pub created_at: Option<i64>,
+    /// 所在文件夹路径, 顶层书签为空
+    pub folder_path: Vec<String>,
+    pub is_read: Option<bool>,
+    pub is_favorite: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+}
+
+/// 解析结果
+#[derive(Debug, Default)]
+pub struct ImportedDocument {
+    /// 文件夹路径 (父文件夹总是先于子文件夹出现)
+    pub folders: Vec<Vec<String>>,
+    pub bookmarks: Vec<ImportedBookmark>,
+}
+
+impl ImportedDocument {
+    /// 由书签列表构建文档, 文件夹取自书签所在路径的所有前缀
+    pub fn from_bookmarks(bookmarks: Vec<ImportedBookmark>) -> Self {
+        let mut folders: Vec<Vec<String>> = Vec::new();
+        for bookmark in &bookmarks {
+            for depth in 1..=bookmark.folder_path.len() {
+                let prefix = &bookmark.folder_path[..depth];
+                if !folders.iter().any(|folder| folder == prefix) {
+                    folders.push(prefix.to_vec());
+                }
+            }
+        }
+
+        Self { folders, bookmarks }
+    }
+}
+
+/// 书签导入适配器
+pub trait BookmarkImporter: Send + Sync {
+    /// 适配器对应的导入格式
+    fn format(&self) -> ImportFormat;
+
+    /// 解析文件内容
+    fn parse(&self, content: &str) -> AppResult<ImportedDocument>;
+}
+
+/// 已注册的导入适配器
+static IMPORTERS: &[&dyn BookmarkImporter] = &[
+    &NetscapeImporter,
+    &PocketImporter,
+    &PinboardImporter,
+    &RaindropImporter,
+];
+
+/// 查找指定格式的导入适配器
+pub fn importer_for(format: ImportFormat) -> Option<&'static dyn BookmarkImporter> {
+    IMPORTERS
+        .iter()
+        .copied()
+        .find(|importer| importer.format() == format)
+}
+
+/// 读取带表头的 CSV, 每行转换为 列名(小写) -> 值 的映射
+pub(crate) fn read_csv(content: &str) -> AppResult<Vec<HashMap<String, String>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::BadRequest(format!("Invalid CSV header: {}", e)))?
+        .iter()
+        .map(|header| header.to_lowercase())
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| AppError::BadRequest(format!("Invalid CSV row: {}", e)))?;
+        rows.push(
+            headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(str::to_string))
+                .collect(),
+        );
+    }
+
+    Ok(rows)
+}
+
+/// 拆分标签字符串, 去除空白与重复项
+pub(crate) fn split_tags(raw: &str, separator: char) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw
+        .split(separator)
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// 解析时间: 支持 Unix 时间戳 (秒) 和 RFC 3339 格式
+pub(crate) fn parse_timestamp(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    raw.parse::<i64>()
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(raw)
+                .ok()
+                .map(|time| time.timestamp())
+        })
+        .filter(|timestamp| *timestamp > 0)
+}
+
+/// 取非空字段值
+pub(crate) fn non_empty(value: Option<&String>) -> Option<String> {
+    value
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_importer_registry_covers_formats() {
+        for format in [
+            ImportFormat::Netscape,
+            ImportFormat::Pocket,
+            ImportFormat::Pinboard,
+            ImportFormat::Raindrop,
+        ] {
+            assert_eq!(importer_for(format).map(|i| i.format()), Some(format));
+        }
+        // JSON 文档由 ImportService 单独处理
+        assert!(importer_for(ImportFormat::Json).is_none());
+    }
+
+    #[test]
+    fn test_from_bookmarks_collects_folder_prefixes() {
+        let bookmark = |path: &[&str]| ImportedBookmark {
+            folder_path: path.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let document = ImportedDocument::from_bookmarks(vec![
+            bookmark(&["Dev", "Tools"]),
+            bookmark(&[]),
+            bookmark(&["Dev"]),
+        ]);
+
+        assert_eq!(
+            document.folders,
+            vec![
+                vec!["Dev".to_string()],
+                vec!["Dev".to_string(), "Tools".to_string()]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1600000000"), Some(1_600_000_000));
+        assert_eq!(parse_timestamp("2020-09-13T12:26:40Z"), Some(1_600_000_000));
+        assert_eq!(
+            parse_timestamp("2020-09-13T12:26:40.000Z"),
+            Some(1_600_000_000)
+        );
+        assert_eq!(parse_timestamp("not a date"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+}
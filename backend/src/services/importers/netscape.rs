@@ -0,0 +1,37 @@
+use super::{BookmarkImporter, ImportedBookmark, ImportedDocument};
+use crate::models::ImportFormat;
+use crate::utils::error::AppResult;
+use crate::utils::netscape::parse_bookmarks;
+
+/// 浏览器导出的 Netscape 书签文件 (bookmarks.html)
+pub struct NetscapeImporter;
+
+impl BookmarkImporter for NetscapeImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::Netscape
+    }
+
+    fn parse(&self, content: &str) -> AppResult<ImportedDocument> {
+        let document = parse_bookmarks(content);
+
+        let bookmarks = document
+            .bookmarks
+            .into_iter()
+            .map(|bookmark| ImportedBookmark {
+                title: bookmark.title,
+                url: bookmark.url,
+                description: bookmark.description,
+                tags: bookmark.tags,
+                created_at: bookmark.add_date,
+                folder_path: bookmark.folder_path,
+                ..Default::default()
+            })
+            .collect();
+
+        // 保留文档中的全部文件夹 (包括空文件夹)
+        Ok(ImportedDocument {
+            folders: document.folders,
+            bookmarks,
+        })
+    }
+}
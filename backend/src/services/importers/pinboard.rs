@@ -0,0 +1,93 @@
+use serde::Deserialize;
+
+use super::{parse_timestamp, split_tags, BookmarkImporter, ImportedBookmark, ImportedDocument};
+use crate::models::ImportFormat;
+use crate::utils::error::{AppError, AppResult};
+
+/// Pinboard JSON 导出中的单条书签
+#[derive(Debug, Deserialize)]
+struct PinboardPost {
+    href: String,
+    #[serde(default)]
+    description: String, // 标题
+    #[serde(default)]
+    extended: String, // 描述
+    #[serde(default)]
+    time: String,
+    #[serde(default)]
+    shared: String, // yes/no
+    #[serde(default)]
+    toread: String, // yes/no
+    #[serde(default)]
+    tags: String, // 空格分隔
+}
+
+/// Pinboard JSON 导出文件 (`/v1/posts/all?format=json`)
+///
+/// toread=yes 映射为未读, shared=no 映射为私有
+pub struct PinboardImporter;
+
+impl BookmarkImporter for PinboardImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::Pinboard
+    }
+
+    fn parse(&self, content: &str) -> AppResult<ImportedDocument> {
+        let posts: Vec<PinboardPost> = serde_json::from_str(content)
+            .map_err(|e| AppError::BadRequest(format!("Invalid Pinboard export: {}", e)))?;
+
+        let bookmarks = posts
+            .into_iter()
+            .filter(|post| !post.href.trim().is_empty())
+            .map(|post| {
+                let extended = post.extended.trim();
+                ImportedBookmark {
+                    title: post.description.trim().to_string(),
+                    url: post.href.trim().to_string(),
+                    description: (!extended.is_empty()).then(|| extended.to_string()),
+                    tags: split_tags(&post.tags, ' '),
+                    created_at: parse_timestamp(&post.time),
+                    is_read: Some(!post.toread.eq_ignore_ascii_case("yes")),
+                    is_private: Some(post.shared.eq_ignore_ascii_case("no")),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Ok(ImportedDocument::from_bookmarks(bookmarks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pinboard_json() {
+        let json = r#"[
+            {"href": "https://www.rust-lang.org/", "description": "Rust", "extended": "A language",
+             "meta": "x", "hash": "y", "time": "2020-09-13T12:26:40Z", "shared": "no",
+             "toread": "yes", "tags": "rust lang rust"},
+            {"href": "https://example.com/", "description": "Example", "extended": "",
+             "time": "2020-09-13T12:26:41Z", "shared": "yes", "toread": "no", "tags": ""}
+        ]"#;
+
+        let document = PinboardImporter.parse(json).unwrap();
+        assert_eq!(document.bookmarks.len(), 2);
+
+        let rust = &document.bookmarks[0];
+        assert_eq!(rust.title, "Rust");
+        assert_eq!(rust.description.as_deref(), Some("A language"));
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(rust.created_at, Some(1_600_000_000));
+        assert_eq!(rust.is_read, Some(false));
+        assert_eq!(rust.is_private, Some(true));
+
+        let example = &document.bookmarks[1];
+        assert!(example.description.is_none());
+        assert_eq!(example.is_read, Some(true));
+        assert_eq!(example.is_private, Some(false));
+
+        assert!(PinboardImporter.parse("{}").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{
+    non_empty, parse_timestamp, read_csv, split_tags, BookmarkImporter, ImportedBookmark,
+    ImportedDocument,
+};
+use crate::models::ImportFormat;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::netscape::decode_entities;
+
+/// 匹配 Pocket HTML 导出中的分组标题和链接
+static POCKET_TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<h1[^>]*>(.*?)</h1>|<a\s([^>]*)>(.*?)</a>")
+        .expect("Failed to compile pocket token regex")
+});
+
+/// 匹配标签属性 key="value"
+static POCKET_ATTR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)([a-z_]+)\s*=\s*"([^"]*)""#)
+        .expect("Failed to compile pocket attribute regex")
+});
+
+/// Pocket 导出文件
+///
+/// 支持两种格式, 按内容自动识别:
+/// - HTML: `<h1>Unread</h1>` / `<h1>Read Archive</h1>` 分组下的 `<a href time_added tags>` 链接
+/// - CSV: `title,url,time_added,tags,status` 表头, 标签以 `|` 分隔, status 为 unread/archive
+///
+/// Pocket 中归档即表示已读, 因此 archive 同时映射为 is_archived 和 is_read
+pub struct PocketImporter;
+
+impl BookmarkImporter for PocketImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::Pocket
+    }
+
+    fn parse(&self, content: &str) -> AppResult<ImportedDocument> {
+        let bookmarks = if content.trim_start().starts_with('<') {
+            Self::parse_html(content)
+        } else {
+            Self::parse_csv(content)?
+        };
+
+        Ok(ImportedDocument::from_bookmarks(bookmarks))
+    }
+}
+
+impl PocketImporter {
+    fn parse_html(content: &str) -> Vec<ImportedBookmark> {
+        let mut bookmarks = Vec::new();
+        let mut archived = false;
+
+        for caps in POCKET_TOKEN_REGEX.captures_iter(content) {
+            if let Some(heading) = caps.get(1) {
+                archived = heading.as_str().to_lowercase().contains("archive");
+                continue;
+            }
+
+            let attrs = caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+            let mut url = None;
+            let mut created_at = None;
+            let mut tags = Vec::new();
+            for attr in POCKET_ATTR_REGEX.captures_iter(attrs) {
+                let value = decode_entities(attr[2].trim());
+                match attr[1].to_lowercase().as_str() {
+                    "href" => url = Some(value),
+                    "time_added" => created_at = parse_timestamp(&value),
+                    "tags" => tags = split_tags(&value, ','),
+                    _ => {}
+                }
+            }
+
+            let Some(url) = url.filter(|url| !url.is_empty()) else {
+                continue;
+            };
+            let title = caps
+                .get(3)
+                .map(|m| decode_entities(m.as_str().trim()))
+                .unwrap_or_default();
+
+            bookmarks.push(ImportedBookmark {
+                title,
+                url,
+                tags,
+                created_at,
+                is_read: Some(archived),
+                is_archived: Some(archived),
+                ..Default::default()
+            });
+        }
+
+        bookmarks
+    }
+
+    fn parse_csv(content: &str) -> AppResult<Vec<ImportedBookmark>> {
+        let rows = read_csv(content)?;
+        if rows.first().is_some_and(|row| !row.contains_key("url")) {
+            return Err(AppError::BadRequest(
+                "Pocket CSV export must contain a url column".to_string(),
+            ));
+        }
+
+        let bookmarks = rows
+            .into_iter()
+            .filter_map(|row| {
+                let url = non_empty(row.get("url"))?;
+                let archived = row
+                    .get("status")
+                    .is_some_and(|status| status.eq_ignore_ascii_case("archive"));
+                let favorite = row
+                    .get("favorite")
+                    .map(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+
+                Some(ImportedBookmark {
+                    title: non_empty(row.get("title")).unwrap_or_default(),
+                    url,
+                    tags: row
+                        .get("tags")
+                        .map(|tags| split_tags(tags, '|'))
+                        .unwrap_or_default(),
+                    created_at: row.get("time_added").and_then(|time| parse_timestamp(time)),
+                    is_read: Some(archived),
+                    is_archived: Some(archived),
+                    is_favorite: favorite,
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(bookmarks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pocket_html() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Pocket Export</title></head><body>
+<h1>Unread</h1>
+<ul>
+<li><a href="https://example.com/a" time_added="1600000000" tags="rust,web">A &amp; B</a></li>
+</ul>
+<h1>Read Archive</h1>
+<ul>
+<li><a href="https://example.com/b" time_added="1600000100" tags="">B</a></li>
+</ul>
+</body></html>"#;
+
+        let document = PocketImporter.parse(html).unwrap();
+        assert!(document.folders.is_empty());
+        assert_eq!(document.bookmarks.len(), 2);
+
+        let unread = &document.bookmarks[0];
+        assert_eq!(unread.title, "A & B");
+        assert_eq!(unread.tags, vec!["rust", "web"]);
+        assert_eq!(unread.created_at, Some(1_600_000_000));
+        assert_eq!(unread.is_read, Some(false));
+        assert_eq!(unread.is_archived, Some(false));
+
+        let archived = &document.bookmarks[1];
+        assert!(archived.tags.is_empty());
+        assert_eq!(archived.is_read, Some(true));
+        assert_eq!(archived.is_archived, Some(true));
+    }
+
+    #[test]
+    fn test_parse_pocket_csv() {
+        let csv = "title,url,time_added,cursor,tags,status\n\
+                   Rust,https://www.rust-lang.org/,1600000000,abc,rust|lang,unread\n\
+                   \"Hello, world\",https://example.com/,1600000100,def,,archive\n\
+                   No url,,1600000200,ghi,,unread\n";
+
+        let document = PocketImporter.parse(csv).unwrap();
+        assert_eq!(document.bookmarks.len(), 2);
+
+        let rust = &document.bookmarks[0];
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(rust.is_archived, Some(false));
+
+        let hello = &document.bookmarks[1];
+        assert_eq!(hello.title, "Hello, world");
+        assert_eq!(hello.created_at, Some(1_600_000_100));
+        assert_eq!(hello.is_read, Some(true));
+        assert_eq!(hello.is_archived, Some(true));
+    }
+}
@@ -0,0 +1,88 @@
+use super::{
+    non_empty, parse_timestamp, read_csv, split_tags, BookmarkImporter, ImportedBookmark,
+    ImportedDocument,
+};
+use crate::models::ImportFormat;
+use crate::utils::error::{AppError, AppResult};
+
+/// Raindrop.io CSV 导出文件
+///
+/// 表头: `id,title,note,excerpt,url,folder,tags,created,cover,highlights,favorite`
+/// - folder 映射为收藏夹 (Unsorted 视为未归类)
+/// - excerpt 优先作为描述, 没有时使用 note
+/// - favorite 映射为 is_favorite, Raindrop 不区分已读/归档
+pub struct RaindropImporter;
+
+impl BookmarkImporter for RaindropImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::Raindrop
+    }
+
+    fn parse(&self, content: &str) -> AppResult<ImportedDocument> {
+        let rows = read_csv(content)?;
+        if rows.first().is_some_and(|row| !row.contains_key("url")) {
+            return Err(AppError::BadRequest(
+                "Raindrop CSV export must contain a url column".to_string(),
+            ));
+        }
+
+        let bookmarks = rows
+            .into_iter()
+            .filter_map(|row| {
+                let url = non_empty(row.get("url"))?;
+                let folder_path = non_empty(row.get("folder"))
+                    .filter(|folder| !folder.eq_ignore_ascii_case("unsorted"))
+                    .map(|folder| vec![folder])
+                    .unwrap_or_default();
+
+                Some(ImportedBookmark {
+                    title: non_empty(row.get("title")).unwrap_or_default(),
+                    url,
+                    description: non_empty(row.get("excerpt"))
+                        .or_else(|| non_empty(row.get("note"))),
+                    tags: row
+                        .get("tags")
+                        .map(|tags| split_tags(tags, ','))
+                        .unwrap_or_default(),
+                    created_at: row.get("created").and_then(|time| parse_timestamp(time)),
+                    folder_path,
+                    is_favorite: row
+                        .get("favorite")
+                        .map(|value| value.eq_ignore_ascii_case("true")),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(ImportedDocument::from_bookmarks(bookmarks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_raindrop_csv() {
+        let csv = "id,title,note,excerpt,url,folder,tags,created,cover,highlights,favorite\n\
+                   1,Rust,my note,,https://www.rust-lang.org/,Dev,\"rust, lang\",2020-09-13T12:26:40.000Z,,,true\n\
+                   2,Example,,An example,https://example.com/,Unsorted,,2020-09-13T12:26:41.000Z,,,false\n";
+
+        let document = RaindropImporter.parse(csv).unwrap();
+        assert_eq!(document.folders, vec![vec!["Dev".to_string()]]);
+        assert_eq!(document.bookmarks.len(), 2);
+
+        let rust = &document.bookmarks[0];
+        assert_eq!(rust.folder_path, vec!["Dev"]);
+        assert_eq!(rust.description.as_deref(), Some("my note"));
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(rust.created_at, Some(1_600_000_000));
+        assert_eq!(rust.is_favorite, Some(true));
+        assert!(rust.is_read.is_none());
+
+        let example = &document.bookmarks[1];
+        assert!(example.folder_path.is_empty());
+        assert_eq!(example.description.as_deref(), Some("An example"));
+        assert_eq!(example.is_favorite, Some(false));
+    }
+}
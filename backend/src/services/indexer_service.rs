@@ -16,6 +16,7 @@ use crate::utils::segmenter::{prepare_for_search, prepare_tags_for_search};
 /// 使用场景:
 /// - ResourceService 在创建/更新资源时调用
 /// - TagService 在重命名标签时调用(遍历受影响的资源)
+/// - ImportService 在批量导入后一次性调用
 /// - MaintenanceService 在重建索引时调用
 pub struct IndexerService;
 
@@ -29,7 +30,7 @@ impl IndexerService {
     ///
     /// # 工作流程
     /// 1. 查询资源的完整数据 (title, description, url, content)
-    /// 2. 查询资源关联的所有标签名称
+    /// 2. 查询资源关联的所有标签名称, 以及离线存档或上传文件的正文
     /// 3. 根据配置处理文本 (默认: 原始文本, jieba: 分词预处理)
     /// 4. 更新或插入 FTS 索引记录
     pub async fn index_resource(
@@ -76,12 +77,26 @@ impl IndexerService {
             .map(|row| row.get::<String, _>("name"))
             .collect();
 
+        // 链接的离线存档正文或上传文件提取的正文
+        let extracted_text: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(
+                (SELECT text FROM resource_archives WHERE resource_id = $1),
+                (SELECT extracted_text FROM resource_files WHERE resource_id = $1)
+            )
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
         // 3. 准备 FTS 数据 (根据配置选择分词策略)
         let title_keywords = prepare_for_search(Some(&resource.title));
         let description_keywords = prepare_for_search(resource.description.as_deref());
         let content_keywords = prepare_for_search(resource.content.as_deref());
         let tags_keywords = prepare_tags_for_search(&tag_names);
         let url_text = resource.url.unwrap_or_default();
+        let extracted_keywords = prepare_for_search(extracted_text.as_deref());
 
         // 4. 检查 FTS 记录是否存在
         let exists: bool =
@@ -95,8 +110,9 @@ impl IndexerService {
             sqlx::query(
                 r#"
                 UPDATE resources_fts
-                SET title = $1, description = $2, content = $3, tags = $4, url = $5
-                WHERE rowid = $6
+                SET title = $1, description = $2, content = $3, tags = $4, url = $5,
+                    extracted_text = $6
+                WHERE rowid = $7
                 "#,
             )
             .bind(title_keywords)
@@ -104,6 +120,7 @@ impl IndexerService {
             .bind(content_keywords)
             .bind(tags_keywords)
             .bind(url_text)
+            .bind(extracted_keywords)
             .bind(resource_id)
             .execute(&mut **tx)
             .await?;
@@ -111,8 +128,10 @@ impl IndexerService {
             // 插入新的 FTS 索引
             sqlx::query(
                 r#"
-                INSERT INTO resources_fts (rowid, title, description, content, tags, url)
-                VALUES ($1, $2, $3, $4, $5, $6)
+                INSERT INTO resources_fts (
+                    rowid, title, description, content, tags, url, extracted_text
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
                 "#,
             )
             .bind(resource_id)
@@ -121,6 +140,7 @@ impl IndexerService {
             .bind(content_keywords)
             .bind(tags_keywords)
             .bind(url_text)
+            .bind(extracted_keywords)
             .execute(&mut **tx)
             .await?;
         }
@@ -128,6 +148,21 @@ impl IndexerService {
         Ok(())
     }
 
+    /// 在同一事务中为多个资源建立索引
+    ///
+    /// 用于导入等批量写入场景, 避免为每个资源单独开启后台任务和事务
+    pub async fn index_resources(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        resource_ids: &[i64],
+        user_id: i64,
+    ) -> AppResult<u64> {
+        for &resource_id in resource_ids {
+            Self::index_resource(tx, resource_id, user_id).await?;
+        }
+
+        Ok(resource_ids.len() as u64)
+    }
+
     /// 批量重建 FTS 索引
     ///
     /// 用于维护服务或数据迁移场景
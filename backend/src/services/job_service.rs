@@ -0,0 +1,271 @@
+//! 后台任务服务
+//!
+//! 任务保存在 background_jobs 表中, 与触发它的业务写入处于同一事务,
+//! 因此事务回滚 (例如导入的 dry_run) 时任务也不会入队。
+//! 单个 worker 轮询领取任务, 成功后删除, 失败按配置延迟重试,
+//! 超过最大尝试次数后保留为 failed 状态。
+//! 队列空闲时 worker 还会清理 storage_deletions 中登记的存储对象。
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::SqlitePool;
+use tracing::{error, info, warn};
+
+use crate::config::{ArchiveConfig, FetcherConfig, JobsConfig};
+use crate::models::{BackgroundJob, JobKind, JobStatus};
+use crate::services::storage::Storage;
+use crate::services::{ArchiveService, FileService, MetadataService};
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::http_client;
+
+pub struct JobService;
+
+impl JobService {
+    /// 在事务中入队, 同一资源已有相同类型的待处理任务时忽略
+    pub async fn enqueue(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        kind: JobKind,
+        user_id: i64,
+        resource_id: i64,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO background_jobs (kind, user_id, resource_id)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(kind.as_str())
+        .bind(user_id)
+        .bind(resource_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 领取下一个到期的待处理任务, 领取即计入一次尝试
+    pub async fn claim_next(db_pool: &SqlitePool) -> AppResult<Option<BackgroundJob>> {
+        let job = sqlx::query_as::<_, BackgroundJob>(
+            r#"
+            UPDATE background_jobs
+            SET status = $1,
+                attempts = attempts + 1,
+                updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+            WHERE id = (
+                SELECT id FROM background_jobs
+                WHERE status = $2 AND run_after <= CAST(strftime('%s', 'now') AS INTEGER)
+                ORDER BY run_after, id
+                LIMIT 1
+            )
+            RETURNING id, kind, user_id, resource_id, status, attempts, last_error,
+                      run_after, created_at, updated_at
+            "#,
+        )
+        .bind(JobStatus::Running.as_str())
+        .bind(JobStatus::Pending.as_str())
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// 任务成功, 直接删除
+    pub async fn complete(job_id: i64, db_pool: &SqlitePool) -> AppResult<()> {
+        sqlx::query("DELETE FROM background_jobs WHERE id = $1")
+            .bind(job_id)
+            .execute(db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 任务失败: 未超过最大尝试次数时重新排队, 延迟随尝试次数递增
+    pub async fn fail(
+        job: &BackgroundJob,
+        error_message: &str,
+        config: &JobsConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        if job.attempts >= config.max_attempts {
+            sqlx::query(
+                r#"
+                UPDATE background_jobs
+                SET status = $1, last_error = $2,
+                    updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+                WHERE id = $3
+                "#,
+            )
+            .bind(JobStatus::Failed.as_str())
+            .bind(error_message)
+            .bind(job.id)
+            .execute(db_pool)
+            .await?;
+
+            return Ok(());
+        }
+
+        // 执行期间同一资源可能已重新入队, 此时保留新任务, 丢弃当前任务
+        let result = sqlx::query(
+            r#"
+            UPDATE OR IGNORE background_jobs
+            SET status = $1, last_error = $2,
+                run_after = CAST(strftime('%s', 'now') AS INTEGER) + $3,
+                updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+            WHERE id = $4
+            "#,
+        )
+        .bind(JobStatus::Pending.as_str())
+        .bind(error_message)
+        .bind(config.retry_delay_secs * job.attempts)
+        .bind(job.id)
+        .execute(db_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            Self::complete(job.id, db_pool).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 启动时把上次异常退出遗留的 running 任务放回队列
+    pub async fn reset_running(db_pool: &SqlitePool) -> AppResult<u64> {
+        let result =
+            sqlx::query("UPDATE OR IGNORE background_jobs SET status = $1 WHERE status = $2")
+                .bind(JobStatus::Pending.as_str())
+                .bind(JobStatus::Running.as_str())
+                .execute(db_pool)
+                .await?;
+
+        // 与新任务冲突而未能放回的遗留任务直接删除
+        sqlx::query("DELETE FROM background_jobs WHERE status = $1")
+            .bind(JobStatus::Running.as_str())
+            .execute(db_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// 后台任务 worker
+pub struct JobWorker {
+    db_pool: SqlitePool,
+    client: reqwest::Client,
+    jobs_config: JobsConfig,
+    fetcher_config: FetcherConfig,
+    archive_config: ArchiveConfig,
+    storage: Arc<dyn Storage>,
+}
+
+impl JobWorker {
+    pub fn new(
+        db_pool: SqlitePool,
+        jobs_config: JobsConfig,
+        fetcher_config: FetcherConfig,
+        archive_config: ArchiveConfig,
+        storage: Arc<dyn Storage>,
+    ) -> AppResult<Self> {
+        let client = http_client::build_client(&fetcher_config)?;
+
+        Ok(Self {
+            db_pool,
+            client,
+            jobs_config,
+            fetcher_config,
+            archive_config,
+            storage,
+        })
+    }
+
+    /// 持续处理任务, 队列为空时按 poll_interval_secs 轮询
+    pub async fn run(self) {
+        match JobService::reset_running(&self.db_pool).await {
+            Ok(0) => {}
+            Ok(count) => info!("重新排队 {} 个未完成的后台任务", count),
+            Err(e) => error!("重置后台任务状态失败: {}", e),
+        }
+
+        let poll_interval = Duration::from_secs(self.jobs_config.poll_interval_secs);
+        loop {
+            match self.process_next().await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => error!("后台任务队列读取失败: {}", e),
+            }
+
+            // 队列空闲时清理已删除资源的存储对象
+            match FileService::purge_deleted(self.storage.as_ref(), &self.db_pool).await {
+                Ok(0) => {}
+                Ok(count) => info!("已删除 {} 个存储对象", count),
+                Err(e) => error!("清理存储对象失败: {}", e),
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// 处理一个到期任务, 队列为空时返回 false
+    pub async fn process_next(&self) -> AppResult<bool> {
+        let Some(job) = JobService::claim_next(&self.db_pool).await? else {
+            return Ok(false);
+        };
+
+        match self.execute(&job).await {
+            Ok(()) => JobService::complete(job.id, &self.db_pool).await?,
+            Err(e) => {
+                warn!(
+                    "后台任务 {} ({}) 第 {} 次执行失败: {}",
+                    job.id, job.kind, job.attempts, e
+                );
+                JobService::fail(&job, &e.to_string(), &self.jobs_config, &self.db_pool).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn execute(&self, job: &BackgroundJob) -> AppResult<()> {
+        let kind = JobKind::from(&job.kind).map_err(AppError::Internal)?;
+        let resource_id = job
+            .resource_id
+            .ok_or_else(|| AppError::Internal(format!("Job {} has no resource", job.id)))?;
+
+        match kind {
+            JobKind::FetchMetadata => {
+                let fetched = MetadataService::refresh_resource(
+                    resource_id,
+                    job.user_id,
+                    &self.client,
+                    &self.fetcher_config,
+                    &self.db_pool,
+                )
+                .await?;
+
+                // 开启离线存档时复用已抓取的页面, 不再重复请求
+                match fetched {
+                    Some((url, page)) if self.archive_config.enabled && page.is_html() => {
+                        ArchiveService::store_page(
+                            resource_id,
+                            job.user_id,
+                            &url,
+                            &page,
+                            &self.archive_config,
+                            &self.db_pool,
+                        )
+                        .await?;
+                        Ok(())
+                    }
+                    _ => Ok(()),
+                }
+            }
+            JobKind::ExtractText => {
+                FileService::extract_text(
+                    job.user_id,
+                    resource_id,
+                    self.storage.as_ref(),
+                    &self.db_pool,
+                )
+                .await
+            }
+        }
+    }
+}
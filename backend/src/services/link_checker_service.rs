@@ -0,0 +1,304 @@
+//! 失效链接检查服务
+//!
+//! 定时挑选超过 recheck_after_secs 未检查的链接资源, 以 HEAD 请求探测
+//! (服务器不支持 HEAD 时退回 GET), 结果写入 link_checks 表。
+//! 并发数和对同一主机的请求间隔由 LinkCheckerConfig 控制。
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{error, info};
+
+use crate::config::{FetcherConfig, LinkCheckerConfig};
+use crate::models::LinkCheck;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::http_client;
+
+/// 单次探测结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkProbe {
+    pub status_code: Option<u16>,
+    /// 重定向后的最终地址, 未重定向时为 None
+    pub final_url: Option<String>,
+    pub error: Option<String>,
+}
+
+impl LinkProbe {
+    /// 网络错误或 4xx/5xx 视为失败
+    pub fn is_failure(&self) -> bool {
+        self.error.is_some() || self.status_code.is_none_or(|status| status >= 400)
+    }
+}
+
+pub struct LinkCheckerService;
+
+impl LinkCheckerService {
+    /// 获取资源的链接检查结果, 资源不存在时返回 None
+    pub async fn get_link_check(
+        user_id: i64,
+        resource_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<LinkCheck>> {
+        let link_check = sqlx::query_as::<_, LinkCheck>(
+            r#"
+            SELECT
+                r.id AS resource_id,
+                CASE
+                    WHEN lc.resource_id IS NULL THEN 'unchecked'
+                    WHEN lc.consecutive_failures > 0 THEN 'broken'
+                    WHEN lc.final_url IS NOT NULL THEN 'redirected'
+                    ELSE 'ok'
+                END AS status,
+                lc.status_code, lc.final_url, lc.error,
+                COALESCE(lc.consecutive_failures, 0) AS consecutive_failures,
+                lc.last_checked_at, lc.last_success_at
+            FROM resources r
+            LEFT JOIN link_checks lc ON lc.resource_id = r.id
+            WHERE r.id = $1 AND r.user_id = $2
+            "#,
+        )
+        .bind(resource_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(link_check)
+    }
+
+    /// 删除检查结果, 用于 URL 变更后重新检查
+    pub async fn reset(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        resource_id: i64,
+    ) -> AppResult<()> {
+        sqlx::query("DELETE FROM link_checks WHERE resource_id = $1")
+            .bind(resource_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 探测链接是否可访问
+    pub async fn probe(client: &reqwest::Client, config: &FetcherConfig, url: &str) -> LinkProbe {
+        let target = match http_client::ensure_fetchable(url, config) {
+            Ok(target) => target,
+            Err(e) => {
+                return LinkProbe {
+                    status_code: None,
+                    final_url: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        // 部分服务器不支持 HEAD 或对 HEAD 返回错误状态, 此时改用 GET (不读取响应体)
+        let response = match client.head(target.clone()).send().await {
+            Ok(response) if !matches!(response.status().as_u16(), 403 | 405 | 501) => Ok(response),
+            _ => client.get(target.clone()).send().await,
+        };
+
+        match response {
+            Ok(response) => LinkProbe {
+                status_code: Some(response.status().as_u16()),
+                final_url: (response.url() != &target).then(|| response.url().to_string()),
+                error: None,
+            },
+            Err(e) => LinkProbe {
+                status_code: None,
+                final_url: None,
+                error: Some(AppError::from(e).to_string()),
+            },
+        }
+    }
+
+    /// 保存探测结果
+    ///
+    /// 以探测时的 URL 作为条件, 探测期间 URL 被修改时放弃本次结果
+    pub async fn record(
+        resource_id: i64,
+        url: &str,
+        probe: &LinkProbe,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO link_checks (
+                resource_id, status_code, final_url, error,
+                consecutive_failures, last_checked_at, last_success_at
+            )
+            SELECT id, $1, $2, $3, $4,
+                   CAST(strftime('%s', 'now') AS INTEGER),
+                   CASE WHEN $4 = 0 THEN CAST(strftime('%s', 'now') AS INTEGER) END
+            FROM resources
+            WHERE id = $5 AND url = $6
+            ON CONFLICT(resource_id) DO UPDATE SET
+                status_code = excluded.status_code,
+                final_url = excluded.final_url,
+                error = excluded.error,
+                consecutive_failures = CASE WHEN excluded.consecutive_failures = 0
+                                            THEN 0
+                                            ELSE link_checks.consecutive_failures + 1 END,
+                last_checked_at = excluded.last_checked_at,
+                last_success_at = COALESCE(excluded.last_success_at, link_checks.last_success_at)
+            "#,
+        )
+        .bind(probe.status_code.map(i64::from))
+        .bind(probe.final_url.as_ref())
+        .bind(probe.error.as_ref())
+        .bind(i64::from(probe.is_failure()))
+        .bind(resource_id)
+        .bind(url)
+        .execute(db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 挑选需要检查的链接: 从未检查过的优先, 其次是最久未检查的
+    async fn due_links(
+        recheck_after_secs: i64,
+        limit: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, String)>> {
+        let links = sqlx::query_as(
+            r#"
+            SELECT r.id, r.url
+            FROM resources r
+            LEFT JOIN link_checks lc ON lc.resource_id = r.id
+            WHERE r.type = 'link' AND r.url IS NOT NULL
+              AND (lc.resource_id IS NULL
+                   OR lc.last_checked_at <= CAST(strftime('%s', 'now') AS INTEGER) - $1)
+            ORDER BY lc.last_checked_at IS NOT NULL, lc.last_checked_at, r.id
+            LIMIT $2
+            "#,
+        )
+        .bind(recheck_after_secs)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(links)
+    }
+}
+
+/// 按主机限速: 同一主机的两次请求之间至少间隔 min_interval
+pub struct HostRateLimiter {
+    min_interval: Duration,
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 等待直到可以向该主机发送请求
+    pub async fn acquire(&self, host: &str) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot
+                .get(host)
+                .copied()
+                .filter(|slot| *slot > now)
+                .unwrap_or(now);
+            next_slot.insert(host.to_string(), slot + self.min_interval);
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// 定时链接检查器
+pub struct LinkChecker {
+    db_pool: SqlitePool,
+    client: reqwest::Client,
+    config: LinkCheckerConfig,
+    fetcher_config: FetcherConfig,
+}
+
+impl LinkChecker {
+    pub fn new(
+        db_pool: SqlitePool,
+        config: LinkCheckerConfig,
+        fetcher_config: FetcherConfig,
+    ) -> AppResult<Self> {
+        let client = http_client::build_client(&fetcher_config)?;
+
+        Ok(Self {
+            db_pool,
+            client,
+            config,
+            fetcher_config,
+        })
+    }
+
+    /// 按 interval_secs 定时检查
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.interval_secs));
+        loop {
+            interval.tick().await;
+            match self.check_due().await {
+                Ok(0) => {}
+                Ok(count) => info!("链接检查完成, 共检查 {} 个链接", count),
+                Err(e) => error!("链接检查失败: {}", e),
+            }
+        }
+    }
+
+    /// 检查一批到期的链接, 返回检查数量
+    pub async fn check_due(&self) -> AppResult<usize> {
+        let links = LinkCheckerService::due_links(
+            self.config.recheck_after_secs,
+            self.config.batch_size,
+            &self.db_pool,
+        )
+        .await?;
+        let count = links.len();
+
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency.max(1)));
+        let limiter = Arc::new(HostRateLimiter::new(Duration::from_millis(
+            self.config.per_host_interval_ms,
+        )));
+        let fetcher_config = Arc::new(self.fetcher_config.clone());
+
+        let mut tasks = JoinSet::new();
+        for (resource_id, url) in links {
+            let semaphore = semaphore.clone();
+            let limiter = limiter.clone();
+            let fetcher_config = fetcher_config.clone();
+            let client = self.client.clone();
+            let db_pool = self.db_pool.clone();
+
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                let host = url::Url::parse(&url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(str::to_lowercase))
+                    .unwrap_or_default();
+                limiter.acquire(&host).await;
+
+                let probe = LinkCheckerService::probe(&client, &fetcher_config, &url).await;
+                LinkCheckerService::record(resource_id, &url, &probe, &db_pool).await
+            });
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error!("保存链接检查结果失败: {}", e),
+                Err(e) => error!("链接检查任务异常退出: {}", e),
+            }
+        }
+
+        Ok(count)
+    }
+}
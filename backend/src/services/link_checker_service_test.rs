@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use axum::{
+    http::{Method, StatusCode},
+    response::Redirect,
+    routing::{any, get},
+    Router,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::config::{FetcherConfig, LinkCheckerConfig};
+use crate::models::{CreateResource, ResourceQuery, UpdateResource};
+use crate::services::link_checker_service::{HostRateLimiter, LinkChecker, LinkCheckerService};
+use crate::services::resource_service::ResourceService;
+use crate::services::test_support::{self, create_test_user, spawn_stub_server};
+
+async fn create_test_pool() -> SqlitePool {
+    let pool = test_support::create_test_pool().await;
+    // 种子数据中的链接指向外网, 测试中只检查本地模拟网站
+    sqlx::query("DELETE FROM resources")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+/// 模拟网站的路由
+fn stub_site() -> Router {
+    Router::new()
+        .route("/ok", get(|| async { "ok" }))
+        .route("/gone", get(|| async { StatusCode::NOT_FOUND }))
+        .route("/moved", get(|| async { Redirect::permanent("/ok") }))
+        // 只接受 GET 的服务器, HEAD 返回 405
+        .route(
+            "/get-only",
+            any(|method: Method| async move {
+                if method == Method::GET {
+                    StatusCode::OK
+                } else {
+                    StatusCode::METHOD_NOT_ALLOWED
+                }
+            }),
+        )
+}
+
+fn test_checker(pool: &SqlitePool) -> LinkChecker {
+    LinkChecker::new(
+        pool.clone(),
+        LinkCheckerConfig {
+            recheck_after_secs: 0,
+            per_host_interval_ms: 0,
+            ..Default::default()
+        },
+        FetcherConfig {
+            allow_private_networks: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+async fn create_link(pool: &SqlitePool, user_id: i64, title: &str, url: &str) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: Some(url.to_string()),
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "link".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    };
+
+    ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn titles_with_status(pool: &SqlitePool, user_id: i64, link_status: &str) -> Vec<String> {
+    let query = ResourceQuery {
+        link_status: Some(link_status.to_string()),
+        sort_by: Some("title".to_string()),
+        sort_order: Some("asc".to_string()),
+        ..Default::default()
+    };
+
+    ResourceService::get_resources(user_id, query, pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|resource| resource.resource.title)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_check_due_records_status_and_filters() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "checker").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let checker = test_checker(&pool);
+
+    let ok_id = create_link(&pool, user_id, "Ok", &format!("{}/ok", base)).await;
+    let gone_id = create_link(&pool, user_id, "Gone", &format!("{}/gone", base)).await;
+    let moved_id = create_link(&pool, user_id, "Moved", &format!("{}/moved", base)).await;
+    create_link(&pool, user_id, "Get only", &format!("{}/get-only", base)).await;
+
+    assert_eq!(
+        titles_with_status(&pool, user_id, "unchecked").await,
+        vec!["Get only", "Gone", "Moved", "Ok"]
+    );
+
+    assert_eq!(checker.check_due().await.unwrap(), 4);
+
+    assert!(titles_with_status(&pool, user_id, "unchecked")
+        .await
+        .is_empty());
+    assert_eq!(
+        titles_with_status(&pool, user_id, "ok").await,
+        vec!["Get only", "Ok"]
+    );
+    assert_eq!(
+        titles_with_status(&pool, user_id, "broken").await,
+        vec!["Gone"]
+    );
+    assert_eq!(
+        titles_with_status(&pool, user_id, "redirected").await,
+        vec!["Moved"]
+    );
+
+    let ok = LinkCheckerService::get_link_check(user_id, ok_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ok.status, "ok");
+    assert_eq!(ok.status_code, Some(200));
+    assert!(ok.last_success_at.is_some());
+
+    let moved = LinkCheckerService::get_link_check(user_id, moved_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(moved.final_url, Some(format!("{}/ok", base)));
+
+    let gone = LinkCheckerService::get_link_check(user_id, gone_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(gone.status, "broken");
+    assert_eq!(gone.status_code, Some(404));
+    assert_eq!(gone.consecutive_failures, 1);
+    assert!(gone.last_success_at.is_none());
+
+    // 再次检查时连续失败次数累加
+    checker.check_due().await.unwrap();
+    let gone = LinkCheckerService::get_link_check(user_id, gone_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(gone.consecutive_failures, 2);
+
+    // 修复 URL 后清除检查结果, 下一轮检查恢复正常
+    let update: UpdateResource =
+        serde_json::from_value(json!({ "url": format!("{}/ok", base) })).unwrap();
+    ResourceService::update_resource(user_id, gone_id, update, &pool)
+        .await
+        .unwrap();
+    let gone = LinkCheckerService::get_link_check(user_id, gone_id, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(gone.status, "unchecked");
+    assert_eq!(gone.consecutive_failures, 0);
+
+    checker.check_due().await.unwrap();
+    assert!(titles_with_status(&pool, user_id, "broken")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_check_due_skips_recently_checked_links() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "checker").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let checker = LinkChecker::new(
+        pool.clone(),
+        LinkCheckerConfig {
+            per_host_interval_ms: 0,
+            ..Default::default()
+        },
+        FetcherConfig {
+            allow_private_networks: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    create_link(&pool, user_id, "Ok", &format!("{}/ok", base)).await;
+    assert_eq!(checker.check_due().await.unwrap(), 1);
+    assert_eq!(checker.check_due().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_invalid_link_status_filter() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "checker").await;
+
+    let query = ResourceQuery {
+        link_status: Some("dead".to_string()),
+        ..Default::default()
+    };
+    assert!(ResourceService::get_resources(user_id, query, &pool)
+        .await
+        .is_err());
+    assert!(LinkCheckerService::get_link_check(user_id, 999_999, &pool)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_host_rate_limiter_spaces_requests_per_host() {
+    let limiter = HostRateLimiter::new(Duration::from_millis(50));
+    let start = tokio::time::Instant::now();
+
+    limiter.acquire("example.com").await;
+    limiter.acquire("other.example").await;
+    assert!(start.elapsed() < Duration::from_millis(50));
+
+    limiter.acquire("example.com").await;
+    limiter.acquire("example.com").await;
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
@@ -0,0 +1,35 @@
+//! 测试使用的邮件后端, 把邮件保存在内存中
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use super::{Email, Mailer};
+use crate::utils::error::AppResult;
+
+#[derive(Default)]
+pub struct MemoryMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+impl MemoryMailer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已发送的邮件, 按发送顺序
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn send(&self, email: &Email) -> AppResult<()> {
+        self.sent.lock().unwrap().push(email.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+//! 邮件发送
+//!
+//! 每种后端实现 `Mailer`, 只负责投递已经生成好的邮件;
+//! 邮件内容 (重置密码链接等) 由调用方生成。
+//! 默认的 OutboxMailer 不需要邮件服务器, 把邮件写入日志和 outbox 目录;
+//! 测试中使用 MemoryMailer 检查发出的邮件。
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::{MailBackend, MailConfig};
+use crate::utils::error::AppResult;
+
+#[cfg(test)]
+pub mod memory;
+pub mod outbox;
+pub mod smtp;
+
+#[cfg(test)]
+pub use memory::MemoryMailer;
+pub use outbox::OutboxMailer;
+pub use smtp::SmtpMailer;
+
+/// 一封纯文本邮件
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// 邮件发送后端
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// 后端名称, 与 MailBackend 的序列化值一致
+    fn name(&self) -> &'static str;
+
+    /// 发送邮件
+    async fn send(&self, email: &Email) -> AppResult<()>;
+}
+
+/// 根据配置创建邮件发送后端
+pub fn build_mailer(config: &MailConfig) -> AppResult<Arc<dyn Mailer>> {
+    let mailer: Arc<dyn Mailer> = match config.backend {
+        MailBackend::Outbox => Arc::new(OutboxMailer::new(
+            &config.from,
+            config.outbox_dir.as_deref(),
+        )),
+        MailBackend::Smtp => Arc::new(SmtpMailer::new(&config.from, &config.smtp)?),
+    };
+
+    Ok(mailer)
+}
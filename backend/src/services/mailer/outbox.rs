@@ -0,0 +1,102 @@
+//! 不需要邮件服务器的发送后端: 写入日志, 配置了目录时同时保存为 .eml 文件
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{Email, Mailer};
+use crate::utils::error::{AppError, AppResult};
+
+/// 邮件保存为 `{dir}/{时间戳}-{uuid}.eml`, 文件名按发送时间排序
+pub struct OutboxMailer {
+    from: String,
+    dir: Option<PathBuf>,
+}
+
+impl OutboxMailer {
+    pub fn new(from: &str, dir: Option<&str>) -> Self {
+        Self {
+            from: from.to_string(),
+            dir: dir.filter(|dir| !dir.is_empty()).map(PathBuf::from),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    fn name(&self) -> &'static str {
+        "outbox"
+    }
+
+    async fn send(&self, email: &Email) -> AppResult<()> {
+        let Some(dir) = &self.dir else {
+            tracing::info!(
+                "Mail to {} (subject: {}):\n{}",
+                email.to,
+                email.subject,
+                email.body
+            );
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        let message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from,
+            email.to,
+            email.subject,
+            now.to_rfc2822(),
+            email.body
+        );
+
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create outbox: {}", e)))?;
+        let path = dir.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%d%H%M%S%6f"),
+            Uuid::new_v4().simple()
+        ));
+        tokio::fs::write(&path, message)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to write outbox mail: {}", e)))?;
+
+        tracing::info!(
+            "Mail to {} (subject: {}) written to {}",
+            email.to,
+            email.subject,
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outbox_mailer_writes_eml_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mailer = OutboxMailer::new("noreply@example.com", dir.path().to_str());
+
+        mailer
+            .send(&Email {
+                to: "user@example.com".to_string(),
+                subject: "Hello".to_string(),
+                body: "line one\nline two".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut entries = std::fs::read_dir(dir.path()).unwrap();
+        let path = entries.next().unwrap().unwrap().path();
+        assert!(entries.next().is_none());
+        assert_eq!(path.extension().unwrap(), "eml");
+        let message = std::fs::read_to_string(path).unwrap();
+        assert!(message.starts_with("From: noreply@example.com\r\nTo: user@example.com\r\n"));
+        assert!(message.contains("Subject: Hello\r\n"));
+        assert!(message.ends_with("\r\n\r\nline one\nline two\r\n"));
+    }
+}
@@ -0,0 +1,156 @@
+//! 通过 SMTP 服务器发送邮件
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{Email, Mailer};
+use crate::config::{SmtpConfig, SmtpTls};
+use crate::utils::error::{AppError, AppResult};
+
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(from: &str, config: &SmtpConfig) -> AppResult<Self> {
+        if config.host.is_empty() {
+            return Err(AppError::Internal(
+                "SMTP host is not configured".to_string(),
+            ));
+        }
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::Internal(format!("Invalid mail sender address: {}", e)))?;
+
+        let builder = match config.tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &config.host,
+            )),
+        }
+        .map_err(|e| AppError::Internal(format!("Invalid SMTP host: {}", e)))?;
+
+        let mut builder = builder
+            .port(config.port)
+            .timeout(Some(Duration::from_secs(config.timeout_secs)));
+        if !config.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            ));
+        }
+
+        Ok(Self {
+            from,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, email: &Email) -> AppResult<()> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::Internal(format!("Invalid recipient address: {}", e)))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.as_str())
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())
+            .map_err(|e| AppError::Internal(format!("Failed to build mail: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to send mail via SMTP: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// 只实现发送一封邮件所需命令的 SMTP 服务器, 返回收到的 DATA 内容
+    async fn serve_one_mail(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+
+        let mut data = String::new();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let command = line.to_ascii_uppercase();
+            if command.starts_with("EHLO") {
+                writer.write_all(b"250 localhost\r\n").await.unwrap();
+            } else if command.starts_with("DATA") {
+                writer.write_all(b"354 go ahead\r\n").await.unwrap();
+                while let Some(line) = lines.next_line().await.unwrap() {
+                    if line == "." {
+                        break;
+                    }
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                writer.write_all(b"250 queued\r\n").await.unwrap();
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                writer.write_all(b"250 ok\r\n").await.unwrap();
+            }
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_smtp_mailer_sends_message() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(serve_one_mail(listener));
+
+        let config = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            tls: SmtpTls::None,
+            ..SmtpConfig::default()
+        };
+        let mailer = SmtpMailer::new("noreply@example.com", &config).unwrap();
+        mailer
+            .send(&Email {
+                to: "user@example.com".to_string(),
+                subject: "Verify your email".to_string(),
+                body: "hello".to_string(),
+            })
+            .await
+            .unwrap();
+        drop(mailer);
+
+        let data = server.await.unwrap();
+        assert!(data.contains("From: noreply@example.com"));
+        assert!(data.contains("To: user@example.com"));
+        assert!(data.contains("Subject: Verify your email"));
+        assert!(data.trim_end().ends_with("hello"));
+    }
+
+    #[test]
+    fn test_smtp_mailer_requires_host() {
+        let result = SmtpMailer::new("noreply@example.com", &SmtpConfig::default());
+        assert!(matches!(result, Err(AppError::Internal(_))));
+    }
+}
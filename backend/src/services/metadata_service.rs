@@ -0,0 +1,131 @@
+//! 链接元数据服务
+//!
+//! 由后台任务调用: 抓取链接页面, 提取标题、描述、预览图和站点图标后写回资源。
+//! 用户填写的标题和描述不会被覆盖, 只在为空 (或标题就是 URL) 时补全。
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::config::FetcherConfig;
+use crate::services::IndexerService;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::http_client::{self, FetchedPage};
+use crate::utils::page_metadata::extract_metadata;
+
+// 与 ResourceService 的输入长度限制保持一致
+const MAX_TITLE_LENGTH: usize = 500;
+const MAX_DESCRIPTION_LENGTH: usize = 2000;
+
+pub struct MetadataService;
+
+impl MetadataService {
+    /// 抓取链接资源的页面元数据并写回
+    ///
+    /// 资源已删除、不是链接或没有 URL 时直接返回 None;
+    /// 元数据写回成功时返回抓取到的页面及抓取时的 URL, 供离线存档复用
+    pub async fn refresh_resource(
+        resource_id: i64,
+        user_id: i64,
+        client: &reqwest::Client,
+        config: &FetcherConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<(String, FetchedPage)>> {
+        let resource: Option<(Option<String>, String)> =
+            sqlx::query_as("SELECT url, type FROM resources WHERE id = $1 AND user_id = $2")
+                .bind(resource_id)
+                .bind(user_id)
+                .fetch_optional(db_pool)
+                .await?;
+
+        let Some((Some(url), resource_type)) = resource else {
+            return Ok(None);
+        };
+        if resource_type != "link" {
+            return Ok(None);
+        }
+
+        let target = http_client::ensure_fetchable(&url, config)?;
+        let page = http_client::fetch_page(client, target, config.max_body_bytes).await?;
+        if !(200..300).contains(&page.status) {
+            return Err(AppError::Internal(format!(
+                "Unexpected HTTP status {} from {}",
+                page.status, page.final_url
+            )));
+        }
+
+        // 非 HTML 内容 (图片、PDF 等) 只记录最终地址和默认图标
+        let body = if page.is_html() {
+            page.body.as_str()
+        } else {
+            ""
+        };
+        let metadata = extract_metadata(body, &page.final_url);
+
+        let title = metadata
+            .title
+            .as_deref()
+            .map(|title| truncate(title, MAX_TITLE_LENGTH));
+        let description = metadata
+            .description
+            .as_deref()
+            .map(|description| truncate(description, MAX_DESCRIPTION_LENGTH));
+        let page_metadata = json!({
+            "page": {
+                "title": metadata.title,
+                "description": metadata.description,
+                "image": metadata.image,
+                "canonical_url": metadata.canonical_url,
+                "favicon_url": metadata.favicon_url,
+                "site_name": metadata.site_name,
+                "final_url": page.final_url.as_str(),
+                "fetched_at": chrono::Utc::now().timestamp(),
+            }
+        });
+
+        // 以抓取时的 URL 作为条件, 抓取期间 URL 被修改时放弃本次结果 (新 URL 已重新入队)
+        // 元数据由系统补全, 不更新 updated_at
+        let result = sqlx::query(
+            r#"
+            UPDATE resources SET
+                title = CASE WHEN trim(title) = '' OR title = url
+                             THEN COALESCE($1, title) ELSE title END,
+                description = CASE WHEN description IS NULL OR trim(description) = ''
+                                   THEN COALESCE($2, description) ELSE description END,
+                favicon_url = COALESCE($3, favicon_url),
+                thumbnail_url = COALESCE($4, thumbnail_url),
+                metadata = json_patch(COALESCE(metadata, '{}'), $5)
+            WHERE id = $6 AND user_id = $7 AND url = $8
+            "#,
+        )
+        .bind(title)
+        .bind(description)
+        .bind(metadata.favicon_url.as_ref())
+        .bind(metadata.image.as_ref())
+        .bind(page_metadata.to_string())
+        .bind(resource_id)
+        .bind(user_id)
+        .bind(&url)
+        .execute(db_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        IndexerService::index_resource_with_pool(db_pool, resource_id, user_id).await?;
+
+        Ok(Some((url, page)))
+    }
+}
+
+/// 按字节长度截断, 保证不切断多字节字符
+fn truncate(text: &str, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text.to_string();
+    }
+
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
+}
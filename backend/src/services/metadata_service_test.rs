@@ -0,0 +1,272 @@
+use axum::{
+    http::{header, StatusCode},
+    response::{Html, Redirect},
+    routing::get,
+    Router,
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::config::{ArchiveConfig, FetcherConfig, JobsConfig};
+use crate::models::{BackgroundJob, CreateResource, Resource, UpdateResource};
+use crate::services::job_service::JobWorker;
+use crate::services::resource_service::ResourceService;
+use crate::services::test_support::{
+    create_test_pool, create_test_user, default_storage, spawn_stub_server, test_worker,
+};
+
+const ARTICLE_HTML: &str = r#"<!DOCTYPE html>
+<html><head>
+<title>Stub Article</title>
+<meta name="description" content="An article served by the stub server">
+<meta property="og:image" content="/cover.png">
+<meta property="og:site_name" content="Stub">
+<link rel="canonical" href="/article">
+<link rel="icon" href="/static/icon.png">
+</head><body><h1>Hello</h1></body></html>"#;
+
+/// 模拟网站的路由
+fn stub_site() -> Router {
+    Router::new()
+        .route("/article", get(|| async { Html(ARTICLE_HTML) }))
+        .route("/moved", get(|| async { Redirect::permanent("/article") }))
+        .route(
+            "/image.png",
+            get(|| async { ([(header::CONTENT_TYPE, "image/png")], vec![0u8; 16]) }),
+        )
+        .route("/missing", get(|| async { StatusCode::NOT_FOUND }))
+}
+
+fn link(title: &str, url: &str, description: Option<&str>) -> CreateResource {
+    CreateResource {
+        title: title.to_string(),
+        url: Some(url.to_string()),
+        description: description.map(str::to_string),
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "link".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    }
+}
+
+async fn load_resource(pool: &SqlitePool, resource_id: i64) -> Resource {
+    sqlx::query_as("SELECT * FROM resources WHERE id = $1")
+        .bind(resource_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn load_jobs(pool: &SqlitePool, user_id: i64) -> Vec<BackgroundJob> {
+    sqlx::query_as("SELECT * FROM background_jobs WHERE user_id = $1 ORDER BY id")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_fetch_metadata_fills_empty_fields() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(&pool, 3, ArchiveConfig::default(), default_storage());
+
+    // 标题与 URL 相同视为未填写
+    let url = format!("{}/moved", base);
+    let resource = ResourceService::create_resource(user_id, link(&url, &url, None), &pool)
+        .await
+        .unwrap();
+
+    let jobs = load_jobs(&pool, user_id).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].kind, "fetch_metadata");
+    assert_eq!(jobs[0].resource_id, Some(resource.id));
+
+    assert!(worker.process_next().await.unwrap());
+    assert!(!worker.process_next().await.unwrap());
+    assert!(load_jobs(&pool, user_id).await.is_empty());
+
+    let resource = load_resource(&pool, resource.id).await;
+    assert_eq!(resource.title, "Stub Article");
+    assert_eq!(
+        resource.description.as_deref(),
+        Some("An article served by the stub server")
+    );
+    assert_eq!(
+        resource.favicon_url,
+        Some(format!("{}/static/icon.png", base))
+    );
+    assert_eq!(resource.thumbnail_url, Some(format!("{}/cover.png", base)));
+    // 用户提交的 URL 保持不变, 重定向后的地址记录在 metadata 中
+    assert_eq!(resource.url, Some(url));
+
+    let page = &resource.metadata["page"];
+    assert_eq!(page["final_url"], format!("{}/article", base));
+    assert_eq!(page["canonical_url"], format!("{}/article", base));
+    assert_eq!(page["site_name"], "Stub");
+    assert!(page["fetched_at"].as_i64().is_some());
+}
+
+#[tokio::test]
+async fn test_fetch_metadata_keeps_user_title_and_description() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(&pool, 3, ArchiveConfig::default(), default_storage());
+
+    let url = format!("{}/article", base);
+    let resource =
+        ResourceService::create_resource(user_id, link("My title", &url, Some("My notes")), &pool)
+            .await
+            .unwrap();
+    assert!(worker.process_next().await.unwrap());
+
+    let resource = load_resource(&pool, resource.id).await;
+    assert_eq!(resource.title, "My title");
+    assert_eq!(resource.description.as_deref(), Some("My notes"));
+    assert_eq!(
+        resource.favicon_url,
+        Some(format!("{}/static/icon.png", base))
+    );
+    assert_eq!(resource.metadata["page"]["title"], "Stub Article");
+}
+
+#[tokio::test]
+async fn test_fetch_metadata_for_non_html_content() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(&pool, 3, ArchiveConfig::default(), default_storage());
+
+    let url = format!("{}/image.png", base);
+    let resource = ResourceService::create_resource(user_id, link("Image", &url, None), &pool)
+        .await
+        .unwrap();
+    assert!(worker.process_next().await.unwrap());
+
+    let resource = load_resource(&pool, resource.id).await;
+    assert_eq!(resource.title, "Image");
+    assert!(resource.description.is_none());
+    assert!(resource.thumbnail_url.is_none());
+    assert_eq!(resource.favicon_url, Some(format!("{}/favicon.ico", base)));
+}
+
+#[tokio::test]
+async fn test_update_resource_requeues_only_when_url_changes() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(&pool, 3, ArchiveConfig::default(), default_storage());
+
+    // 笔记不抓取元数据
+    let note = CreateResource {
+        content: Some("just a note".to_string()),
+        url: None,
+        resource_type: "note".to_string(),
+        ..link("Note", "", None)
+    };
+    ResourceService::create_resource(user_id, note, &pool)
+        .await
+        .unwrap();
+    assert!(load_jobs(&pool, user_id).await.is_empty());
+
+    let url = format!("{}/article", base);
+    let resource = ResourceService::create_resource(user_id, link("Link", &url, None), &pool)
+        .await
+        .unwrap();
+    assert!(worker.process_next().await.unwrap());
+
+    let update =
+        |value: serde_json::Value| -> UpdateResource { serde_json::from_value(value).unwrap() };
+
+    // 修改标题或提交相同 URL 不重新抓取
+    ResourceService::update_resource(
+        user_id,
+        resource.id,
+        update(json!({ "title": "Renamed", "url": url, "type": "link" })),
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(load_jobs(&pool, user_id).await.is_empty());
+
+    ResourceService::update_resource(
+        user_id,
+        resource.id,
+        update(json!({ "url": format!("{}/moved", base) })),
+        &pool,
+    )
+    .await
+    .unwrap();
+    let jobs = load_jobs(&pool, user_id).await;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].resource_id, Some(resource.id));
+}
+
+#[tokio::test]
+async fn test_failed_fetch_retries_then_marks_failed() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = test_worker(&pool, 2, ArchiveConfig::default(), default_storage());
+
+    let url = format!("{}/missing", base);
+    ResourceService::create_resource(user_id, link("Missing", &url, None), &pool)
+        .await
+        .unwrap();
+
+    assert!(worker.process_next().await.unwrap());
+    let jobs = load_jobs(&pool, user_id).await;
+    assert_eq!(jobs[0].status, "pending");
+    assert_eq!(jobs[0].attempts, 1);
+    assert!(jobs[0].last_error.as_deref().unwrap().contains("404"));
+
+    assert!(worker.process_next().await.unwrap());
+    let jobs = load_jobs(&pool, user_id).await;
+    assert_eq!(jobs[0].status, "failed");
+    assert_eq!(jobs[0].attempts, 2);
+
+    // failed 任务不再被领取
+    assert!(!worker.process_next().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_private_addresses_rejected_by_default() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "fetcher").await;
+    let base = spawn_stub_server(stub_site()).await;
+    let worker = JobWorker::new(
+        pool.clone(),
+        JobsConfig {
+            max_attempts: 1,
+            ..Default::default()
+        },
+        FetcherConfig::default(),
+        ArchiveConfig::default(),
+        default_storage(),
+    )
+    .unwrap();
+
+    let url = format!("{}/article", base);
+    let resource = ResourceService::create_resource(user_id, link("Local", &url, None), &pool)
+        .await
+        .unwrap();
+    assert!(worker.process_next().await.unwrap());
+
+    let jobs = load_jobs(&pool, user_id).await;
+    assert_eq!(jobs[0].status, "failed");
+    assert!(jobs[0]
+        .last_error
+        .as_deref()
+        .unwrap()
+        .contains("private network"));
+    assert!(load_resource(&pool, resource.id)
+        .await
+        .favicon_url
+        .is_none());
+}
@@ -1,21 +1,80 @@
+pub mod access_token_service;
+pub mod archive_service;
 pub mod auth_service;
 pub mod collection_service;
+#[cfg(feature = "embeddings")]
+pub mod embedding;
+pub mod export_service;
+pub mod file_service;
+pub mod import_service;
+pub mod importers;
 pub mod indexer_service;
+pub mod job_service;
+pub mod mailer;
+pub mod link_checker_service;
 pub mod maintenance_service;
+pub mod metadata_service;
 pub mod query_helper;
+pub mod related_service;
 pub mod resource_service;
+pub mod saved_search_service;
+pub mod search_history_service;
 pub mod search_service;
+#[cfg(feature = "embeddings")]
+pub mod semantic_search_service;
 pub mod stats_service;
+pub mod storage;
 pub mod tag_service;
 
+pub use access_token_service::*;
+pub use archive_service::*;
 pub use auth_service::*;
 pub use collection_service::*;
+pub use export_service::*;
+pub use file_service::*;
+pub use import_service::*;
 pub use indexer_service::*;
+pub use job_service::*;
+pub use link_checker_service::*;
 pub use maintenance_service::*;
+pub use metadata_service::*;
+pub use related_service::*;
 pub use resource_service::*;
+pub use saved_search_service::*;
+pub use search_history_service::*;
 pub use search_service::*;
+#[cfg(feature = "embeddings")]
+pub use semantic_search_service::*;
 pub use stats_service::*;
 pub use tag_service::*;
 
+#[cfg(test)]
+mod access_token_service_test;
+#[cfg(test)]
+mod archive_service_test;
 #[cfg(test)]
 mod collection_service_test;
+#[cfg(test)]
+mod export_service_test;
+#[cfg(test)]
+mod file_service_test;
+#[cfg(test)]
+mod import_service_test;
+#[cfg(test)]
+mod link_checker_service_test;
+#[cfg(test)]
+mod metadata_service_test;
+#[cfg(test)]
+mod query_helper_test;
+#[cfg(test)]
+mod related_service_test;
+#[cfg(test)]
+mod saved_search_service_test;
+#[cfg(test)]
+mod search_history_service_test;
+#[cfg(test)]
+mod search_service_test;
+#[cfg(all(test, feature = "embeddings"))]
+mod semantic_search_service_test;
+#[cfg(test)]
+mod test_support;
@@ -1,25 +1,45 @@
-use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::collections::HashMap;
 
-use crate::models::{ResourceWithTags, SearchType};
-use crate::utils::error::AppResult;
+use chrono::Utc;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite, SqlitePool};
+
+use crate::config::RankingConfig;
+use crate::models::{
+    CollectionFacet, Cursor, CursorPage, FacetCount, LinkStatus, ResourceWithTags, SearchFacets,
+    SearchType, SortValue,
+};
+use crate::utils::error::{AppError, AppResult};
 
 pub struct QueryOptions<'a> {
     pub user_id: i64,
     pub collection_id: Option<i64>,
-    pub resource_type: Option<&'a str>,
+    /// 资源类型, 多个类型之间为 OR
+    pub resource_types: &'a [String],
     pub tags: &'a [String],
+    /// 排除带有任一标签的资源
+    pub exclude_tags: &'a [String],
     pub is_favorite: Option<bool>,
     pub is_archived: Option<bool>,
     pub is_private: Option<bool>,
     pub is_read: Option<bool>,
+    pub link_status: Option<LinkStatus>,
     pub search_term: Option<&'a str>,
     pub search_type: Option<&'a SearchType>, // Default to All if None
     pub date_from: Option<i64>,
     pub date_to: Option<i64>,
+    /// 链接主机名等于该域名或为其子域名
+    pub domain: Option<&'a str>,
+    /// 只包含这些资源
+    pub ids: Option<&'a [i64]>,
     pub limit: i64,
     pub offset: i64,
     pub sort_by: &'a str,
     pub sort_order: &'a str,
+    /// 按相关度排序时的权重, None 时使用默认配置
+    pub ranking: Option<&'a RankingConfig>,
+    /// 新近度加成的参考时间, None 时使用当前时间
+    pub ranked_at: Option<i64>,
 }
 
 impl Default for QueryOptions<'_> {
@@ -27,29 +47,33 @@ impl Default for QueryOptions<'_> {
         Self {
             user_id: 0,
             collection_id: None,
-            resource_type: None,
+            resource_types: &[],
             tags: &[],
+            exclude_tags: &[],
             is_favorite: None,
             is_archived: None,
             is_private: None,
             is_read: None,
+            link_status: None,
             search_term: None,
             search_type: None,
             date_from: None,
             date_to: None,
+            domain: None,
+            ids: None,
             limit: 50,
             offset: 0,
             sort_by: "created_at",
             sort_order: "desc",
+            ranking: None,
+            ranked_at: None,
         }
     }
 }
 
-pub async fn fetch_resources(
-    pool: &SqlitePool,
-    options: &QueryOptions<'_>,
-) -> AppResult<Vec<ResourceWithTags>> {
-    let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+/// 列表查询的 SELECT 与 FROM 部分, 额外返回当前排序列的取值 (sort_key) 用于生成游标
+fn select_resources<'a>(sort: &SortKey) -> QueryBuilder<'a, Sqlite> {
+    QueryBuilder::new(format!(
         r#"
         SELECT
             r.id, r.user_id, r.collection_id, r.title, r.url, r.description,
@@ -72,137 +96,352 @@ pub async fn fetch_resources(
                 (SELECT COUNT(*) FROM resource_references rr
                  WHERE rr.source_id = r.id OR rr.target_id = r.id),
                 0
-            ) as reference_count
+            ) as reference_count,
+            {} as sort_key
         FROM resources r
         LEFT JOIN collections c ON r.collection_id = c.id
         LEFT JOIN resource_tags rt ON r.id = rt.resource_id
         LEFT JOIN tags t ON rt.tag_id = t.id
         "#,
-    );
+        sort.expression
+    ))
+}
 
-    // If search term is present, join FTS table
-    if options.search_term.is_some() {
-        query_builder.push(" JOIN resources_fts fts ON r.id = fts.rowid ");
-    }
+pub async fn fetch_resources(
+    pool: &SqlitePool,
+    options: &QueryOptions<'_>,
+) -> AppResult<Vec<ResourceWithTags>> {
+    let sort = SortKey::from_options(options, None);
+    let mut query_builder = select_resources(&sort);
 
-    query_builder.push(" WHERE r.user_id = ");
-    query_builder.push_bind(options.user_id);
+    push_conditions(&mut query_builder, options);
 
-    // Dynamic filters
-    if let Some(collection_id) = options.collection_id {
-        query_builder.push(" AND r.collection_id = ");
-        query_builder.push_bind(collection_id);
-    }
+    // Grouping
+    query_builder.push(" GROUP BY r.id, c.name, c.color");
 
-    if let Some(resource_type) = options.resource_type {
-        query_builder.push(" AND r.type = ");
-        query_builder.push_bind(resource_type);
-    }
+    // Sorting, ID 作为相同取值时的次序
+    query_builder.push(sort.order_by(false));
 
-    if let Some(is_favorite) = options.is_favorite {
-        query_builder.push(" AND r.is_favorite = ");
-        query_builder.push_bind(is_favorite);
+    // Pagination
+    query_builder.push(" LIMIT ");
+    query_builder.push_bind(options.limit);
+    query_builder.push(" OFFSET ");
+    query_builder.push_bind(options.offset);
+
+    let resources = query_builder
+        .build_query_as::<ResourceWithTags>()
+        .fetch_all(pool)
+        .await?;
+    Ok(resources)
+}
+
+/// 按给定的 ID 顺序加载资源, 不存在或不属于该用户的 ID 被跳过
+pub async fn fetch_resources_in_order(
+    pool: &SqlitePool,
+    user_id: i64,
+    ids: &[i64],
+) -> AppResult<Vec<ResourceWithTags>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
     }
 
-    if let Some(is_archived) = options.is_archived {
-        query_builder.push(" AND r.is_archived = ");
-        query_builder.push_bind(is_archived);
+    let options = QueryOptions {
+        user_id,
+        ids: Some(ids),
+        limit: ids.len() as i64,
+        ..Default::default()
+    };
+    let mut resources: HashMap<i64, ResourceWithTags> = fetch_resources(pool, &options)
+        .await?
+        .into_iter()
+        .map(|resource| (resource.resource.id, resource))
+        .collect();
+
+    Ok(ids.iter().filter_map(|id| resources.remove(id)).collect())
+}
+
+/// 游标 (keyset) 分页: 以排序列取值和 ID 定位, 插入新资源不会导致重复或遗漏
+///
+/// cursor 为 None 时返回第一页; 游标的排序方式必须与当前查询一致。
+pub async fn fetch_resources_page(
+    pool: &SqlitePool,
+    options: &QueryOptions<'_>,
+    cursor: Option<&Cursor>,
+) -> AppResult<CursorPage<ResourceWithTags>> {
+    let sort = SortKey::from_options(options, cursor.and_then(|cursor| cursor.ranked_at));
+    if let Some(cursor) = cursor {
+        if cursor.sort != sort.name() {
+            return Err(AppError::BadRequest(
+                "Cursor does not match the current sort order".to_string(),
+            ));
+        }
     }
+    let backward = cursor.is_some_and(|cursor| cursor.backward);
 
-    if let Some(is_private) = options.is_private {
-        query_builder.push(" AND r.is_private = ");
-        query_builder.push_bind(is_private);
+    let mut query_builder = select_resources(&sort);
+    push_conditions(&mut query_builder, options);
+
+    if let Some(cursor) = cursor {
+        // 向后翻页时取排序在游标之后的行, 向前翻页时取之前的行
+        let comparison = if sort.ascending != backward { ">" } else { "<" };
+        query_builder.push(format!(" AND ({} {} ", sort.expression, comparison));
+        push_sort_value(&mut query_builder, &cursor.value);
+        query_builder.push(format!(" OR ({} = ", sort.expression));
+        push_sort_value(&mut query_builder, &cursor.value);
+        query_builder.push(format!(" AND r.id {} ", comparison));
+        query_builder.push_bind(cursor.id);
+        query_builder.push("))");
     }
 
-    if let Some(is_read) = options.is_read {
-        query_builder.push(" AND r.is_read = ");
-        query_builder.push_bind(is_read);
+    query_builder.push(" GROUP BY r.id, c.name, c.color");
+    query_builder.push(sort.order_by(backward));
+    // 多取一条检测是否还有更多数据
+    query_builder.push(" LIMIT ");
+    query_builder.push_bind(options.limit + 1);
+
+    let rows = query_builder.build().fetch_all(pool).await?;
+    let mut items = rows
+        .iter()
+        .map(|row| Ok((ResourceWithTags::from_row(row)?, sort.read_value(row)?)))
+        .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+    let has_more = items.len() > options.limit as usize;
+    if has_more {
+        items.pop();
+    }
+    if backward {
+        items.reverse();
     }
 
-    if let Some(date_from) = options.date_from {
-        query_builder.push(" AND r.created_at >= ");
-        query_builder.push_bind(date_from);
+    // 从后一页返回时后面一定还有数据
+    let (has_next, has_prev) = if backward {
+        (true, has_more)
+    } else {
+        (has_more, cursor.is_some())
+    };
+    let cursor_at = |(resource, value): &(ResourceWithTags, SortValue), backward: bool| {
+        Cursor {
+            sort: sort.name(),
+            value: value.clone(),
+            id: resource.resource.id,
+            backward,
+            ranked_at: sort.ranked_at,
+        }
+        .encode()
+    };
+
+    Ok(CursorPage {
+        next_cursor: items
+            .last()
+            .filter(|_| has_next)
+            .map(|item| cursor_at(item, false)),
+        prev_cursor: items
+            .first()
+            .filter(|_| has_prev)
+            .map(|item| cursor_at(item, true)),
+        items: items.into_iter().map(|(resource, _)| resource).collect(),
+        limit: options.limit,
+    })
+}
+
+#[derive(Clone, Copy)]
+enum SortValueKind {
+    Int,
+    Float,
+    Text,
+}
+
+/// 当前查询的排序列
+struct SortKey {
+    field: &'static str,
+    /// 排序使用的 SQL 表达式, 可空列以默认值代替 NULL 以便比较
+    expression: String,
+    kind: SortValueKind,
+    ascending: bool,
+    /// 按相关度排序时新近度加成的参考时间
+    ranked_at: Option<i64>,
+}
+
+impl SortKey {
+    /// ranked_at 为游标中保存的参考时间, 优先于查询选项
+    fn from_options(options: &QueryOptions<'_>, ranked_at: Option<i64>) -> Self {
+        // 相关度只在全文搜索时可用, 得分越大越相关
+        if options.search_term.is_some() && options.sort_by == "rank" {
+            let default_ranking = RankingConfig::default();
+            let ranking = options.ranking.unwrap_or(&default_ranking);
+            let ranked_at = ranked_at
+                .or(options.ranked_at)
+                .unwrap_or_else(|| Utc::now().timestamp());
+            return Self {
+                field: "rank",
+                expression: format!(
+                    "({}) * ({})",
+                    TEXT_SCORE_EXPRESSION,
+                    boost_expression(ranking, ranked_at)
+                ),
+                kind: SortValueKind::Float,
+                ascending: false,
+                ranked_at: Some(ranked_at),
+            };
+        }
+
+        let (field, expression, kind) = match options.sort_by {
+            "title" => ("title", "r.title", SortValueKind::Text),
+            "updated_at" => ("updated_at", "r.updated_at", SortValueKind::Int),
+            "visit_count" => (
+                "visit_count",
+                "COALESCE(r.visit_count, 0)",
+                SortValueKind::Int,
+            ),
+            "last_visited" => (
+                "last_visited",
+                "COALESCE(r.last_visited, 0)",
+                SortValueKind::Int,
+            ),
+            _ => ("created_at", "r.created_at", SortValueKind::Int),
+        };
+
+        Self {
+            field,
+            expression: expression.to_string(),
+            kind,
+            ascending: options.sort_order.eq_ignore_ascii_case("asc"),
+            ranked_at: None,
+        }
     }
 
-    if let Some(date_to) = options.date_to {
-        query_builder.push(" AND r.created_at <= ");
-        query_builder.push_bind(date_to);
+    /// 写入游标的排序方式, 例如 `created_at:desc`
+    fn name(&self) -> String {
+        format!(
+            "{}:{}",
+            self.field,
+            if self.ascending { "asc" } else { "desc" }
+        )
     }
 
-    // FTS Search
-    if let Some(search_term) = options.search_term {
-        // Use FTS Match
-        let fts_match = match options.search_type.unwrap_or(&SearchType::All) {
-            SearchType::Title => " AND resources_fts MATCH 'title:' || ",
-            SearchType::Content => " AND resources_fts MATCH 'content:' || ",
-            SearchType::Url => " AND resources_fts MATCH 'url:' || ",
-            SearchType::All => " AND resources_fts MATCH ",
+    /// ORDER BY 子句, reverse 为 true 时反向排序 (用于向前翻页)
+    fn order_by(&self, reverse: bool) -> String {
+        let direction = if self.ascending != reverse {
+            "ASC"
+        } else {
+            "DESC"
         };
-        query_builder.push(fts_match);
-        query_builder.push_bind(search_term);
+        format!(
+            " ORDER BY {} {}, r.id {}",
+            self.expression, direction, direction
+        )
     }
 
-    // Tags Filtering
-    if !options.tags.is_empty() {
-        query_builder.push(
-            " AND r.id IN (
-                SELECT resource_id
-                FROM resource_tags
-                JOIN tags ON resource_tags.tag_id = tags.id
-                WHERE tags.name IN (",
-        );
+    fn read_value(&self, row: &SqliteRow) -> Result<SortValue, sqlx::Error> {
+        Ok(match self.kind {
+            SortValueKind::Int => SortValue::Int(row.try_get("sort_key")?),
+            SortValueKind::Float => SortValue::Float(row.try_get("sort_key")?),
+            SortValueKind::Text => SortValue::Text(row.try_get("sort_key")?),
+        })
+    }
+}
 
-        let mut separated = query_builder.separated(", ");
-        for tag in options.tags {
-            separated.push_bind(tag);
-        }
+/// 访问次数达到该值时取得一半的访问加成
+const VISIT_BOOST_MIDPOINT: i64 = 10;
 
-        query_builder.push(") GROUP BY resource_id HAVING COUNT(DISTINCT tags.id) = ");
-        query_builder.push_bind(options.tags.len() as i64);
-        query_builder.push(")");
-    }
+/// 文本相关度: 按列加权的 bm25 得分取反, 越大越相关
+///
+/// 辅助函数不能在分组查询中直接调用, 因此通过 rank 列计算, 权重见 push_fts_rank
+pub const TEXT_SCORE_EXPRESSION: &str = "-fts.rank";
 
-    // Grouping
-    query_builder.push(" GROUP BY r.id, c.name, c.color");
+/// 设置 rank 列使用按列加权的 bm25(), 需要跟在 MATCH 条件之后
+pub fn push_fts_rank(query_builder: &mut QueryBuilder<'_, Sqlite>, ranking: &RankingConfig) {
+    let weights = [
+        ranking.title_weight,
+        ranking.description_weight,
+        ranking.content_weight,
+        ranking.tags_weight,
+        ranking.url_weight,
+        ranking.extracted_text_weight,
+    ];
+    query_builder.push(" AND fts.rank MATCH ");
+    query_builder.push_bind(format!("bm25({})", weights.map(sql_real).join(", ")));
+}
 
-    // Sorting
-    let sort_field = match options.sort_by {
-        "title" => "r.title",
-        "updated_at" => "r.updated_at",
-        "visit_count" => "r.visit_count",
-        "last_visited" => "r.last_visited",
-        _ => "r.created_at",
-    };
+/// 收藏、访问次数和新近度的加成系数, 不低于 1
+///
+/// 内置的 SQLite 没有启用数学函数, 访问次数和资源年龄使用 x / (x + k) 形式平滑:
+/// 访问 VISIT_BOOST_MIDPOINT 次取得一半的访问加成, 创建半衰期天数后新近度加成减半。
+pub fn boost_expression(ranking: &RankingConfig, ranked_at: i64) -> String {
+    format!(
+        "(1.0 + {} * r.is_favorite \
+         + {} * COALESCE(r.visit_count, 0) / (COALESCE(r.visit_count, 0) + {}.0) \
+         + {} / (1.0 + MAX({} - r.created_at, 0) / {}))",
+        sql_real(ranking.favorite_boost),
+        sql_real(ranking.visit_boost),
+        VISIT_BOOST_MIDPOINT,
+        sql_real(ranking.recency_boost),
+        ranked_at,
+        sql_real(ranking.recency_half_life_days.max(1.0) * 86_400.0),
+    )
+}
 
-    let sort_direction = match options.sort_order.to_lowercase().as_str() {
-        "asc" => "ASC",
-        _ => "DESC",
+/// 配置中的数值写入 SQL 时使用的字面量, 负数和非有限值按 0 处理
+fn sql_real(value: f64) -> String {
+    let value = if value.is_finite() {
+        value.max(0.0)
+    } else {
+        0.0
     };
+    format!("{:?}", value)
+}
 
-    // Support rank sort if searching
-    if options.search_term.is_some() && options.sort_by == "rank" {
-        query_builder.push(" ORDER BY rank");
-    } else {
-        query_builder.push(format!(" ORDER BY {} {}", sort_field, sort_direction));
-    }
+fn push_sort_value<'a>(query_builder: &mut QueryBuilder<'a, Sqlite>, value: &SortValue) {
+    match value {
+        SortValue::Int(value) => query_builder.push_bind(*value),
+        SortValue::Float(value) => query_builder.push_bind(*value),
+        SortValue::Text(value) => query_builder.push_bind(value.clone()),
+    };
+}
 
-    // Pagination
+/// 按排序返回匹配资源的 ID, 不加载资源内容 (混合搜索的全文候选)
+#[cfg(feature = "embeddings")]
+pub async fn fetch_resource_ids(
+    pool: &SqlitePool,
+    options: &QueryOptions<'_>,
+) -> AppResult<Vec<i64>> {
+    let sort = SortKey::from_options(options, None);
+    let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT r.id FROM resources r");
+    push_conditions(&mut query_builder, options);
+    query_builder.push(sort.order_by(false));
     query_builder.push(" LIMIT ");
     query_builder.push_bind(options.limit);
     query_builder.push(" OFFSET ");
     query_builder.push_bind(options.offset);
 
-    let resources = query_builder
-        .build_query_as::<ResourceWithTags>()
+    let ids = query_builder
+        .build_query_scalar::<i64>()
         .fetch_all(pool)
         .await?;
-    Ok(resources)
+    Ok(ids)
 }
 
 pub async fn count_resources(pool: &SqlitePool, options: &QueryOptions<'_>) -> AppResult<i64> {
     let mut query_builder: QueryBuilder<Sqlite> =
         QueryBuilder::new("SELECT COUNT(*) FROM resources r");
 
+    push_conditions(&mut query_builder, options);
+
+    let count = query_builder
+        .build_query_scalar::<i64>()
+        .fetch_one(pool)
+        .await?;
+    Ok(count)
+}
+
+/// 资源的筛选条件: 全文索引连接和 WHERE 子句, 列表、计数和分面统计共用
+///
+/// 调用方的 FROM 子句需要以别名 r 引用 resources 表
+pub fn push_conditions<'a>(
+    query_builder: &mut QueryBuilder<'a, Sqlite>,
+    options: &QueryOptions<'a>,
+) {
+    // If search term is present, join FTS table
     if options.search_term.is_some() {
         query_builder.push(" JOIN resources_fts fts ON r.id = fts.rowid ");
     }
@@ -215,46 +454,77 @@ pub async fn count_resources(pool: &SqlitePool, options: &QueryOptions<'_>) -> A
         query_builder.push(" AND r.collection_id = ");
         query_builder.push_bind(collection_id);
     }
-    if let Some(resource_type) = options.resource_type {
-        query_builder.push(" AND r.type = ");
-        query_builder.push_bind(resource_type);
+
+    if !options.resource_types.is_empty() {
+        query_builder.push(" AND r.type IN (");
+        let mut separated = query_builder.separated(", ");
+        for resource_type in options.resource_types {
+            separated.push_bind(resource_type);
+        }
+        query_builder.push(")");
     }
+
     if let Some(is_favorite) = options.is_favorite {
         query_builder.push(" AND r.is_favorite = ");
         query_builder.push_bind(is_favorite);
     }
+
     if let Some(is_archived) = options.is_archived {
         query_builder.push(" AND r.is_archived = ");
         query_builder.push_bind(is_archived);
     }
+
     if let Some(is_private) = options.is_private {
         query_builder.push(" AND r.is_private = ");
         query_builder.push_bind(is_private);
     }
+
     if let Some(is_read) = options.is_read {
         query_builder.push(" AND r.is_read = ");
         query_builder.push_bind(is_read);
     }
+
+    if let Some(link_status) = options.link_status {
+        query_builder.push(link_status_filter(link_status));
+    }
+
     if let Some(date_from) = options.date_from {
         query_builder.push(" AND r.created_at >= ");
         query_builder.push_bind(date_from);
     }
+
     if let Some(date_to) = options.date_to {
         query_builder.push(" AND r.created_at <= ");
         query_builder.push_bind(date_to);
     }
 
+    if let Some(domain) = options.domain {
+        push_domain_filter(query_builder, domain);
+    }
+
+    if let Some(ids) = options.ids {
+        if ids.is_empty() {
+            query_builder.push(" AND 0");
+        } else {
+            query_builder.push(" AND r.id IN (");
+            let mut separated = query_builder.separated(", ");
+            for id in ids {
+                separated.push_bind(*id);
+            }
+            query_builder.push(")");
+        }
+    }
+
+    // FTS Search
     if let Some(search_term) = options.search_term {
-        let fts_match = match options.search_type.unwrap_or(&SearchType::All) {
-            SearchType::Title => " AND resources_fts MATCH 'title:' || ",
-            SearchType::Content => " AND resources_fts MATCH 'content:' || ",
-            SearchType::Url => " AND resources_fts MATCH 'url:' || ",
-            SearchType::All => " AND resources_fts MATCH ",
-        };
-        query_builder.push(fts_match);
-        query_builder.push_bind(search_term);
+        // Use FTS Match
+        query_builder.push(" AND ");
+        push_fts_match(query_builder, options.search_type, search_term);
+        let default_ranking = RankingConfig::default();
+        push_fts_rank(query_builder, options.ranking.unwrap_or(&default_ranking));
     }
 
+    // Tags Filtering
     if !options.tags.is_empty() {
         query_builder.push(
             " AND r.id IN (
@@ -263,18 +533,221 @@ pub async fn count_resources(pool: &SqlitePool, options: &QueryOptions<'_>) -> A
                 JOIN tags ON resource_tags.tag_id = tags.id
                 WHERE tags.name IN (",
         );
+
         let mut separated = query_builder.separated(", ");
         for tag in options.tags {
             separated.push_bind(tag);
         }
+
         query_builder.push(") GROUP BY resource_id HAVING COUNT(DISTINCT tags.id) = ");
         query_builder.push_bind(options.tags.len() as i64);
         query_builder.push(")");
     }
 
-    let count = query_builder
-        .build_query_scalar::<i64>()
-        .fetch_one(pool)
-        .await?;
-    Ok(count)
+    if !options.exclude_tags.is_empty() {
+        query_builder.push(
+            " AND r.id NOT IN (
+                SELECT resource_id
+                FROM resource_tags
+                JOIN tags ON resource_tags.tag_id = tags.id
+                WHERE tags.name IN (",
+        );
+
+        let mut separated = query_builder.separated(", ");
+        for tag in options.exclude_tags {
+            separated.push_bind(tag);
+        }
+
+        query_builder.push("))");
+    }
+}
+
+/// FTS MATCH 条件, 按搜索类型限定列
+///
+/// 搜索词可以是组合表达式, 限定列时整体加括号, 使列过滤作用于整个表达式
+pub fn push_fts_match<'a>(
+    query_builder: &mut QueryBuilder<'a, Sqlite>,
+    search_type: Option<&SearchType>,
+    search_term: &'a str,
+) {
+    let column = match search_type.unwrap_or(&SearchType::All) {
+        SearchType::Title => "title",
+        SearchType::Content => "content",
+        SearchType::Url => "url",
+        SearchType::All => {
+            query_builder.push("resources_fts MATCH ");
+            query_builder.push_bind(search_term);
+            return;
+        }
+    };
+
+    query_builder.push(format!("resources_fts MATCH '{} : (' || ", column));
+    query_builder.push_bind(search_term);
+    query_builder.push(" || ')'");
+}
+
+/// 资源 URL 的主机名 (去掉协议、路径和端口, 转为小写), 没有协议的 URL 不参与
+pub const RESOURCE_HOSTS: &str = "
+    SELECT id,
+           CASE WHEN instr(authority, ':') > 0
+                THEN substr(authority, 1, instr(authority, ':') - 1)
+                ELSE authority END AS host
+    FROM (
+        SELECT id,
+               CASE WHEN instr(rest, '/') > 0
+                    THEN substr(rest, 1, instr(rest, '/') - 1)
+                    ELSE rest END AS authority
+        FROM (
+            SELECT id, lower(substr(url, instr(url, '://') + 3)) AS rest
+            FROM resources
+            WHERE instr(url, '://') > 0
+        )
+    )";
+
+/// 域名过滤条件: 主机名等于该域名或为其子域名
+fn push_domain_filter<'a>(query_builder: &mut QueryBuilder<'a, Sqlite>, domain: &'a str) {
+    query_builder.push(format!(
+        " AND r.id IN (SELECT id FROM ({}) WHERE host = ",
+        RESOURCE_HOSTS
+    ));
+    query_builder.push_bind(domain);
+    query_builder.push(" OR host LIKE '%.' || ");
+    query_builder.push_bind(domain);
+    query_builder.push(")");
+}
+
+/// 状态分面 (名称, 计数表达式)
+const FLAG_FACETS: [(&str, &str); 4] = [
+    ("favorite", "r.is_favorite"),
+    ("read", "r.is_read"),
+    ("unread", "NOT r.is_read"),
+    ("archived", "r.is_archived"),
+];
+
+/// 统计全部匹配结果的分面计数, 不受分页影响
+///
+/// 匹配集合与列表使用相同的筛选条件, 各分面在一次查询中以 UNION ALL 合并返回。
+/// 标签、收藏夹和域名按数量取前 facet_limit 项, 创建时间按月份分组。
+pub async fn fetch_facets(
+    pool: &SqlitePool,
+    options: &QueryOptions<'_>,
+    facet_limit: i64,
+) -> AppResult<SearchFacets> {
+    let mut query_builder: QueryBuilder<Sqlite> =
+        QueryBuilder::new("WITH matched AS (SELECT r.id FROM resources r");
+    push_conditions(&mut query_builder, options);
+    query_builder.push(
+        r#")
+        SELECT 'type' AS facet, r.type AS value, NULL AS label, COUNT(*) AS count
+        FROM matched m JOIN resources r ON r.id = m.id
+        GROUP BY r.type
+        UNION ALL SELECT * FROM (
+            SELECT 'tag', t.name, NULL, COUNT(*)
+            FROM matched m
+            JOIN resource_tags rt ON rt.resource_id = m.id
+            JOIN tags t ON t.id = rt.tag_id
+            GROUP BY t.id
+            ORDER BY COUNT(*) DESC, t.name
+            LIMIT "#,
+    );
+    query_builder.push_bind(facet_limit);
+    query_builder.push(
+        r#"
+        )
+        UNION ALL SELECT * FROM (
+            SELECT 'collection', CAST(c.id AS TEXT), c.name, COUNT(*)
+            FROM matched m
+            JOIN resources r ON r.id = m.id
+            JOIN collections c ON c.id = r.collection_id
+            GROUP BY c.id
+            ORDER BY COUNT(*) DESC, c.name
+            LIMIT "#,
+    );
+    query_builder.push_bind(facet_limit);
+    query_builder.push(format!(
+        r#"
+        )
+        UNION ALL SELECT * FROM (
+            SELECT 'domain', h.host, NULL, COUNT(*)
+            FROM matched m
+            JOIN ({}) h ON h.id = m.id
+            WHERE h.host <> ''
+            GROUP BY h.host
+            ORDER BY COUNT(*) DESC, h.host
+            LIMIT "#,
+        RESOURCE_HOSTS
+    ));
+    query_builder.push_bind(facet_limit);
+    query_builder.push(
+        r#"
+        )
+        UNION ALL
+        SELECT 'created_at', strftime('%Y-%m', r.created_at, 'unixepoch'), NULL, COUNT(*)
+        FROM matched m JOIN resources r ON r.id = m.id
+        GROUP BY 2"#,
+    );
+    for (name, expression) in FLAG_FACETS {
+        query_builder.push(format!(
+            " UNION ALL SELECT 'flag', '{}', NULL, COALESCE(SUM({}), 0)
+              FROM matched m JOIN resources r ON r.id = m.id",
+            name, expression
+        ));
+    }
+
+    let rows = query_builder.build().fetch_all(pool).await?;
+
+    let mut facets = SearchFacets::default();
+    for row in rows {
+        let facet: String = row.get("facet");
+        let value: Option<String> = row.get("value");
+        let count: i64 = row.get("count");
+        let Some(value) = value else { continue };
+
+        match facet.as_str() {
+            "type" => facets.types.push(FacetCount { value, count }),
+            "tag" => facets.tags.push(FacetCount { value, count }),
+            "collection" => facets.collections.push(CollectionFacet {
+                id: value.parse().unwrap_or_default(),
+                name: row.get::<Option<String>, _>("label").unwrap_or_default(),
+                count,
+            }),
+            "domain" => facets.domains.push(FacetCount { value, count }),
+            "created_at" => facets.created_at.push(FacetCount { value, count }),
+            "flag" => match value.as_str() {
+                "favorite" => facets.flags.favorite = count,
+                "read" => facets.flags.read = count,
+                "unread" => facets.flags.unread = count,
+                _ => facets.flags.archived = count,
+            },
+            _ => {}
+        }
+    }
+
+    // 类型按数量排序, 月份按时间倒序
+    facets
+        .types
+        .sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facets.created_at.sort_by(|a, b| b.value.cmp(&a.value));
+
+    Ok(facets)
+}
+
+/// 链接健康状态过滤条件, 状态定义见 link_checks 表
+fn link_status_filter(link_status: LinkStatus) -> &'static str {
+    match link_status {
+        LinkStatus::Ok => {
+            " AND r.id IN (SELECT resource_id FROM link_checks
+                           WHERE consecutive_failures = 0 AND final_url IS NULL)"
+        }
+        LinkStatus::Redirected => {
+            " AND r.id IN (SELECT resource_id FROM link_checks
+                           WHERE consecutive_failures = 0 AND final_url IS NOT NULL)"
+        }
+        LinkStatus::Broken => {
+            " AND r.id IN (SELECT resource_id FROM link_checks WHERE consecutive_failures > 0)"
+        }
+        LinkStatus::Unchecked => {
+            " AND r.type = 'link' AND r.id NOT IN (SELECT resource_id FROM link_checks)"
+        }
+    }
 }
@@ -0,0 +1,304 @@
+use sqlx::SqlitePool;
+
+use crate::config::RankingConfig;
+use crate::models::{
+    CreateResource, Cursor, FilterCriteria, PaginationParams, ResourceQuery,
+    ResourceReferenceQuery, SearchFilters, SearchMode, SearchType, SortValue,
+};
+use crate::services::indexer_service::IndexerService;
+use crate::services::resource_service::ResourceService;
+use crate::services::search_service::SearchService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+async fn create_resource(pool: &SqlitePool, user_id: i64, title: &str) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "note".to_string(),
+        content: Some(format!("{} paging notes", title)),
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+fn page_query(limit: i64, cursor: Option<String>) -> ResourceQuery {
+    ResourceQuery {
+        limit: Some(limit),
+        cursor: Some(cursor.unwrap_or_default()),
+        ..Default::default()
+    }
+}
+
+fn search_filters(query: &str, limit: i64, cursor: Option<String>) -> SearchFilters {
+    SearchFilters {
+        query: query.to_string(),
+        search_type: SearchType::All,
+        mode: SearchMode::Keyword,
+        filters: FilterCriteria::default(),
+        pagination: PaginationParams::from_page(1, limit),
+        highlight: None,
+        facet_limit: None,
+        cursor: Some(cursor.unwrap_or_default()),
+        ranking: RankingConfig::default(),
+        include_scores: false,
+    }
+}
+
+#[test]
+fn test_cursor_round_trip() {
+    let cursor = Cursor {
+        sort: "title:asc".to_string(),
+        value: SortValue::Text("Rust".to_string()),
+        id: 42,
+        backward: true,
+        ranked_at: Some(1_700_000_000),
+    };
+
+    let encoded = cursor.encode();
+    assert!(encoded
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(Cursor::decode(&encoded).unwrap(), cursor);
+
+    assert_eq!(Cursor::from_param("").unwrap(), None);
+    assert!(Cursor::decode("not a cursor").is_err());
+}
+
+#[tokio::test]
+async fn test_cursor_pages_are_stable_across_inserts() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "pager").await;
+
+    // 同一秒内创建, created_at 相同, 依靠 ID 决定次序
+    let mut ids = Vec::new();
+    for title in ["one", "two", "three", "four", "five"] {
+        ids.push(create_resource(&pool, user_id, title).await);
+    }
+    ids.reverse();
+
+    let first = ResourceService::get_resources_page(user_id, page_query(2, None), &pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        first
+            .items
+            .iter()
+            .map(|r| r.resource.id)
+            .collect::<Vec<_>>(),
+        ids[..2]
+    );
+    assert!(first.prev_cursor.is_none());
+
+    // 翻页期间插入的新资源排在最前, 不影响后续页
+    create_resource(&pool, user_id, "six").await;
+
+    let mut seen: Vec<i64> = first.items.iter().map(|r| r.resource.id).collect();
+    let mut cursor = first.next_cursor;
+    while let Some(next) = cursor {
+        let page = ResourceService::get_resources_page(user_id, page_query(2, Some(next)), &pool)
+            .await
+            .unwrap();
+        assert!(page.prev_cursor.is_some());
+        seen.extend(page.items.iter().map(|r| r.resource.id));
+        assert!(seen.len() <= ids.len());
+        cursor = page.next_cursor;
+    }
+    assert_eq!(seen, ids);
+}
+
+#[tokio::test]
+async fn test_prev_cursor_returns_previous_page() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "pager").await;
+
+    for title in ["delta", "alpha", "echo", "charlie", "bravo"] {
+        create_resource(&pool, user_id, title).await;
+    }
+
+    let query = |cursor: Option<String>| ResourceQuery {
+        sort_by: Some("title".to_string()),
+        sort_order: Some("asc".to_string()),
+        ..page_query(2, cursor)
+    };
+    let titles = |items: &[crate::models::ResourceWithTags]| {
+        items
+            .iter()
+            .map(|r| r.resource.title.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let first = ResourceService::get_resources_page(user_id, query(None), &pool)
+        .await
+        .unwrap();
+    assert_eq!(titles(&first.items), ["alpha", "bravo"]);
+
+    let second = ResourceService::get_resources_page(user_id, query(first.next_cursor), &pool)
+        .await
+        .unwrap();
+    assert_eq!(titles(&second.items), ["charlie", "delta"]);
+
+    let back = ResourceService::get_resources_page(user_id, query(second.prev_cursor), &pool)
+        .await
+        .unwrap();
+    assert_eq!(titles(&back.items), ["alpha", "bravo"]);
+    assert!(back.prev_cursor.is_none());
+    assert!(back.next_cursor.is_some());
+
+    let last = ResourceService::get_resources_page(user_id, query(second.next_cursor), &pool)
+        .await
+        .unwrap();
+    assert_eq!(titles(&last.items), ["echo"]);
+    assert!(last.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn test_cursor_rejects_different_sort() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "pager").await;
+
+    for title in ["one", "two", "three"] {
+        create_resource(&pool, user_id, title).await;
+    }
+
+    let first = ResourceService::get_resources_page(user_id, page_query(1, None), &pool)
+        .await
+        .unwrap();
+
+    let query = ResourceQuery {
+        sort_by: Some("title".to_string()),
+        ..page_query(1, first.next_cursor)
+    };
+    let result = ResourceService::get_resources_page(user_id, query, &pool).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+    let result = ResourceService::get_resources_page(
+        user_id,
+        page_query(1, Some("garbage".to_string())),
+        &pool,
+    )
+    .await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn test_search_cursor_pages_by_rank() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "pager").await;
+
+    for title in ["one", "two", "three", "four", "five"] {
+        create_resource(&pool, user_id, title).await;
+    }
+
+    let first = SearchService::search_resources(user_id, search_filters("paging", 2, None), &pool)
+        .await
+        .unwrap();
+    assert_eq!(first.pagination.page, None);
+    assert_eq!(first.pagination.total, 5);
+    assert!(first.pagination.has_next);
+    assert!(!first.pagination.has_prev);
+
+    let mut seen: Vec<i64> = first.items.iter().map(|r| r.resource.id).collect();
+    let mut cursor = first.pagination.next_cursor;
+    while let Some(next) = cursor {
+        let page = SearchService::search_resources(
+            user_id,
+            search_filters("paging", 2, Some(next)),
+            &pool,
+        )
+        .await
+        .unwrap();
+        assert!(page.pagination.has_prev);
+        seen.extend(page.items.iter().map(|r| r.resource.id));
+        assert!(seen.len() <= 5);
+        cursor = page.pagination.next_cursor;
+    }
+
+    seen.sort();
+    seen.dedup();
+    assert_eq!(seen.len(), 5);
+}
+
+#[tokio::test]
+async fn test_reference_cursor_pages() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "pager").await;
+
+    let source_id = create_resource(&pool, user_id, "source").await;
+    let mut target_ids = Vec::new();
+    for title in ["a", "b", "c"] {
+        let target_id = create_resource(&pool, user_id, title).await;
+        ResourceService::create_resource_reference(source_id, target_id, None, user_id, &pool)
+            .await
+            .unwrap();
+        target_ids.push(target_id);
+    }
+
+    let query = |cursor: Option<String>| ResourceReferenceQuery {
+        limit: Some(2),
+        offset: None,
+        reference_type: None,
+        direction: Some("source".to_string()),
+        cursor: Some(cursor.unwrap_or_default()),
+    };
+
+    let first = ResourceService::get_resource_references(source_id, query(None), user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        first
+            .items
+            .iter()
+            .map(|r| r.resource.id)
+            .collect::<Vec<_>>(),
+        target_ids[..2]
+    );
+    assert!(first.has_more);
+    assert!(first.prev_cursor.is_none());
+
+    let second = ResourceService::get_resource_references(
+        source_id,
+        query(first.next_cursor),
+        user_id,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        second
+            .items
+            .iter()
+            .map(|r| r.resource.id)
+            .collect::<Vec<_>>(),
+        target_ids[2..]
+    );
+    assert!(!second.has_more);
+    assert!(second.next_cursor.is_none());
+
+    let back = ResourceService::get_resource_references(
+        source_id,
+        query(second.prev_cursor),
+        user_id,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(back.items.len(), 2);
+    assert!(back.prev_cursor.is_none());
+}
@@ -0,0 +1,503 @@
+//! 相关资源推荐
+//!
+//! 根据相同标签、相同域名、同一收藏夹、全文索引中的共同词语和引用关系图中的距离,
+//! 为一个资源推荐同一用户的其他资源。每种依据的强度在 0 ~ 1 之间, 乘以权重后相加,
+//! 权重之和为 1, 因此总分也在 0 ~ 1 之间。已经直接引用的资源不再推荐。
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+
+use crate::models::{
+    AcceptedRelatedResource, RelatedQuery, RelatedReason, RelatedResource, RelatedResourceList,
+    RelatedSignal, ResourceWithTags,
+};
+use crate::services::query_helper::{self, RESOURCE_HOSTS};
+use crate::services::resource_service::ResourceService;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::segmenter::prepare_for_search;
+
+const DEFAULT_RELATED_LIMIT: i64 = 10;
+const MAX_RELATED_LIMIT: i64 = 50;
+
+const SHARED_TAGS_WEIGHT: f64 = 0.3;
+const TERM_OVERLAP_WEIGHT: f64 = 0.3;
+const REFERENCE_GRAPH_WEIGHT: f64 = 0.2;
+const SAME_DOMAIN_WEIGHT: f64 = 0.1;
+const SAME_COLLECTION_WEIGHT: f64 = 0.1;
+
+/// 参与词语重叠计算的 ASCII 词的最短长度, 过滤 "a"、"of" 之类的虚词
+const MIN_TERM_LENGTH: usize = 3;
+/// 来源资源最多取多少个词参与重叠计算
+const MAX_SOURCE_TERMS: usize = 32;
+/// 标题和标签中的词在选词时相当于正文中出现的次数
+const TITLE_TERM_WEIGHT: usize = 3;
+/// 说明中最多列出的共同词语和中间资源
+const MAX_DETAIL_ITEMS: usize = 5;
+
+pub struct RelatedService;
+
+impl RelatedService {
+    /// 推荐与资源相关的其他资源, 按得分倒序
+    pub async fn get_related_resources(
+        user_id: i64,
+        resource_id: i64,
+        query: RelatedQuery,
+        db_pool: &SqlitePool,
+    ) -> AppResult<RelatedResourceList> {
+        let source = ResourceService::get_resource_by_id(user_id, resource_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Resource not found".to_string()))?;
+
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_RELATED_LIMIT)
+            .clamp(1, MAX_RELATED_LIMIT) as usize;
+        let min_score = query.min_score.unwrap_or(0.0);
+
+        let mut ranked: Vec<(i64, f64, Vec<RelatedReason>)> =
+            Self::score_candidates(user_id, &source, db_pool)
+                .await?
+                .into_iter()
+                .map(|(id, reasons)| (id, total_score(&reasons), reasons))
+                .filter(|(_, score, _)| *score >= min_score)
+                .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+        ranked.truncate(limit);
+
+        let ids: Vec<i64> = ranked.iter().map(|(id, _, _)| *id).collect();
+        let mut resources: HashMap<i64, ResourceWithTags> =
+            query_helper::fetch_resources_in_order(db_pool, user_id, &ids)
+                .await?
+                .into_iter()
+                .map(|resource| (resource.resource.id, resource))
+                .collect();
+
+        let items = ranked
+            .into_iter()
+            .filter_map(|(id, score, reasons)| {
+                resources.remove(&id).map(|resource| RelatedResource {
+                    resource,
+                    score,
+                    reasons,
+                })
+            })
+            .collect();
+
+        Ok(RelatedResourceList { items })
+    }
+
+    /// 把推荐的资源转为真正的引用关系, 目标必须是当前仍然成立的推荐
+    pub async fn accept_related_resource(
+        user_id: i64,
+        resource_id: i64,
+        target_id: i64,
+        reference_type: Option<String>,
+        db_pool: &SqlitePool,
+    ) -> AppResult<AcceptedRelatedResource> {
+        if resource_id == target_id {
+            return Err(AppError::BadRequest(
+                "A resource cannot reference itself".to_string(),
+            ));
+        }
+
+        let source = ResourceService::get_resource_by_id(user_id, resource_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Source resource not found".to_string()))?;
+        ResourceService::get_resource_by_id(user_id, target_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Target resource not found".to_string()))?;
+
+        if Self::referenced_ids(resource_id, db_pool)
+            .await?
+            .contains(&target_id)
+        {
+            return Err(AppError::Conflict(
+                "Resources are already referenced".to_string(),
+            ));
+        }
+
+        let reasons = Self::score_candidates(user_id, &source, db_pool)
+            .await?
+            .remove(&target_id)
+            .ok_or_else(|| {
+                AppError::NotFound("Related resource suggestion not found".to_string())
+            })?;
+
+        let reference_type = reference_type.unwrap_or_else(|| "related".to_string());
+        let id = ResourceService::create_resource_reference(
+            resource_id,
+            target_id,
+            Some(reference_type.clone()),
+            user_id,
+            db_pool,
+        )
+        .await?;
+
+        Ok(AcceptedRelatedResource {
+            id,
+            source_id: resource_id,
+            target_id,
+            reference_type,
+            score: total_score(&reasons),
+            reasons,
+        })
+    }
+
+    /// 汇总各项依据, 返回候选资源及其推荐依据; 不包含来源资源和已直接引用的资源
+    async fn score_candidates(
+        user_id: i64,
+        source: &ResourceWithTags,
+        db_pool: &SqlitePool,
+    ) -> AppResult<HashMap<i64, Vec<RelatedReason>>> {
+        let source_id = source.resource.id;
+        let referenced = Self::referenced_ids(source_id, db_pool).await?;
+
+        let mut reasons = Self::shared_tags(user_id, source_id, source.tags.len(), db_pool).await?;
+        reasons.extend(Self::same_domain(user_id, source_id, db_pool).await?);
+        if let Some(collection_id) = source.resource.collection_id {
+            let detail = source
+                .collection_name
+                .clone()
+                .unwrap_or_else(|| collection_id.to_string());
+            reasons.extend(
+                Self::same_collection(user_id, source_id, collection_id, &detail, db_pool).await?,
+            );
+        }
+        reasons.extend(Self::term_overlap(user_id, source, db_pool).await?);
+        reasons.extend(Self::reference_graph(user_id, source_id, &referenced, db_pool).await?);
+
+        let mut candidates: HashMap<i64, Vec<RelatedReason>> = HashMap::new();
+        for (id, reason) in reasons {
+            if id != source_id && !referenced.contains(&id) {
+                candidates.entry(id).or_default().push(reason);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// 与资源直接引用 (任一方向) 的资源
+    async fn referenced_ids(resource_id: i64, db_pool: &SqlitePool) -> AppResult<HashSet<i64>> {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT target_id FROM resource_references WHERE source_id = $1
+            UNION
+            SELECT source_id FROM resource_references WHERE target_id = $1
+            "#,
+        )
+        .bind(resource_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// 相同标签, 强度为两个资源标签集合的 Jaccard 系数
+    async fn shared_tags(
+        user_id: i64,
+        source_id: i64,
+        source_tag_count: usize,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, RelatedReason)>> {
+        if source_tag_count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT rt.resource_id,
+                   GROUP_CONCAT(t.name, ', ') as shared_tags,
+                   COUNT(*) as shared_count,
+                   (SELECT COUNT(*) FROM resource_tags WHERE resource_id = rt.resource_id) as tag_count
+            FROM resource_tags rt
+            JOIN tags t ON t.id = rt.tag_id
+            JOIN resources r ON r.id = rt.resource_id
+            WHERE r.user_id = $1
+              AND rt.resource_id != $2
+              AND rt.tag_id IN (SELECT tag_id FROM resource_tags WHERE resource_id = $2)
+            GROUP BY rt.resource_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(source_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let shared: i64 = row.get("shared_count");
+                let tag_count: i64 = row.get("tag_count");
+                let union = source_tag_count as i64 + tag_count - shared;
+                let strength = shared as f64 / union.max(1) as f64;
+                (
+                    row.get("resource_id"),
+                    RelatedReason {
+                        signal: RelatedSignal::SharedTags,
+                        score: SHARED_TAGS_WEIGHT * strength,
+                        detail: row.get("shared_tags"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// URL 主机名相同的资源
+    async fn same_domain(
+        user_id: i64,
+        source_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, RelatedReason)>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH hosts AS ({})
+            SELECT h.id, h.host
+            FROM hosts h
+            JOIN resources r ON r.id = h.id
+            WHERE r.user_id = $1
+              AND h.id != $2
+              AND h.host != ''
+              AND h.host = (SELECT host FROM hosts WHERE id = $2)
+            "#,
+            RESOURCE_HOSTS
+        ))
+        .bind(user_id)
+        .bind(source_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("id"),
+                    RelatedReason {
+                        signal: RelatedSignal::SameDomain,
+                        score: SAME_DOMAIN_WEIGHT,
+                        detail: row.get("host"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 同一收藏夹中的资源
+    async fn same_collection(
+        user_id: i64,
+        source_id: i64,
+        collection_id: i64,
+        collection_name: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, RelatedReason)>> {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM resources WHERE user_id = $1 AND collection_id = $2 AND id != $3",
+        )
+        .bind(user_id)
+        .bind(collection_id)
+        .bind(source_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                (
+                    id,
+                    RelatedReason {
+                        signal: RelatedSignal::SameCollection,
+                        score: SAME_COLLECTION_WEIGHT,
+                        detail: collection_name.to_string(),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 全文索引中的共同词语
+    ///
+    /// 从来源资源的标题、标签、描述和内容中选出出现最多的词, 在 fts5vocab 中查找包含这些词的资源。
+    /// 每个词按用户资源中的逆文档频率加权, 强度为共同词语的权重占来源资源全部词语权重的比例,
+    /// 大多数资源都包含的词贡献很小。
+    async fn term_overlap(
+        user_id: i64,
+        source: &ResourceWithTags,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, RelatedReason)>> {
+        let terms = source_terms(source);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let resource_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM resources WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(db_pool)
+                .await?;
+
+        // CROSS JOIN 固定以词表为外层循环, 使 term 的条件由 fts5vocab 处理
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            r#"
+            SELECT v.term, v.doc
+            FROM resources_fts_vocab_instance v
+            CROSS JOIN resources r ON r.id = v.doc
+            WHERE v.term IN ("#,
+        );
+        let mut separated = query_builder.separated(", ");
+        for term in &terms {
+            separated.push_bind(term.as_str());
+        }
+        query_builder.push(") AND r.user_id = ");
+        query_builder.push_bind(user_id);
+        query_builder.push(" GROUP BY v.term, v.doc");
+
+        let rows = query_builder.build().fetch_all(db_pool).await?;
+
+        let mut documents: HashMap<String, Vec<i64>> = HashMap::new();
+        for row in rows {
+            documents
+                .entry(row.get("term"))
+                .or_default()
+                .push(row.get("doc"));
+        }
+
+        // 逆文档频率, 只统计出现在索引中的词
+        let weights: HashMap<&str, f64> = documents
+            .iter()
+            .map(|(term, docs)| {
+                let weight = (1.0 + resource_count as f64 / docs.len() as f64).ln();
+                (term.as_str(), weight)
+            })
+            .collect();
+        let total_weight: f64 = weights.values().sum();
+        if total_weight <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let mut shared: HashMap<i64, Vec<&str>> = HashMap::new();
+        for (term, docs) in &documents {
+            for doc in docs {
+                if *doc != source.resource.id {
+                    shared.entry(*doc).or_default().push(term.as_str());
+                }
+            }
+        }
+
+        Ok(shared
+            .into_iter()
+            .map(|(id, mut terms)| {
+                terms.sort_by(|a, b| weights[b].total_cmp(&weights[a]).then_with(|| a.cmp(b)));
+                let weight: f64 = terms.iter().map(|term| weights[term]).sum();
+                terms.truncate(MAX_DETAIL_ITEMS);
+                (
+                    id,
+                    RelatedReason {
+                        signal: RelatedSignal::TermOverlap,
+                        score: TERM_OVERLAP_WEIGHT * weight / total_weight,
+                        detail: terms.join(", "),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// 引用关系图中相距两步的资源, 强度随中间资源的数量增加: 1 - 0.5^n
+    async fn reference_graph(
+        user_id: i64,
+        source_id: i64,
+        referenced: &HashSet<i64>,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, RelatedReason)>> {
+        if referenced.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 边不区分方向, 同一对资源之间的多种引用只计一次
+        let rows = sqlx::query(
+            r#"
+            WITH neighbors(id) AS (
+                SELECT target_id FROM resource_references WHERE source_id = $1
+                UNION
+                SELECT source_id FROM resource_references WHERE target_id = $1
+            ),
+            edges(via, other) AS (
+                SELECT source_id, target_id FROM resource_references
+                WHERE source_id IN (SELECT id FROM neighbors)
+                UNION
+                SELECT target_id, source_id FROM resource_references
+                WHERE target_id IN (SELECT id FROM neighbors)
+            )
+            SELECT e.other as id, v.title
+            FROM edges e
+            JOIN resources v ON v.id = e.via
+            JOIN resources r ON r.id = e.other
+            WHERE r.user_id = $2 AND e.other != $1
+            ORDER BY e.other, v.title
+            "#,
+        )
+        .bind(source_id)
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut paths: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            paths
+                .entry(row.get("id"))
+                .or_default()
+                .push(row.get("title"));
+        }
+
+        Ok(paths
+            .into_iter()
+            .filter(|(id, _)| !referenced.contains(id))
+            .map(|(id, mut via)| {
+                let strength = 1.0 - 0.5f64.powi(via.len() as i32);
+                via.truncate(MAX_DETAIL_ITEMS);
+                (
+                    id,
+                    RelatedReason {
+                        signal: RelatedSignal::ReferenceGraph,
+                        score: REFERENCE_GRAPH_WEIGHT * strength,
+                        detail: via.join(", "),
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+fn total_score(reasons: &[RelatedReason]) -> f64 {
+    reasons.iter().map(|reason| reason.score).sum()
+}
+
+/// 来源资源中出现次数最多的词 (小写), 标题和标签中的词按 TITLE_TERM_WEIGHT 次计
+fn source_terms(source: &ResourceWithTags) -> Vec<String> {
+    let resource = &source.resource;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut count_terms = |text: Option<&str>, weight: usize| {
+        for term in prepare_for_search(text)
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|term| is_significant_term(term))
+        {
+            *counts.entry(term.to_lowercase()).or_default() += weight;
+        }
+    };
+
+    count_terms(Some(&resource.title), TITLE_TERM_WEIGHT);
+    for tag in &source.tags {
+        count_terms(Some(tag), TITLE_TERM_WEIGHT);
+    }
+    count_terms(resource.description.as_deref(), 1);
+    count_terms(resource.content.as_deref(), 1);
+
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(MAX_SOURCE_TERMS);
+    terms.into_iter().map(|(term, _)| term).collect()
+}
+
+/// 过滤纯数字和过短的 ASCII 词; 非 ASCII 的词 (例如中文) 不受长度限制
+fn is_significant_term(term: &str) -> bool {
+    !term.is_empty()
+        && !term.chars().all(|c| c.is_ascii_digit())
+        && (!term.is_ascii() || term.len() >= MIN_TERM_LENGTH)
+}
@@ -0,0 +1,224 @@
+use sqlx::SqlitePool;
+
+use crate::models::{CreateCollection, CreateResource, RelatedQuery, RelatedSignal};
+use crate::services::collection_service::CollectionService;
+use crate::services::indexer_service::IndexerService;
+use crate::services::related_service::RelatedService;
+use crate::services::resource_service::ResourceService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+fn note(title: &str, content: &str) -> CreateResource {
+    CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "note".to_string(),
+        content: Some(content.to_string()),
+        source: None,
+        mime_type: None,
+    }
+}
+
+fn link(title: &str, url: &str) -> CreateResource {
+    CreateResource {
+        url: Some(url.to_string()),
+        resource_type: "link".to_string(),
+        content: None,
+        ..note(title, "")
+    }
+}
+
+async fn create(pool: &SqlitePool, user_id: i64, resource_data: CreateResource) -> i64 {
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+async fn related(
+    pool: &SqlitePool,
+    user_id: i64,
+    resource_id: i64,
+) -> Vec<(i64, f64, Vec<RelatedSignal>)> {
+    RelatedService::get_related_resources(user_id, resource_id, RelatedQuery::default(), pool)
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|item| {
+            let signals = item.reasons.iter().map(|reason| reason.signal).collect();
+            (item.resource.resource.id, item.score, signals)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_related_resources_combine_signals() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "related").await;
+    let collection_id = CollectionService::create_collection(
+        user_id,
+        CreateCollection {
+            name: "Runtimes".to_string(),
+            description: None,
+            color: None,
+            icon: None,
+            parent_id: None,
+        },
+        &pool,
+    )
+    .await
+    .unwrap()
+    .id;
+
+    let source_id = create(
+        &pool,
+        user_id,
+        CreateResource {
+            description: Some("Scheduler internals".to_string()),
+            collection_id: Some(collection_id),
+            tags: Some(vec!["rust".to_string(), "async".to_string()]),
+            ..link("Tokio guide", "https://tokio.rs/tokio/tutorial")
+        },
+    )
+    .await;
+    let tagged_id = create(
+        &pool,
+        user_id,
+        CreateResource {
+            tags: Some(vec!["rust".to_string(), "async".to_string()]),
+            ..link("Crate docs", "https://docs.rs/futures")
+        },
+    )
+    .await;
+    let domain_id = create(
+        &pool,
+        user_id,
+        link("Release notes", "https://tokio.rs/blog"),
+    )
+    .await;
+    let collection_member_id = create(
+        &pool,
+        user_id,
+        CreateResource {
+            collection_id: Some(collection_id),
+            ..note("Reading list", "papers to read")
+        },
+    )
+    .await;
+    let term_id = create(&pool, user_id, note("Work stealing", "a scheduler design")).await;
+    let unrelated_id = create(&pool, user_id, note("Sourdough", "bread baking at home")).await;
+
+    let items = related(&pool, user_id, source_id).await;
+    let ids: Vec<i64> = items.iter().map(|(id, _, _)| *id).collect();
+    assert!(!ids.contains(&unrelated_id));
+    assert!(items.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(items
+        .iter()
+        .all(|(_, score, _)| *score > 0.0 && *score <= 1.0));
+
+    let signals = |id: i64| {
+        items
+            .iter()
+            .find(|(item_id, _, _)| *item_id == id)
+            .map(|(_, _, signals)| signals.clone())
+            .unwrap()
+    };
+    assert!(signals(tagged_id).contains(&RelatedSignal::SharedTags));
+    assert!(signals(domain_id).contains(&RelatedSignal::SameDomain));
+    assert!(signals(collection_member_id).contains(&RelatedSignal::SameCollection));
+    assert!(signals(term_id).contains(&RelatedSignal::TermOverlap));
+    // 全部标签相同的资源排在最前
+    assert_eq!(ids[0], tagged_id);
+
+    let response = RelatedService::get_related_resources(
+        user_id,
+        source_id,
+        RelatedQuery {
+            limit: Some(1),
+            min_score: None,
+        },
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.items.len(), 1);
+    let reason = &response.items[0].reasons[0];
+    assert_eq!(reason.signal, RelatedSignal::SharedTags);
+    assert!(reason.detail.contains("rust") && reason.detail.contains("async"));
+
+    let response = RelatedService::get_related_resources(
+        user_id,
+        source_id,
+        RelatedQuery {
+            limit: None,
+            min_score: Some(0.2),
+        },
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert!(response.items.iter().all(|item| item.score >= 0.2));
+    assert!(!response
+        .items
+        .iter()
+        .any(|item| item.resource.resource.id == collection_member_id));
+}
+
+#[tokio::test]
+async fn test_accept_related_resource_from_reference_graph() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "related").await;
+
+    let source_id = create(&pool, user_id, note("Alpha", "first")).await;
+    let middle_id = create(&pool, user_id, note("Beta", "second")).await;
+    let target_id = create(&pool, user_id, note("Gamma", "third")).await;
+    let unrelated_id = create(&pool, user_id, note("Delta", "fourth")).await;
+    ResourceService::create_resource_reference(source_id, middle_id, None, user_id, &pool)
+        .await
+        .unwrap();
+    ResourceService::create_resource_reference(target_id, middle_id, None, user_id, &pool)
+        .await
+        .unwrap();
+
+    // 已直接引用的资源不再推荐, 通过中间资源相连的资源被推荐
+    let items = related(&pool, user_id, source_id).await;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].0, target_id);
+    assert_eq!(items[0].2, vec![RelatedSignal::ReferenceGraph]);
+
+    let accepted = RelatedService::accept_related_resource(
+        user_id,
+        source_id,
+        target_id,
+        Some("references".to_string()),
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(accepted.reference_type, "references");
+    assert_eq!(accepted.reasons[0].detail, "Beta");
+    assert!(related(&pool, user_id, source_id).await.is_empty());
+
+    let result =
+        RelatedService::accept_related_resource(user_id, source_id, target_id, None, &pool).await;
+    assert!(matches!(result, Err(AppError::Conflict(_))));
+    let result =
+        RelatedService::accept_related_resource(user_id, source_id, unrelated_id, None, &pool)
+            .await;
+    assert!(matches!(result, Err(AppError::NotFound(_))));
+    let result =
+        RelatedService::accept_related_resource(user_id, source_id, source_id, None, &pool).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
@@ -1,17 +1,21 @@
 use sqlx::{Row, SqlitePool};
 
 use crate::models::{
-    CreateResource, Resource, ResourceBatchAction, ResourceBatchError, ResourceBatchRequest,
-    ResourceBatchResult, ResourceQuery, ResourceReferenceList, ResourceReferenceQuery,
-    ResourceType, ResourceWithTags, UpdateResource,
+    CreateResource, Cursor, CursorPage, JobKind, LinkStatus, Resource, ResourceBatchAction,
+    ResourceBatchError, ResourceBatchRequest, ResourceBatchResult, ResourceQuery,
+    ResourceReferenceList, ResourceReferenceQuery, ResourceType, ResourceWithTags, SortValue,
+    UpdateResource,
 };
 use crate::services::{
     query_helper::{self, QueryOptions},
-    IndexerService,
+    ArchiveService, FileService, IndexerService, JobService, LinkCheckerService,
+    SavedSearchService,
 };
 use crate::utils::error::{AppError, AppResult};
 use crate::utils::validation::validate_url;
 
+const REFERENCE_CURSOR_SORT: &str = "id:asc";
+
 // 输入验证常量
 const MAX_TITLE_LENGTH: usize = 500;
 const MAX_DESCRIPTION_LENGTH: usize = 2000;
@@ -33,6 +37,43 @@ impl ResourceService {
         resource_data: CreateResource,
         db_pool: &SqlitePool,
     ) -> AppResult<Resource> {
+        Self::validate_create_resource(&resource_data)?;
+
+        // 开始事务 - 同时更新 resources 和 resources_fts
+        let mut tx = db_pool.begin().await?;
+
+        // 创建资源
+        let resource = Self::insert_resource(&mut tx, user_id, &resource_data, None).await?;
+
+        // 处理标签
+        if let Some(ref tags) = resource_data.tags {
+            Self::attach_tags(&mut tx, user_id, resource.id, tags).await?;
+        }
+
+        // 链接资源由后台任务抓取页面元数据
+        if resource.resource_type == ResourceType::Link.as_str() && resource.url.is_some() {
+            JobService::enqueue(&mut tx, JobKind::FetchMetadata, user_id, resource.id).await?;
+        }
+
+        // 提交事务 - ACID 保证
+        tx.commit().await?;
+
+        // 异步 FTS 索引
+        let pool = db_pool.clone();
+        let r_id = resource.id;
+        tokio::spawn(async move {
+            if let Err(e) = IndexerService::index_resource_with_pool(&pool, r_id, user_id).await {
+                eprintln!("Background indexing failed for resource {}: {}", r_id, e);
+            }
+        });
+
+        Ok(resource)
+    }
+
+    /// 验证创建资源请求 - 长度限制与类型感知验证
+    ///
+    /// 导入等批量创建路径与 create_resource 共用此验证, 返回解析后的资源类型
+    pub fn validate_create_resource(resource_data: &CreateResource) -> AppResult<ResourceType> {
         // 输入长度验证
         if resource_data.title.len() > MAX_TITLE_LENGTH {
             return Err(AppError::BadRequest(format!(
@@ -99,14 +140,22 @@ impl ResourceService {
             }
         }
 
-        // 开始事务 - 同时更新 resources 和 resources_fts
-        let mut tx = db_pool.begin().await?;
+        Ok(resource_type)
+    }
 
-        // 创建资源
+    /// 在事务中插入资源行 (不处理标签和 FTS 索引)
+    ///
+    /// created_at 为 None 时使用当前时间, 导入时可保留原始创建时间
+    pub(crate) async fn insert_resource(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        resource_data: &CreateResource,
+        created_at: Option<i64>,
+    ) -> AppResult<Resource> {
         let resource = sqlx::query_as::<_, Resource>(
             r#"
-            INSERT INTO resources (user_id, collection_id, title, url, description, is_favorite, is_private, type, content, source, mime_type)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            INSERT INTO resources (user_id, collection_id, title, url, description, is_favorite, is_private, type, content, source, mime_type, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, COALESCE($12, CAST(strftime('%s', 'now') AS INTEGER)))
             RETURNING id, user_id, collection_id, title, url, description, favicon_url, screenshot_url,
                       thumbnail_url, is_favorite, is_archived, is_private, is_read, visit_count,
                       last_visited, metadata, type, content, source, mime_type,
@@ -124,50 +173,54 @@ impl ResourceService {
         .bind(&resource_data.content)
         .bind(&resource_data.source)
         .bind(&resource_data.mime_type)
-        .fetch_one(&mut *tx)
+        .bind(created_at)
+        .fetch_one(&mut **tx)
         .await?;
 
-        // 处理标签
-        if let Some(tags) = resource_data.tags {
-            for tag_name in tags {
-                // 确保标签存在 (SQLite compatible)
-                let tag_row = sqlx::query(
-                    r#"
-                    INSERT OR IGNORE INTO tags (user_id, name)
-                    VALUES ($1, $2);
-                    SELECT id FROM tags WHERE user_id = $1 AND name = $2
-                    "#,
-                )
-                .bind(user_id)
-                .bind(&tag_name)
-                .fetch_one(&mut *tx)
-                .await?;
-                let tag_id: i64 = tag_row.get("id");
+        Ok(resource)
+    }
 
-                // 关联资源与标签
-                sqlx::query(
-                    "INSERT OR IGNORE INTO resource_tags (resource_id, tag_id) VALUES ($1, $2)",
-                )
-                .bind(resource.id)
-                .bind(tag_id)
-                .execute(&mut *tx)
-                .await?;
+    /// 在事务中为资源关联标签, 不存在的标签会被创建
+    ///
+    /// 返回新创建的标签数量
+    pub(crate) async fn attach_tags(
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        user_id: i64,
+        resource_id: i64,
+        tags: &[String],
+    ) -> AppResult<usize> {
+        let mut created = 0;
+
+        for tag_name in tags {
+            // 确保标签存在 (SQLite compatible)
+            let inserted =
+                sqlx::query("INSERT OR IGNORE INTO tags (user_id, name) VALUES ($1, $2)")
+                    .bind(user_id)
+                    .bind(tag_name)
+                    .execute(&mut **tx)
+                    .await?;
+            if inserted.rows_affected() > 0 {
+                created += 1;
             }
-        }
 
-        // 提交事务 - ACID 保证
-        tx.commit().await?;
+            let tag_id: i64 =
+                sqlx::query_scalar("SELECT id FROM tags WHERE user_id = $1 AND name = $2")
+                    .bind(user_id)
+                    .bind(tag_name)
+                    .fetch_one(&mut **tx)
+                    .await?;
 
-        // 异步 FTS 索引
-        let pool = db_pool.clone();
-        let r_id = resource.id;
-        tokio::spawn(async move {
-            if let Err(e) = IndexerService::index_resource_with_pool(&pool, r_id, user_id).await {
-                eprintln!("Background indexing failed for resource {}: {}", r_id, e);
-            }
-        });
+            // 关联资源与标签
+            sqlx::query(
+                "INSERT OR IGNORE INTO resource_tags (resource_id, tag_id) VALUES ($1, $2)",
+            )
+            .bind(resource_id)
+            .bind(tag_id)
+            .execute(&mut **tx)
+            .await?;
+        }
 
-        Ok(resource)
+        Ok(created)
     }
 
     /// 获取资源列表 - 支持类型过滤
@@ -176,26 +229,85 @@ impl ResourceService {
         query: ResourceQuery,
         db_pool: &SqlitePool,
     ) -> AppResult<Vec<ResourceWithTags>> {
-        let options = QueryOptions {
+        if let Some(saved_search_id) = query.saved_search_id {
+            return SavedSearchService::get_resources(
+                user_id,
+                saved_search_id,
+                query.limit.unwrap_or(50),
+                query.offset.unwrap_or(0),
+                db_pool,
+            )
+            .await;
+        }
+
+        let options = Self::list_options(user_id, &query)?;
+
+        query_helper::fetch_resources(db_pool, &options).await
+    }
+
+    /// 获取资源列表 - 按 query.cursor 游标分页, 插入新资源时翻页结果保持稳定
+    pub async fn get_resources_page(
+        user_id: i64,
+        query: ResourceQuery,
+        db_pool: &SqlitePool,
+    ) -> AppResult<CursorPage<ResourceWithTags>> {
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(Cursor::from_param)
+            .transpose()
+            .map_err(AppError::BadRequest)?
+            .flatten();
+        let cursor = cursor.as_ref();
+
+        if let Some(saved_search_id) = query.saved_search_id {
+            return SavedSearchService::get_resources_page(
+                user_id,
+                saved_search_id,
+                query.limit.unwrap_or(50),
+                cursor,
+                db_pool,
+            )
+            .await;
+        }
+
+        let options = Self::list_options(user_id, &query)?;
+
+        query_helper::fetch_resources_page(db_pool, &options, cursor).await
+    }
+
+    fn list_options(user_id: i64, query: &ResourceQuery) -> AppResult<QueryOptions<'_>> {
+        let link_status = query
+            .link_status
+            .as_deref()
+            .map(LinkStatus::from)
+            .transpose()
+            .map_err(AppError::BadRequest)?;
+
+        Ok(QueryOptions {
             user_id,
             collection_id: query.collection_id,
-            resource_type: query.resource_type.as_deref(),
+            resource_types: query.resource_type.as_slice(),
             tags: query.tags.as_deref().unwrap_or(&[]),
+            exclude_tags: &[],
             is_favorite: query.is_favorite,
             is_archived: query.is_archived,
             is_private: query.is_private,
             is_read: query.is_read,
+            link_status,
             search_term: query.search.as_deref(),
             search_type: None,
             date_from: None,
             date_to: None,
+            domain: None,
+            ids: None,
             limit: query.limit.unwrap_or(50),
             offset: query.offset.unwrap_or(0),
             sort_by: query.sort_by.as_deref().unwrap_or("created_at"),
             sort_order: query.sort_order.as_deref().unwrap_or("desc"),
-        };
-
-        query_helper::fetch_resources(db_pool, &options).await
+            ranking: None,
+            ranked_at: None,
+        })
     }
 
     /// 根据 ID 获取单个资源
@@ -278,6 +390,14 @@ impl ResourceService {
         // 开始事务
         let mut tx = db_pool.begin().await?;
 
+        // 记录原 URL 和类型, 用于判断是否需要重新抓取元数据
+        let previous: Option<(Option<String>, String)> =
+            sqlx::query_as("SELECT url, type FROM resources WHERE id = $1 AND user_id = $2")
+                .bind(resource_id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
         // 获取或更新资源
         // 直接执行 UPDATE,如果字段为 None, COALESCE 会保持原值
         let resource = sqlx::query_as::<_, Resource>(
@@ -328,6 +448,26 @@ impl ResourceService {
             return Ok(None);
         };
 
+        // 链接 URL 变化 (或类型改为链接) 后重新抓取元数据, 并清除旧的链接检查结果
+        let link_changed = previous.is_none_or(|(url, resource_type)| {
+            url != updated_resource.url || resource_type != updated_resource.resource_type
+        });
+        if link_changed {
+            LinkCheckerService::reset(&mut tx, updated_resource.id).await?;
+        }
+        if updated_resource.resource_type == ResourceType::Link.as_str()
+            && updated_resource.url.is_some()
+            && link_changed
+        {
+            JobService::enqueue(
+                &mut tx,
+                JobKind::FetchMetadata,
+                user_id,
+                updated_resource.id,
+            )
+            .await?;
+        }
+
         // 处理标签更新
         if let Some(tags) = update_data.tags {
             // 删除现有标签关联
@@ -430,6 +570,11 @@ impl ResourceService {
             .execute(&mut *tx)
             .await?;
 
+        // 存档记录随资源级联删除, 存储目录中的文件在提交后清理
+        let archive_paths = ArchiveService::storage_paths(&mut tx, resource_id).await?;
+        // 上传文件登记到 storage_deletions, 由后台任务删除对象
+        FileService::queue_deletion(&mut tx, user_id, resource_id).await?;
+
         // 删除资源(CASCADE 会自动删除 resource_tags 和 resource_references)
         let result = sqlx::query("DELETE FROM resources WHERE id = $1 AND user_id = $2")
             .bind(resource_id)
@@ -442,6 +587,10 @@ impl ResourceService {
         // 提交事务 - ACID 保证
         tx.commit().await?;
 
+        if was_deleted {
+            ArchiveService::remove_files(&archive_paths).await;
+        }
+
         Ok(was_deleted)
     }
 
@@ -674,8 +823,29 @@ impl ResourceService {
         }
 
         let limit = query.limit.unwrap_or(50);
-        let offset = query.offset.unwrap_or(0);
         let direction = query.direction.as_deref().unwrap_or("both");
+        // 引用列表按 ID 排序, 游标只需要记录 ID
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(Cursor::from_param)
+            .transpose()
+            .map_err(AppError::BadRequest)?
+            .flatten();
+        if cursor
+            .as_ref()
+            .is_some_and(|cursor| cursor.sort != REFERENCE_CURSOR_SORT)
+        {
+            return Err(AppError::BadRequest(
+                "Cursor does not match the current sort order".to_string(),
+            ));
+        }
+        let backward = cursor.as_ref().is_some_and(|cursor| cursor.backward);
+        let offset = if cursor.is_some() {
+            0
+        } else {
+            query.offset.unwrap_or(0)
+        };
 
         // 构建查询
         let mut query_builder = sqlx::QueryBuilder::new(
@@ -751,7 +921,20 @@ impl ResourceService {
         }
 
         query_builder.push(")");
+        if let Some(ref cursor) = cursor {
+            query_builder.push(if backward {
+                " AND r.id < "
+            } else {
+                " AND r.id > "
+            });
+            query_builder.push_bind(cursor.id);
+        }
         query_builder.push(" GROUP BY r.id, c.name, c.color");
+        query_builder.push(if backward {
+            " ORDER BY r.id DESC"
+        } else {
+            " ORDER BY r.id ASC"
+        });
         query_builder.push(" LIMIT ");
         query_builder.push_bind(limit + 1); // 多取一条检测 has_more
         query_builder.push(" OFFSET ");
@@ -768,8 +951,36 @@ impl ResourceService {
         if has_more {
             items.pop(); // 移除多取的那一条
         }
+        if backward {
+            items.reverse();
+        }
+
+        // 游标模式下 has_more 表示当前翻页方向上是否还有数据
+        let (has_next, has_prev) = match cursor {
+            Some(_) if backward => (true, has_more),
+            Some(_) => (has_more, true),
+            None => (has_more, offset > 0),
+        };
+        let cursor_at = |resource: &ResourceWithTags, backward: bool| {
+            Cursor {
+                sort: REFERENCE_CURSOR_SORT.to_string(),
+                value: SortValue::Int(resource.resource.id),
+                id: resource.resource.id,
+                backward,
+                ranked_at: None,
+            }
+            .encode()
+        };
 
         Ok(ResourceReferenceList {
+            next_cursor: items
+                .last()
+                .filter(|_| has_next)
+                .map(|resource| cursor_at(resource, false)),
+            prev_cursor: items
+                .first()
+                .filter(|_| has_prev)
+                .map(|resource| cursor_at(resource, true)),
             items,
             limit,
             offset,
@@ -0,0 +1,367 @@
+use sqlx::SqlitePool;
+
+use crate::models::{
+    Cursor, CursorPage, FilterCriteria, ResourceType, ResourceWithTags, SaveSearchRequest,
+    SavedSearch, SavedSearchWithCount, SearchType,
+};
+use crate::services::query_helper::{self, QueryOptions};
+use crate::services::search_service::{ResolvedQuery, SearchService};
+use crate::utils::error::{AppError, AppResult};
+
+const SAVED_SEARCH_COLUMNS: &str = "id, user_id, name, query, search_type, collection_id, tags,
+    resource_type, is_favorite, is_archived, is_private, is_read, date_from, date_to,
+    sort_by, sort_order, created_at, updated_at";
+
+/// 可用的排序字段, rank 只在查询包含全文条件时生效
+const SORT_FIELDS: [&str; 6] = [
+    "created_at",
+    "updated_at",
+    "title",
+    "visit_count",
+    "last_visited",
+    "rank",
+];
+
+const MAX_NAME_LENGTH: usize = 100;
+
+pub struct SavedSearchService;
+
+impl SavedSearchService {
+    pub async fn create_saved_search(
+        user_id: i64,
+        data: SaveSearchRequest,
+        db_pool: &SqlitePool,
+    ) -> AppResult<SavedSearch> {
+        let data = Self::validate(user_id, data, db_pool).await?;
+
+        let saved_search = sqlx::query_as::<_, SavedSearch>(&format!(
+            r#"
+            INSERT INTO saved_searches (
+                user_id, name, query, search_type, collection_id, tags, resource_type,
+                is_favorite, is_archived, is_private, is_read, date_from, date_to,
+                sort_by, sort_order
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            RETURNING {}
+            "#,
+            SAVED_SEARCH_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&data.name)
+        .bind(data.query.as_deref().unwrap_or_default())
+        .bind(data.search_type.as_deref().unwrap_or("all"))
+        .bind(data.collection_id)
+        .bind(serde_json::to_string(&data.tags.unwrap_or_default())?)
+        .bind(&data.resource_type)
+        .bind(data.is_favorite)
+        .bind(data.is_archived)
+        .bind(data.is_private)
+        .bind(data.is_read)
+        .bind(data.date_from)
+        .bind(data.date_to)
+        .bind(data.sort_by.as_deref().unwrap_or("created_at"))
+        .bind(data.sort_order.as_deref().unwrap_or("desc"))
+        .fetch_one(db_pool)
+        .await
+        .map_err(|e| Self::map_name_conflict(e, &data.name))?;
+
+        Ok(saved_search)
+    }
+
+    /// 获取全部保存的搜索及实时的匹配数量
+    pub async fn get_saved_searches(
+        user_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<SavedSearchWithCount>> {
+        let saved_searches = sqlx::query_as::<_, SavedSearch>(&format!(
+            "SELECT {} FROM saved_searches WHERE user_id = $1 ORDER BY name",
+            SAVED_SEARCH_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut result = Vec::with_capacity(saved_searches.len());
+        for saved_search in saved_searches {
+            // 查询中引用的收藏夹被重命名或删除后条件失效, 此时数量记为 0, 不影响其他条目
+            let resource_count = match Self::count_resources(user_id, &saved_search, db_pool).await
+            {
+                Ok(count) => count,
+                Err(AppError::BadRequest(message)) => {
+                    tracing::warn!(
+                        "Saved search {} can no longer be evaluated: {}",
+                        saved_search.id,
+                        message
+                    );
+                    0
+                }
+                Err(e) => return Err(e),
+            };
+            result.push(SavedSearchWithCount {
+                saved_search,
+                resource_count,
+            });
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_saved_search(
+        user_id: i64,
+        saved_search_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<SavedSearch>> {
+        let saved_search = sqlx::query_as::<_, SavedSearch>(&format!(
+            "SELECT {} FROM saved_searches WHERE id = $1 AND user_id = $2",
+            SAVED_SEARCH_COLUMNS
+        ))
+        .bind(saved_search_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await?;
+
+        Ok(saved_search)
+    }
+
+    /// 更新保存的搜索, 请求中的条件整体替换原有条件
+    pub async fn update_saved_search(
+        user_id: i64,
+        saved_search_id: i64,
+        data: SaveSearchRequest,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<SavedSearch>> {
+        let data = Self::validate(user_id, data, db_pool).await?;
+
+        let saved_search = sqlx::query_as::<_, SavedSearch>(&format!(
+            r#"
+            UPDATE saved_searches SET
+                name = $1,
+                query = $2,
+                search_type = $3,
+                collection_id = $4,
+                tags = $5,
+                resource_type = $6,
+                is_favorite = $7,
+                is_archived = $8,
+                is_private = $9,
+                is_read = $10,
+                date_from = $11,
+                date_to = $12,
+                sort_by = $13,
+                sort_order = $14,
+                updated_at = CAST(strftime('%s', 'now') AS INTEGER)
+            WHERE id = $15 AND user_id = $16
+            RETURNING {}
+            "#,
+            SAVED_SEARCH_COLUMNS
+        ))
+        .bind(&data.name)
+        .bind(data.query.as_deref().unwrap_or_default())
+        .bind(data.search_type.as_deref().unwrap_or("all"))
+        .bind(data.collection_id)
+        .bind(serde_json::to_string(&data.tags.unwrap_or_default())?)
+        .bind(&data.resource_type)
+        .bind(data.is_favorite)
+        .bind(data.is_archived)
+        .bind(data.is_private)
+        .bind(data.is_read)
+        .bind(data.date_from)
+        .bind(data.date_to)
+        .bind(data.sort_by.as_deref().unwrap_or("created_at"))
+        .bind(data.sort_order.as_deref().unwrap_or("desc"))
+        .bind(saved_search_id)
+        .bind(user_id)
+        .fetch_optional(db_pool)
+        .await
+        .map_err(|e| Self::map_name_conflict(e, &data.name))?;
+
+        Ok(saved_search)
+    }
+
+    pub async fn delete_saved_search(
+        user_id: i64,
+        saved_search_id: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM saved_searches WHERE id = $1 AND user_id = $2")
+            .bind(saved_search_id)
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 按保存的条件计算当前成员, 排序使用保存的设置
+    pub async fn get_resources(
+        user_id: i64,
+        saved_search_id: i64,
+        limit: i64,
+        offset: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<ResourceWithTags>> {
+        let saved_search = Self::get_saved_search(user_id, saved_search_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+        let resolved = Self::resolve(user_id, &saved_search, db_pool).await?;
+        let search_type = SearchType::from(&saved_search.search_type).unwrap_or_default();
+        let options = QueryOptions {
+            limit,
+            offset,
+            ..Self::query_options(user_id, &saved_search, &resolved, &search_type)
+        };
+
+        query_helper::fetch_resources(db_pool, &options).await
+    }
+
+    /// 游标分页版本的 get_resources
+    pub async fn get_resources_page(
+        user_id: i64,
+        saved_search_id: i64,
+        limit: i64,
+        cursor: Option<&Cursor>,
+        db_pool: &SqlitePool,
+    ) -> AppResult<CursorPage<ResourceWithTags>> {
+        let saved_search = Self::get_saved_search(user_id, saved_search_id, db_pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+        let resolved = Self::resolve(user_id, &saved_search, db_pool).await?;
+        let search_type = SearchType::from(&saved_search.search_type).unwrap_or_default();
+        let options = QueryOptions {
+            limit,
+            ..Self::query_options(user_id, &saved_search, &resolved, &search_type)
+        };
+
+        query_helper::fetch_resources_page(db_pool, &options, cursor).await
+    }
+
+    pub async fn count_resources(
+        user_id: i64,
+        saved_search: &SavedSearch,
+        db_pool: &SqlitePool,
+    ) -> AppResult<i64> {
+        let resolved = Self::resolve(user_id, saved_search, db_pool).await?;
+        let search_type = SearchType::from(&saved_search.search_type).unwrap_or_default();
+        let options = Self::query_options(user_id, saved_search, &resolved, &search_type);
+
+        query_helper::count_resources(db_pool, &options).await
+    }
+
+    async fn resolve(
+        user_id: i64,
+        saved_search: &SavedSearch,
+        db_pool: &SqlitePool,
+    ) -> AppResult<ResolvedQuery> {
+        let criteria = FilterCriteria {
+            collection_id: saved_search.collection_id,
+            tags: saved_search.tags.clone(),
+            date_from: saved_search.date_from,
+            date_to: saved_search.date_to,
+            ..Default::default()
+        };
+
+        SearchService::resolve_query(user_id, &saved_search.query, &criteria, db_pool).await
+    }
+
+    /// 查询文本中的过滤条件优先于保存的类型和状态条件
+    fn query_options<'a>(
+        user_id: i64,
+        saved_search: &'a SavedSearch,
+        resolved: &'a ResolvedQuery,
+        search_type: &'a SearchType,
+    ) -> QueryOptions<'a> {
+        let options = resolved.query_options(user_id);
+
+        QueryOptions {
+            resource_types: if options.resource_types.is_empty() {
+                saved_search.resource_type.as_slice()
+            } else {
+                options.resource_types
+            },
+            is_favorite: options.is_favorite.or(saved_search.is_favorite),
+            is_archived: options.is_archived.or(saved_search.is_archived),
+            is_private: options.is_private.or(saved_search.is_private),
+            is_read: options.is_read.or(saved_search.is_read),
+            search_type: Some(search_type),
+            sort_by: &saved_search.sort_by,
+            sort_order: &saved_search.sort_order,
+            ..options
+        }
+    }
+
+    /// 校验并规范化请求, 查询文本需要能够解析
+    async fn validate(
+        user_id: i64,
+        mut data: SaveSearchRequest,
+        db_pool: &SqlitePool,
+    ) -> AppResult<SaveSearchRequest> {
+        data.name = data.name.trim().to_string();
+        if data.name.is_empty() || data.name.chars().count() > MAX_NAME_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Saved search name must be 1-{} characters",
+                MAX_NAME_LENGTH
+            )));
+        }
+
+        if let Some(search_type) = data.search_type.as_deref() {
+            SearchType::from(search_type).map_err(AppError::BadRequest)?;
+            data.search_type = Some(search_type.to_lowercase());
+        }
+
+        if let Some(resource_type) = data.resource_type.as_deref() {
+            let resource_type = ResourceType::from(resource_type).map_err(AppError::BadRequest)?;
+            data.resource_type = Some(resource_type.as_str().to_string());
+        }
+
+        if let Some(sort_by) = data.sort_by.as_deref() {
+            if !SORT_FIELDS.contains(&sort_by) {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid sort field: {}",
+                    sort_by
+                )));
+            }
+        }
+
+        if let Some(sort_order) = data.sort_order.as_deref() {
+            let sort_order = sort_order.to_lowercase();
+            if sort_order != "asc" && sort_order != "desc" {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid sort order: {}",
+                    sort_order
+                )));
+            }
+            data.sort_order = Some(sort_order);
+        }
+
+        if let Some(collection_id) = data.collection_id {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM collections WHERE id = $1 AND user_id = $2)",
+            )
+            .bind(collection_id)
+            .bind(user_id)
+            .fetch_one(db_pool)
+            .await?;
+            if !exists {
+                return Err(AppError::BadRequest("Collection not found".to_string()));
+            }
+        }
+
+        // 解析查询并解析其中的收藏夹名称, 保存前发现语法错误
+        let query = data.query.as_deref().unwrap_or_default().trim();
+        SearchService::resolve_query(user_id, query, &FilterCriteria::default(), db_pool).await?;
+        data.query = Some(query.to_string());
+
+        Ok(data)
+    }
+
+    fn map_name_conflict(error: sqlx::Error, name: &str) -> AppError {
+        match error {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                AppError::Conflict(format!("Saved search '{}' already exists", name))
+            }
+            error => error.into(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use sqlx::SqlitePool;
+
+use crate::models::{CreateResource, ResourceQuery, SaveSearchRequest};
+use crate::services::indexer_service::IndexerService;
+use crate::services::resource_service::ResourceService;
+use crate::services::saved_search_service::SavedSearchService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+async fn create_resource(
+    pool: &SqlitePool,
+    user_id: i64,
+    title: &str,
+    resource_type: &str,
+    tags: &[&str],
+    is_favorite: bool,
+) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: (resource_type == "link").then(|| format!("https://example.com/{}", title.len())),
+        description: None,
+        collection_id: None,
+        tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+        is_favorite: Some(is_favorite),
+        is_private: None,
+        resource_type: resource_type.to_string(),
+        content: (resource_type != "link").then(|| "body".to_string()),
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+fn save_request(name: &str, query: &str) -> SaveSearchRequest {
+    SaveSearchRequest {
+        name: name.to_string(),
+        query: Some(query.to_string()),
+        search_type: None,
+        collection_id: None,
+        tags: None,
+        resource_type: None,
+        is_favorite: None,
+        is_archived: None,
+        is_private: None,
+        is_read: None,
+        date_from: None,
+        date_to: None,
+        sort_by: None,
+        sort_order: None,
+    }
+}
+
+async fn saved_search_titles(pool: &SqlitePool, user_id: i64, saved_search_id: i64) -> Vec<String> {
+    let query = ResourceQuery {
+        saved_search_id: Some(saved_search_id),
+        // 保存的搜索忽略其他过滤条件
+        is_favorite: Some(false),
+        ..Default::default()
+    };
+
+    ResourceService::get_resources(user_id, query, pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|resource| resource.resource.title)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_saved_search_members_are_computed_on_read() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "saver").await;
+
+    create_resource(&pool, user_id, "Rust book", "link", &["rust"], true).await;
+    create_resource(&pool, user_id, "Rust snippets", "snippet", &["rust"], true).await;
+    create_resource(&pool, user_id, "Go book", "link", &["go"], true).await;
+
+    let mut request = save_request("Favorite Rust", "book OR snippets");
+    request.tags = Some(vec!["rust".to_string()]);
+    request.is_favorite = Some(true);
+    request.sort_by = Some("title".to_string());
+    request.sort_order = Some("ASC".to_string());
+    let saved_search = SavedSearchService::create_saved_search(user_id, request, &pool)
+        .await
+        .unwrap();
+    assert_eq!(saved_search.tags, vec!["rust"]);
+    assert_eq!(saved_search.sort_order, "asc");
+
+    assert_eq!(
+        saved_search_titles(&pool, user_id, saved_search.id).await,
+        vec!["Rust book", "Rust snippets"]
+    );
+
+    // 新资源满足条件后立即出现在结果和数量中
+    create_resource(&pool, user_id, "Async Rust book", "link", &["rust"], true).await;
+    assert_eq!(
+        saved_search_titles(&pool, user_id, saved_search.id).await,
+        vec!["Async Rust book", "Rust book", "Rust snippets"]
+    );
+
+    let listed = SavedSearchService::get_saved_searches(user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].resource_count, 3);
+
+    // 查询中的过滤条件优先于保存的类型条件
+    let mut request = save_request("Favorite Rust", "type:link");
+    request.resource_type = Some("snippet".to_string());
+    request.tags = Some(vec!["rust".to_string()]);
+    let updated = SavedSearchService::update_saved_search(user_id, saved_search.id, request, &pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.resource_type.as_deref(), Some("snippet"));
+    let mut titles = saved_search_titles(&pool, user_id, saved_search.id).await;
+    titles.sort();
+    assert_eq!(titles, vec!["Async Rust book", "Rust book"]);
+
+    assert!(
+        SavedSearchService::delete_saved_search(user_id, saved_search.id, &pool)
+            .await
+            .unwrap()
+    );
+    let query = ResourceQuery {
+        saved_search_id: Some(saved_search.id),
+        ..Default::default()
+    };
+    assert!(matches!(
+        ResourceService::get_resources(user_id, query, &pool).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_saved_search_validation() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "saver").await;
+
+    SavedSearchService::create_saved_search(user_id, save_request("Unread", "is:unread"), &pool)
+        .await
+        .unwrap();
+
+    // 名称在同一用户下唯一
+    assert!(matches!(
+        SavedSearchService::create_saved_search(user_id, save_request("Unread", ""), &pool).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    let invalid_requests = [
+        save_request("  ", "rust"),
+        save_request("Broken", "rust \"async"),
+        save_request("Missing", "collection:Missing"),
+        SaveSearchRequest {
+            sort_by: Some("random()".to_string()),
+            ..save_request("Sorted", "rust")
+        },
+        SaveSearchRequest {
+            resource_type: Some("video".to_string()),
+            ..save_request("Typed", "rust")
+        },
+        SaveSearchRequest {
+            collection_id: Some(999),
+            ..save_request("Collected", "rust")
+        },
+    ];
+    for request in invalid_requests {
+        let name = request.name.clone();
+        assert!(
+            matches!(
+                SavedSearchService::create_saved_search(user_id, request, &pool).await,
+                Err(AppError::BadRequest(_))
+            ),
+            "{} should be rejected",
+            name
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_saved_search_with_renamed_collection_counts_zero() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "saver").await;
+
+    let collection_id: i64 = sqlx::query_scalar(
+        "INSERT INTO collections (user_id, name) VALUES (?, 'Design') RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    SavedSearchService::create_saved_search(
+        user_id,
+        save_request("Design", "collection:design"),
+        &pool,
+    )
+    .await
+    .unwrap();
+
+    sqlx::query("UPDATE collections SET name = 'Visual' WHERE id = ?")
+        .bind(collection_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let listed = SavedSearchService::get_saved_searches(user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(listed[0].resource_count, 0);
+}
@@ -0,0 +1,141 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::models::{
+    PopularSearch, SearchFilters, SearchHistoryEntry, SearchHistoryQuery, SearchResponse,
+};
+use crate::utils::error::AppResult;
+
+/// 每个用户保留的历史记录条数, 超出时删除最早的记录
+const MAX_HISTORY_ENTRIES: i64 = 1000;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 20;
+const MAX_HISTORY_LIMIT: i64 = 100;
+
+pub struct SearchHistoryService;
+
+impl SearchHistoryService {
+    /// 记录一次搜索
+    pub async fn record_search(
+        user_id: i64,
+        filters: &SearchFilters,
+        response: &SearchResponse,
+        db_pool: &SqlitePool,
+    ) -> AppResult<()> {
+        let query = filters.query.trim();
+        if query.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = db_pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO search_history (user_id, query, search_type, filters, result_count, search_time)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(user_id)
+        .bind(query)
+        .bind(filters.search_type.as_str())
+        .bind(serde_json::to_string(&filters.filters)?)
+        .bind(response.pagination.total)
+        .bind(response.search_time)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM search_history
+            WHERE user_id = $1 AND id NOT IN (
+                SELECT id FROM search_history
+                WHERE user_id = $1
+                ORDER BY id DESC
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(MAX_HISTORY_ENTRIES)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 最近的搜索, 按时间倒序
+    pub async fn get_recent_searches(
+        user_id: i64,
+        query: SearchHistoryQuery,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<SearchHistoryEntry>> {
+        let entries = sqlx::query_as::<_, SearchHistoryEntry>(
+            r#"
+            SELECT id, query, search_type, filters, result_count, search_time, created_at
+            FROM search_history
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(Self::limit(&query))
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// 搜索次数最多的查询, 忽略大小写合并, 显示最近一次使用的写法
+    pub async fn get_popular_searches(
+        user_id: i64,
+        query: SearchHistoryQuery,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<PopularSearch>> {
+        let since = query
+            .days
+            .map(|days| Utc::now().timestamp() - days.max(0) * 86_400)
+            .unwrap_or(0);
+
+        let popular = sqlx::query_as::<_, PopularSearch>(
+            r#"
+            SELECT
+                (SELECT h.query FROM search_history h
+                 WHERE h.user_id = $1 AND h.query = s.query COLLATE NOCASE
+                 ORDER BY h.id DESC LIMIT 1) as query,
+                COUNT(*) as search_count,
+                MAX(s.created_at) as last_searched_at
+            FROM search_history s
+            WHERE s.user_id = $1 AND s.created_at >= $2
+            GROUP BY s.query COLLATE NOCASE
+            ORDER BY search_count DESC, last_searched_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(Self::limit(&query))
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(popular)
+    }
+
+    /// 清空搜索历史, 返回删除的条数
+    pub async fn clear_history(user_id: i64, db_pool: &SqlitePool) -> AppResult<u64> {
+        let result = sqlx::query("DELETE FROM search_history WHERE user_id = $1")
+            .bind(user_id)
+            .execute(db_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    fn limit(query: &SearchHistoryQuery) -> i64 {
+        query
+            .limit
+            .unwrap_or(DEFAULT_HISTORY_LIMIT)
+            .clamp(1, MAX_HISTORY_LIMIT)
+    }
+}
@@ -0,0 +1,161 @@
+use sqlx::SqlitePool;
+
+use crate::config::RankingConfig;
+use crate::models::{
+    CreateResource, FilterCriteria, PaginationParams, SearchFilters, SearchHistoryQuery,
+    SearchMode, SearchType,
+};
+use crate::services::indexer_service::IndexerService;
+use crate::services::resource_service::ResourceService;
+use crate::services::search_history_service::SearchHistoryService;
+use crate::services::search_service::SearchService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+
+async fn create_note(pool: &SqlitePool, user_id: i64, title: &str) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "note".to_string(),
+        content: Some("notes".to_string()),
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+async fn search(pool: &SqlitePool, user_id: i64, query: &str, page: i64) -> i64 {
+    let filters = SearchFilters {
+        query: query.to_string(),
+        search_type: SearchType::Title,
+        mode: SearchMode::Keyword,
+        filters: FilterCriteria {
+            is_favorite: Some(false),
+            ..Default::default()
+        },
+        pagination: PaginationParams::from_page(page, 1),
+        highlight: None,
+        facet_limit: None,
+        cursor: None,
+        ranking: RankingConfig::default(),
+        include_scores: false,
+    };
+
+    SearchService::search_resources(user_id, filters, pool)
+        .await
+        .unwrap()
+        .pagination
+        .total
+}
+
+#[tokio::test]
+async fn test_searches_are_recorded_once_per_query() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "historian").await;
+
+    create_note(&pool, user_id, "Rust async").await;
+    create_note(&pool, user_id, "Rust macros").await;
+
+    assert_eq!(search(&pool, user_id, "rust", 1).await, 2);
+    // 翻页不重复记录
+    search(&pool, user_id, "rust", 2).await;
+    assert_eq!(search(&pool, user_id, "python", 1).await, 0);
+
+    let history =
+        SearchHistoryService::get_recent_searches(user_id, SearchHistoryQuery::default(), &pool)
+            .await
+            .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].query, "python");
+    assert_eq!(history[0].result_count, 0);
+    assert_eq!(history[1].query, "rust");
+    assert_eq!(history[1].result_count, 2);
+    assert_eq!(history[1].search_type, "title");
+    assert_eq!(
+        history[1].filters,
+        serde_json::json!({"is_favorite": false})
+    );
+    assert!(history[1].search_time >= 0.0);
+}
+
+#[tokio::test]
+async fn test_popular_searches_and_suggestions() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "historian").await;
+
+    create_note(&pool, user_id, "Rust async").await;
+
+    search(&pool, user_id, "rust", 1).await;
+    search(&pool, user_id, "Rust", 1).await;
+    search(&pool, user_id, "async", 1).await;
+    search(&pool, user_id, "rusty nails", 1).await;
+
+    let popular =
+        SearchHistoryService::get_popular_searches(user_id, SearchHistoryQuery::default(), &pool)
+            .await
+            .unwrap();
+    let popular: Vec<(&str, i64)> = popular
+        .iter()
+        .map(|search| (search.query.as_str(), search.search_count))
+        .collect();
+    // 忽略大小写合并, 显示最近一次的写法
+    assert_eq!(popular[0], ("Rust", 2));
+    assert_eq!(popular.len(), 3);
+
+    // 只有返回过结果的历史搜索作为建议
+    let suggestions = SearchService::get_search_suggestions(user_id, "rus", None, &pool)
+        .await
+        .unwrap();
+    let history: Vec<&str> = suggestions
+        .iter()
+        .filter(|suggestion| suggestion.suggestion_type == "history")
+        .map(|suggestion| suggestion.text.as_str())
+        .collect();
+    assert_eq!(history.len(), 1);
+    assert!(history[0].eq_ignore_ascii_case("rust"));
+}
+
+#[tokio::test]
+async fn test_clear_history_only_affects_own_entries() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "historian").await;
+    let other_user_id = create_test_user(&pool, "other").await;
+
+    search(&pool, user_id, "rust", 1).await;
+    search(&pool, user_id, "async", 1).await;
+    search(&pool, other_user_id, "rust", 1).await;
+
+    let deleted = SearchHistoryService::clear_history(user_id, &pool)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 2);
+
+    let history =
+        SearchHistoryService::get_recent_searches(user_id, SearchHistoryQuery::default(), &pool)
+            .await
+            .unwrap();
+    assert!(history.is_empty());
+
+    let history = SearchHistoryService::get_recent_searches(
+        other_user_id,
+        SearchHistoryQuery::default(),
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(history.len(), 1);
+}
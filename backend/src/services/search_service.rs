@@ -1,17 +1,127 @@
-use sqlx::{Row, SqlitePool};
+use chrono::Utc;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use crate::models::{SearchFilters, SearchPagination, SearchResponse, SearchSuggestion};
+use crate::config::RankingConfig;
+use crate::models::{
+    Cursor, FieldHighlights, FilterCriteria, HighlightOptions, LinkStatus, RelevanceScore,
+    SearchFilters, SearchPagination, SearchResponse, SearchSuggestion, SearchType,
+};
 use crate::services::query_helper::{self, QueryOptions};
-use crate::utils::error::AppResult;
-use crate::utils::segmenter::prepare_for_search;
+use crate::services::search_history_service::SearchHistoryService;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::search_query::{parse_search_query, SearchQuery};
+use crate::utils::segmenter::restore_segmented_text;
+use crate::utils::spelling::{
+    apply_corrections, correctable_words, edit_distance, max_edit_distance,
+};
 
 // 搜索验证常量
-const MIN_SEARCH_QUERY_LENGTH: usize = 3;
+pub const MIN_SEARCH_QUERY_LENGTH: usize = 3;
+
+/// 生成高亮片段的 FTS 列 (列序号, 字段名)
+const HIGHLIGHT_COLUMNS: [(i64, &str); 6] = [
+    (0, "title"),
+    (1, "description"),
+    (2, "content"),
+    (3, "tags"),
+    (4, "url"),
+    (5, "extracted_text"),
+];
+/// 片段被截断处使用的省略号
+const SNIPPET_ELLIPSIS: &str = "…";
+/// 建议排序中最近使用时间的半衰期 (天)
+const SUGGESTION_RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+/// 拼写纠正时保留的候选词数量
+const MAX_CORRECTION_CANDIDATES: usize = 10;
 
 pub struct SearchService;
 
+/// 查询语言与请求过滤条件合并后的搜索条件
+pub struct ResolvedQuery {
+    pub parsed: SearchQuery,
+    pub collection_id: Option<i64>,
+    pub resource_types: Vec<String>,
+    pub tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    pub is_favorite: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+    pub is_read: Option<bool>,
+    pub link_status: Option<LinkStatus>,
+    pub date_from: Option<i64>,
+    pub date_to: Option<i64>,
+}
+
+impl ResolvedQuery {
+    /// 生成列表查询选项, 调用方按需覆盖搜索类型、排序和分页
+    pub fn query_options(&self, user_id: i64) -> QueryOptions<'_> {
+        QueryOptions {
+            user_id,
+            collection_id: self.collection_id,
+            resource_types: &self.resource_types,
+            tags: &self.tags,
+            exclude_tags: &self.exclude_tags,
+            is_favorite: self.is_favorite,
+            is_archived: self.is_archived,
+            is_private: self.is_private,
+            is_read: self.is_read,
+            link_status: self.link_status,
+            search_term: self.parsed.fts_query.as_deref(),
+            date_from: self.date_from,
+            date_to: self.date_to,
+            domain: self.parsed.domain.as_deref(),
+            ..Default::default()
+        }
+    }
+}
+
 impl SearchService {
+    /// 解析查询语言并与请求中的过滤条件合并, 查询中的过滤条件优先
+    ///
+    /// 全文部分编译为 FTS5 表达式 (已按分词策略处理); 查询为空时只使用过滤条件
+    pub async fn resolve_query(
+        user_id: i64,
+        query: &str,
+        criteria: &FilterCriteria,
+        db_pool: &SqlitePool,
+    ) -> AppResult<ResolvedQuery> {
+        let parsed = if query.trim().is_empty() {
+            SearchQuery::default()
+        } else {
+            parse_search_query(query)
+                .map_err(|e| AppError::BadRequest(format!("Invalid search query: {}", e)))?
+        };
+
+        let collection_id = match parsed.collection {
+            Some(ref name) => Some(Self::resolve_collection(user_id, name, db_pool).await?),
+            None => criteria.collection_id,
+        };
+        let resource_types = match parsed.resource_type {
+            Some(ref resource_type) => vec![resource_type.clone()],
+            None => criteria.resource_types.clone(),
+        };
+        let tags = criteria.tags.iter().chain(&parsed.tags).cloned().collect();
+        let date_from = criteria.date_from.into_iter().chain(parsed.date_from).max();
+        let date_to = criteria.date_to.into_iter().chain(parsed.date_to).min();
+
+        Ok(ResolvedQuery {
+            collection_id,
+            resource_types,
+            tags,
+            exclude_tags: criteria.exclude_tags.clone(),
+            is_favorite: parsed.is_favorite.or(criteria.is_favorite),
+            is_archived: parsed.is_archived.or(criteria.is_archived),
+            is_private: parsed.is_private.or(criteria.is_private),
+            is_read: parsed.is_read.or(criteria.is_read),
+            link_status: criteria.link_status,
+            date_from,
+            date_to,
+            parsed,
+        })
+    }
+
     pub async fn search_resources(
         user_id: i64,
         filters: SearchFilters,
@@ -19,7 +129,7 @@ impl SearchService {
     ) -> AppResult<SearchResponse> {
         // 验证搜索词最小长度
         if filters.query.trim().len() < MIN_SEARCH_QUERY_LENGTH {
-            return Err(crate::utils::error::AppError::BadRequest(format!(
+            return Err(AppError::BadRequest(format!(
                 "Search query must be at least {} characters",
                 MIN_SEARCH_QUERY_LENGTH
             )));
@@ -27,63 +137,312 @@ impl SearchService {
 
         let start = Instant::now();
 
-        // 根据配置对查询进行分词处理 (虽然 FTS 会自己处理,但在 unified query 中我们还是需要它吗?
-        // query_helper converts search_term to match query.
-        // Wait, prepare_for_search does segmentation (jieba). We need that.
-        let search_keywords = prepare_for_search(Some(&filters.query));
+        let resolved =
+            Self::resolve_query(user_id, &filters.query, &filters.filters, db_pool).await?;
 
+        // 传入 cursor 时使用游标分页
+        let cursor = filters
+            .cursor
+            .as_deref()
+            .map(Cursor::from_param)
+            .transpose()
+            .map_err(AppError::BadRequest)?;
+        // 同一次翻页过程使用相同的参考时间计算新近度, 得分才能与游标比较
+        let ranked_at = cursor
+            .as_ref()
+            .and_then(|cursor| cursor.as_ref())
+            .and_then(|cursor| cursor.ranked_at)
+            .unwrap_or_else(|| Utc::now().timestamp());
         let options = QueryOptions {
-            user_id,
-            collection_id: filters.filters.collection_id,
-            resource_type: None, // SearchFilters doesn't have type filter? Wait, it should. `filters.filters` has tags etc.
-            // Check FilterCriteria definition in search.rs. It doesn't have resource_type usually?
-            // Let's check search.rs content again.
-            // Yes, FilterCriteria struct (lines 37-43) has: collection_id, tags, date_from, date_to. No resource_type.
-            tags: &filters.filters.tags,
-            is_favorite: None, // Search usually doesn't filter specific flags, or maybe it should?
-            is_archived: None,
-            is_private: None,
-            is_read: None,
-            search_term: Some(&search_keywords),
             search_type: Some(&filters.search_type),
-            date_from: filters.filters.date_from,
-            date_to: filters.filters.date_to,
             limit: filters.pagination.limit,
             offset: filters.pagination.offset,
-            sort_by: "rank", // Search defaults to rank
+            // 只有过滤条件时没有相关度, 按创建时间排序
+            sort_by: if resolved.parsed.fts_query.is_some() {
+                "rank"
+            } else {
+                "created_at"
+            },
             sort_order: "desc",
+            ranking: Some(&filters.ranking),
+            ranked_at: Some(ranked_at),
+            ..resolved.query_options(user_id)
         };
 
         // 执行主查询
-        let resources = query_helper::fetch_resources(db_pool, &options).await?;
+        let (resources, cursor_page) = match cursor {
+            Some(cursor) => {
+                let page =
+                    query_helper::fetch_resources_page(db_pool, &options, cursor.as_ref()).await?;
+                (page.items, Some((page.next_cursor, page.prev_cursor)))
+            }
+            None => (
+                query_helper::fetch_resources(db_pool, &options).await?,
+                None,
+            ),
+        };
 
         // 执行计数查询
         let total = query_helper::count_resources(db_pool, &options).await?;
 
+        // 分面基于全部匹配结果统计
+        let facets = match filters.facet_limit {
+            Some(facet_limit) => {
+                Some(query_helper::fetch_facets(db_pool, &options, facet_limit).await?)
+            }
+            None => None,
+        };
+
+        // 只为当前页的结果生成高亮片段
+        let highlights = match (&filters.highlight, &resolved.parsed.fts_query) {
+            (Some(highlight), Some(fts_query)) if !resources.is_empty() => {
+                let resource_ids: Vec<i64> = resources
+                    .iter()
+                    .map(|resource| resource.resource.id)
+                    .collect();
+                Some(
+                    Self::fetch_highlights(
+                        fts_query,
+                        &filters.search_type,
+                        &resource_ids,
+                        highlight,
+                        db_pool,
+                    )
+                    .await?,
+                )
+            }
+            _ => None,
+        };
+
+        let scores = match &resolved.parsed.fts_query {
+            Some(fts_query) if filters.include_scores && !resources.is_empty() => {
+                let resource_ids: Vec<i64> = resources
+                    .iter()
+                    .map(|resource| resource.resource.id)
+                    .collect();
+                Some(
+                    Self::fetch_scores(
+                        fts_query,
+                        &filters.search_type,
+                        &resource_ids,
+                        &filters.ranking,
+                        ranked_at,
+                        db_pool,
+                    )
+                    .await?,
+                )
+            }
+            _ => None,
+        };
+
+        // 没有结果时尝试纠正拼写错误的词语
+        let did_you_mean = if total == 0 && resolved.parsed.fts_query.is_some() {
+            Self::suggest_correction(user_id, &filters.query, db_pool).await?
+        } else {
+            None
+        };
+
         // 构建响应
         let elapsed = start.elapsed().as_secs_f64();
-        let page = filters.pagination.page();
         let total_pages = if total == 0 {
             0
         } else {
             (total + filters.pagination.limit - 1) / filters.pagination.limit
         };
-
-        Ok(SearchResponse {
-            items: resources,
-            pagination: SearchPagination {
-                page,
+        let pagination = match cursor_page {
+            Some((next_cursor, prev_cursor)) => SearchPagination {
+                page: None,
                 limit: filters.pagination.limit,
                 total,
                 total_pages,
-                has_next: page < total_pages,
-                has_prev: page > 1,
+                has_next: next_cursor.is_some(),
+                has_prev: prev_cursor.is_some(),
+                next_cursor,
+                prev_cursor,
             },
+            None => {
+                let page = filters.pagination.page();
+                SearchPagination {
+                    page: Some(page),
+                    limit: filters.pagination.limit,
+                    total,
+                    total_pages,
+                    has_next: page < total_pages,
+                    has_prev: page > 1,
+                    next_cursor: None,
+                    prev_cursor: None,
+                }
+            }
+        };
+
+        let response = SearchResponse {
+            items: resources,
+            pagination,
             search_time: elapsed,
-            highlights: None,
+            highlights,
+            facets,
+            did_you_mean,
+            scores,
+        };
+
+        // 只记录第一页, 翻页不重复记录; 记录失败不影响搜索结果
+        let first_page = match filters.cursor.as_deref() {
+            Some(cursor) => cursor.is_empty(),
+            None => filters.pagination.offset == 0,
+        };
+        if first_page {
+            if let Err(e) =
+                SearchHistoryService::record_search(user_id, &filters, &response, db_pool).await
+            {
+                tracing::warn!("Failed to record search history: {}", e);
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// 将查询中不在用户词表里的词替换为编辑距离最近的词, 没有可纠正的词时返回 None
+    pub async fn suggest_correction(
+        user_id: i64,
+        query: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<String>> {
+        let mut corrections = HashMap::new();
+        for word in correctable_words(query) {
+            if Self::user_term(user_id, &word, db_pool).await?.is_some() {
+                continue;
+            }
+            if let Some(term) = Self::similar_terms(user_id, &word, db_pool)
+                .await?
+                .into_iter()
+                .next()
+            {
+                corrections.insert(word, term.term);
+            }
+        }
+
+        if corrections.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(apply_corrections(query, &corrections)))
+    }
+
+    /// 使用 FTS5 snippet() 生成各字段的高亮片段
+    ///
+    /// snippet() 只能在带 MATCH 的查询中使用, 因此对当前页的资源重新执行一次匹配。
+    /// 没有命中词的字段不返回; jieba 模式下片段中的分词空格会被还原。
+    pub async fn fetch_highlights(
+        fts_query: &str,
+        search_type: &SearchType,
+        resource_ids: &[i64],
+        options: &HighlightOptions,
+        db_pool: &SqlitePool,
+    ) -> AppResult<HashMap<i64, FieldHighlights>> {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT rowid");
+        for (column, name) in HIGHLIGHT_COLUMNS {
+            query_builder.push(format!(", snippet(resources_fts, {}, ", column));
+            query_builder.push_bind(&options.start_marker);
+            query_builder.push(", ");
+            query_builder.push_bind(&options.end_marker);
+            query_builder.push(", ");
+            query_builder.push_bind(SNIPPET_ELLIPSIS);
+            query_builder.push(", ");
+            query_builder.push_bind(options.snippet_tokens);
+            query_builder.push(format!(") AS {}", name));
+        }
+
+        query_builder.push(" FROM resources_fts WHERE ");
+        query_helper::push_fts_match(&mut query_builder, Some(search_type), fts_query);
+        query_builder.push(" AND rowid IN (");
+        let mut separated = query_builder.separated(", ");
+        for resource_id in resource_ids {
+            separated.push_bind(*resource_id);
+        }
+        query_builder.push(")");
+
+        let rows = query_builder.build().fetch_all(db_pool).await?;
+
+        let mut highlights = HashMap::new();
+        for row in rows {
+            let resource_id: i64 = row.get("rowid");
+            let mut fields = FieldHighlights::new();
+            for (_, name) in HIGHLIGHT_COLUMNS {
+                let snippet: Option<String> = row.get(name);
+                if let Some(snippet) = snippet.filter(|s| s.contains(&options.start_marker)) {
+                    fields.insert(name.to_string(), vec![restore_segmented_text(&snippet)]);
+                }
+            }
+            if !fields.is_empty() {
+                highlights.insert(resource_id, fields);
+            }
+        }
+
+        Ok(highlights)
+    }
+
+    /// 当前页结果的相关度得分, 与排序使用相同的表达式
+    async fn fetch_scores(
+        fts_query: &str,
+        search_type: &SearchType,
+        resource_ids: &[i64],
+        ranking: &RankingConfig,
+        ranked_at: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<HashMap<i64, RelevanceScore>> {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+            "SELECT r.id, {} AS text_score, {} AS boost
+             FROM resources r JOIN resources_fts fts ON r.id = fts.rowid WHERE ",
+            query_helper::TEXT_SCORE_EXPRESSION,
+            query_helper::boost_expression(ranking, ranked_at)
+        ));
+        query_helper::push_fts_match(&mut query_builder, Some(search_type), fts_query);
+        query_helper::push_fts_rank(&mut query_builder, ranking);
+        query_builder.push(" AND r.id IN (");
+        let mut separated = query_builder.separated(", ");
+        for resource_id in resource_ids {
+            separated.push_bind(*resource_id);
+        }
+        query_builder.push(")");
+
+        let rows = query_builder.build().fetch_all(db_pool).await?;
+
+        let mut scores = HashMap::new();
+        for row in rows {
+            let text_score: f64 = row.get("text_score");
+            let boost: f64 = row.get("boost");
+            scores.insert(
+                row.get("id"),
+                RelevanceScore {
+                    score: text_score * boost,
+                    text_score,
+                    boost,
+                },
+            );
+        }
+
+        Ok(scores)
+    }
+
+    /// 按名称查找当前用户的收藏夹 (不区分大小写)
+    async fn resolve_collection(user_id: i64, name: &str, db_pool: &SqlitePool) -> AppResult<i64> {
+        sqlx::query_scalar(
+            "SELECT id FROM collections WHERE user_id = ? AND name = ? COLLATE NOCASE",
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Invalid search query: collection '{}' not found",
+                name
+            ))
         })
     }
 
+    /// 搜索建议: 补全最后一个词、匹配标题、标签和有结果的历史搜索, 按使用频率和最近使用时间排序
+    ///
+    /// 最后一个词没有可补全的索引词时, 返回编辑距离相近的词作为纠正建议。
     pub async fn get_search_suggestions(
         user_id: i64,
         query: &str,
@@ -116,6 +475,18 @@ impl SearchService {
                 WHERE t.user_id = $1
                   AND lower(t.name) LIKE lower($2 || '%')
                 GROUP BY t.name
+
+                UNION ALL
+
+                SELECT MAX(h.query) as suggestion,
+                       'history' as suggestion_type,
+                       COUNT(*) as usage_count,
+                       MAX(h.created_at) as last_used_at
+                FROM search_history h
+                WHERE h.user_id = $1
+                  AND h.result_count > 0
+                  AND lower(h.query) LIKE lower($2 || '%')
+                GROUP BY lower(h.query)
             ) combined
             WHERE suggestion IS NOT NULL AND suggestion <> ''
             ORDER BY usage_count DESC
@@ -127,7 +498,7 @@ impl SearchService {
         .fetch_all(db_pool)
         .await?;
 
-        Ok(rows
+        let mut suggestions: Vec<SearchSuggestion> = rows
             .into_iter()
             .map(|row| SearchSuggestion {
                 text: row
@@ -139,8 +510,176 @@ impl SearchService {
                 count: row.get::<Option<i64>, _>("usage_count").unwrap_or(0),
                 last_used_at: row.get("last_used_at"),
             })
+            .collect();
+
+        // 补全或纠正最后一个词, 前面的部分原样保留
+        let (head, last_word) = match query.rfind(char::is_whitespace) {
+            Some(index) => query.split_at(index + 1),
+            None => ("", query),
+        };
+        let prefix = last_word.to_lowercase();
+        if !prefix.is_empty() {
+            let completions = Self::complete_term(user_id, &prefix, limit, db_pool).await?;
+            let (terms, suggestion_type) = if completions.is_empty() {
+                let corrections = if correctable_words(&prefix).is_empty() {
+                    Vec::new()
+                } else {
+                    Self::similar_terms(user_id, &prefix, db_pool).await?
+                };
+                (corrections, "correction")
+            } else {
+                (completions, "term")
+            };
+            suggestions.extend(terms.into_iter().map(|term| SearchSuggestion {
+                text: format!("{}{}", head, term.term),
+                suggestion_type: suggestion_type.to_string(),
+                count: term.count,
+                last_used_at: term.last_used_at,
+            }));
+        }
+
+        // 相同文本只保留得分最高的一条
+        let now = Utc::now().timestamp();
+        suggestions.sort_by(|a, b| {
+            suggestion_score(b, now)
+                .total_cmp(&suggestion_score(a, now))
+                .then_with(|| a.text.cmp(&b.text))
+        });
+        let mut seen = HashSet::new();
+        suggestions.retain(|suggestion| seen.insert(suggestion.text.to_lowercase()));
+        suggestions.truncate(limit.max(0) as usize);
+
+        Ok(suggestions)
+    }
+
+    /// 以 prefix 开头的索引词, 只统计用户自己的资源
+    async fn complete_term(
+        user_id: i64,
+        prefix: &str,
+        limit: i64,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<TermMatch>> {
+        // CROSS JOIN 固定以词表为外层循环, 使 term 的范围条件由 fts5vocab 处理
+        let rows = sqlx::query(
+            r#"
+            SELECT v.term, COUNT(DISTINCT v.doc) as doc_count, MAX(r.updated_at) as last_used_at
+            FROM resources_fts_vocab_instance v
+            CROSS JOIN resources r ON r.id = v.doc
+            WHERE v.term >= $1 AND v.term <= $2 AND r.user_id = $3
+            GROUP BY v.term
+            ORDER BY doc_count DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(prefix)
+        .bind(format!("{}{}", prefix, char::MAX))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TermMatch {
+                term: row.get("term"),
+                count: row.get("doc_count"),
+                last_used_at: row.get("last_used_at"),
+            })
             .collect())
     }
+
+    /// 用户资源中的索引词, 不存在时返回 None
+    async fn user_term(
+        user_id: i64,
+        term: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Option<TermMatch>> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(DISTINCT v.doc) as doc_count, MAX(r.updated_at) as last_used_at
+            FROM resources_fts_vocab_instance v
+            CROSS JOIN resources r ON r.id = v.doc
+            WHERE v.term = $1 AND r.user_id = $2
+            "#,
+        )
+        .bind(term)
+        .bind(user_id)
+        .fetch_one(db_pool)
+        .await?;
+
+        let count: i64 = row.get("doc_count");
+        Ok((count > 0).then(|| TermMatch {
+            term: term.to_string(),
+            count,
+            last_used_at: row.get("last_used_at"),
+        }))
+    }
+
+    /// 编辑距离在允许范围内的用户索引词, 按距离和使用频率排序
+    ///
+    /// 只在用户自己资源的索引词中按长度筛选候选词, 排序后保留前 MAX_CORRECTION_CANDIDATES 个,
+    /// 其他用户的词既不会被建议, 也不会挤占候选名额。
+    async fn similar_terms(
+        user_id: i64,
+        word: &str,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<TermMatch>> {
+        let max_distance = max_edit_distance(word);
+        let length = word.chars().count();
+
+        let rows = sqlx::query(
+            r#"
+            SELECT v.term, COUNT(DISTINCT v.doc) as doc_count, MAX(r.updated_at) as last_used_at
+            FROM resources_fts_vocab_instance v
+            CROSS JOIN resources r ON r.id = v.doc
+            WHERE r.user_id = $1 AND length(v.term) BETWEEN $2 AND $3
+            GROUP BY v.term
+            "#,
+        )
+        .bind(user_id)
+        .bind(length.saturating_sub(max_distance) as i64)
+        .bind((length + max_distance) as i64)
+        .fetch_all(db_pool)
+        .await?;
+
+        let mut matches: Vec<(usize, TermMatch)> = rows
+            .into_iter()
+            .filter_map(|row| {
+                let term: String = row.get("term");
+                let distance = edit_distance(word, &term);
+                (distance > 0 && distance <= max_distance).then(|| {
+                    let term = TermMatch {
+                        term,
+                        count: row.get("doc_count"),
+                        last_used_at: row.get("last_used_at"),
+                    };
+                    (distance, term)
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.count.cmp(&a.1.count)));
+        matches.truncate(MAX_CORRECTION_CANDIDATES);
+
+        Ok(matches.into_iter().map(|(_, term)| term).collect())
+    }
+}
+
+/// 用户资源中的索引词及其统计
+struct TermMatch {
+    term: String,
+    /// 包含该词的资源数
+    count: i64,
+    last_used_at: Option<i64>,
+}
+
+/// 建议得分: 使用次数取对数, 最近使用的建议最多加倍
+fn suggestion_score(suggestion: &SearchSuggestion, now: i64) -> f64 {
+    let frequency = (1.0 + suggestion.count.max(0) as f64).ln();
+    let recency = suggestion.last_used_at.map_or(0.0, |last_used_at| {
+        let age_days = (now - last_used_at).max(0) as f64 / 86_400.0;
+        0.5_f64.powf(age_days / SUGGESTION_RECENCY_HALF_LIFE_DAYS)
+    });
+    frequency * (1.0 + recency)
 }
 
 // Helper functions removed
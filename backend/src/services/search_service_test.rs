@@ -0,0 +1,704 @@
+use sqlx::SqlitePool;
+
+use crate::config::RankingConfig;
+use crate::models::{
+    CreateResource, FacetCount, FilterCriteria, HighlightOptions, PaginationParams, SearchFilters,
+    SearchMode, SearchType,
+};
+use crate::services::indexer_service::IndexerService;
+use crate::services::resource_service::ResourceService;
+use crate::services::search_service::SearchService;
+use crate::services::test_support::{create_test_pool, create_test_user};
+
+async fn create_note(
+    pool: &SqlitePool,
+    user_id: i64,
+    title: &str,
+    description: Option<&str>,
+    content: &str,
+) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: description.map(str::to_string),
+        collection_id: None,
+        tags: Some(vec!["reading".to_string()]),
+        is_favorite: None,
+        is_private: None,
+        resource_type: "note".to_string(),
+        content: Some(content.to_string()),
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+async fn create_link(
+    pool: &SqlitePool,
+    user_id: i64,
+    title: &str,
+    url: &str,
+    collection_id: Option<i64>,
+    is_favorite: bool,
+) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: Some(url.to_string()),
+        description: None,
+        collection_id,
+        tags: Some(vec!["web".to_string()]),
+        is_favorite: Some(is_favorite),
+        is_private: None,
+        resource_type: "link".to_string(),
+        content: None,
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+async fn search_titles(pool: &SqlitePool, user_id: i64, query: &str) -> Vec<String> {
+    let response = SearchService::search_resources(user_id, search_filters(query, None), pool)
+        .await
+        .unwrap();
+    let mut titles: Vec<String> = response
+        .items
+        .into_iter()
+        .map(|item| item.resource.title)
+        .collect();
+    titles.sort();
+    titles
+}
+
+fn facet_counts(facets: &[FacetCount]) -> Vec<(&str, i64)> {
+    facets
+        .iter()
+        .map(|facet| (facet.value.as_str(), facet.count))
+        .collect()
+}
+
+fn search_filters(query: &str, highlight: Option<HighlightOptions>) -> SearchFilters {
+    SearchFilters {
+        query: query.to_string(),
+        search_type: SearchType::All,
+        mode: SearchMode::Keyword,
+        filters: FilterCriteria::default(),
+        pagination: PaginationParams::from_page(1, 20),
+        highlight,
+        facet_limit: None,
+        cursor: None,
+        ranking: RankingConfig::default(),
+        include_scores: false,
+    }
+}
+
+fn marks(snippet_tokens: i64) -> Option<HighlightOptions> {
+    Some(HighlightOptions {
+        start_marker: "<mark>".to_string(),
+        end_marker: "</mark>".to_string(),
+        snippet_tokens,
+    })
+}
+
+#[tokio::test]
+async fn test_search_returns_field_highlights() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let resource_id = create_note(
+        &pool,
+        user_id,
+        "Rust 异步编程指南",
+        Some("Notes about async Rust runtimes"),
+        "Tokio is the most popular runtime",
+    )
+    .await;
+
+    let response =
+        SearchService::search_resources(user_id, search_filters("Rust", marks(16)), &pool)
+            .await
+            .unwrap();
+    assert_eq!(response.items.len(), 1);
+
+    let highlights = response.highlights.unwrap();
+    let fields = &highlights[&resource_id];
+    assert_eq!(fields["title"], vec!["<mark>Rust</mark> 异步编程指南"]);
+    assert_eq!(
+        fields["description"],
+        vec!["Notes about async <mark>Rust</mark> runtimes"]
+    );
+    // 未命中的字段不返回
+    assert!(!fields.contains_key("content"));
+    assert!(!fields.contains_key("tags"));
+}
+
+#[tokio::test]
+async fn test_highlight_markers_and_snippet_length() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let long_content = format!(
+        "{} needle {}",
+        "before ".repeat(30).trim(),
+        "after ".repeat(30).trim()
+    );
+    let resource_id = create_note(&pool, user_id, "Haystack", None, &long_content).await;
+
+    let options = HighlightOptions {
+        start_marker: "[[".to_string(),
+        end_marker: "]]".to_string(),
+        snippet_tokens: 5,
+    };
+    let response =
+        SearchService::search_resources(user_id, search_filters("needle", Some(options)), &pool)
+            .await
+            .unwrap();
+
+    let snippet = &response.highlights.unwrap()[&resource_id]["content"][0];
+    assert!(snippet.contains("[[needle]]"));
+    assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+    assert_eq!(snippet.split_whitespace().count(), 5);
+
+    // 关闭高亮时不返回片段
+    let response = SearchService::search_resources(user_id, search_filters("needle", None), &pool)
+        .await
+        .unwrap();
+    assert_eq!(response.items.len(), 1);
+    assert!(response.highlights.is_none());
+}
+
+#[cfg(feature = "jieba")]
+#[tokio::test]
+async fn test_highlights_with_jieba_segmentation() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let resource_id = create_note(
+        &pool,
+        user_id,
+        "Linux内核开发",
+        Some("深入理解 Linux 内核的调度器"),
+        "",
+    )
+    .await;
+
+    let response =
+        SearchService::search_resources(user_id, search_filters("内核", marks(16)), &pool)
+            .await
+            .unwrap();
+
+    // 片段中的分词空格被还原为原文
+    let fields = &response.highlights.unwrap()[&resource_id];
+    assert_eq!(fields["title"], vec!["Linux<mark>内核</mark>开发"]);
+    assert_eq!(
+        fields["description"],
+        vec!["深入理解 Linux <mark>内核</mark>的调度器"]
+    );
+}
+
+#[tokio::test]
+async fn test_query_language_operators() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    create_note(&pool, user_id, "Rust async runtime", None, "").await;
+    create_note(&pool, user_id, "Rust unsafe code", None, "").await;
+    create_note(&pool, user_id, "Go concurrency", None, "").await;
+    create_note(&pool, user_id, "C++ templates", None, "").await;
+
+    assert_eq!(
+        search_titles(&pool, user_id, "rust -unsafe").await,
+        vec!["Rust async runtime"]
+    );
+    // AND 的优先级高于 OR
+    assert_eq!(
+        search_titles(&pool, user_id, "rust NOT unsafe OR go").await,
+        vec!["Go concurrency", "Rust async runtime"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "(rust OR go) AND -unsafe").await,
+        vec!["Go concurrency", "Rust async runtime"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "\"async runtime\"").await,
+        vec!["Rust async runtime"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "concur*").await,
+        vec!["Go concurrency"]
+    );
+    // FTS5 语法字符不会导致查询出错
+    assert_eq!(
+        search_titles(&pool, user_id, "c++ templates").await,
+        vec!["C++ templates"]
+    );
+
+    let error =
+        SearchService::search_resources(user_id, search_filters("rust \"async", None), &pool)
+            .await
+            .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Invalid search query: Unterminated quoted phrase at character 6"));
+}
+
+#[tokio::test]
+async fn test_query_language_filters() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let collection_id: i64 = sqlx::query_scalar(
+        "INSERT INTO collections (user_id, name) VALUES (?, 'Design') RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    create_link(
+        &pool,
+        user_id,
+        "Rust compiler",
+        "https://github.com/rust-lang/rust",
+        None,
+        true,
+    )
+    .await;
+    create_link(
+        &pool,
+        user_id,
+        "GitHub docs",
+        "https://docs.github.com:443/en",
+        Some(collection_id),
+        false,
+    )
+    .await;
+    create_link(
+        &pool,
+        user_id,
+        "Not GitHub",
+        "https://notgithub.com/",
+        Some(collection_id),
+        false,
+    )
+    .await;
+    create_note(&pool, user_id, "Rust notes", None, "").await;
+
+    assert_eq!(
+        search_titles(&pool, user_id, "domain:github.com").await,
+        vec!["GitHub docs", "Rust compiler"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "rust domain:github.com").await,
+        vec!["Rust compiler"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "is:favorite").await,
+        vec!["Rust compiler"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "collection:design github").await,
+        vec!["GitHub docs", "Not GitHub"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "rust type:note tag:reading").await,
+        vec!["Rust notes"]
+    );
+    assert_eq!(
+        search_titles(&pool, user_id, "tag:web before:2000-01-01").await,
+        Vec::<String>::new()
+    );
+
+    let error =
+        SearchService::search_resources(user_id, search_filters("collection:Missing", None), &pool)
+            .await
+            .unwrap_err();
+    assert!(error.to_string().contains("collection 'Missing' not found"));
+}
+
+#[tokio::test]
+async fn test_facets_cover_full_match_set() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let collection_id: i64 = sqlx::query_scalar(
+        "INSERT INTO collections (user_id, name) VALUES (?, 'Design') RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    create_link(
+        &pool,
+        user_id,
+        "Rust compiler",
+        "https://github.com/rust-lang/rust",
+        Some(collection_id),
+        true,
+    )
+    .await;
+    create_link(
+        &pool,
+        user_id,
+        "Rust docs",
+        "https://docs.github.com/rust",
+        Some(collection_id),
+        false,
+    )
+    .await;
+    create_link(
+        &pool,
+        user_id,
+        "Rust blog",
+        "https://blog.rust-lang.org/",
+        None,
+        false,
+    )
+    .await;
+    create_note(&pool, user_id, "Rust notes", None, "").await;
+    create_note(&pool, user_id, "Go notes", None, "").await;
+    sqlx::query(
+        "UPDATE resources SET created_at = 1735689600, is_read = 1 WHERE title = 'Rust notes'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let mut filters = search_filters("rust", None);
+    filters.pagination = PaginationParams::from_page(1, 1);
+    filters.facet_limit = Some(1);
+    let response = SearchService::search_resources(user_id, filters, &pool)
+        .await
+        .unwrap();
+
+    // 分面统计全部 4 个匹配结果, 而不只是当前页
+    assert_eq!(response.items.len(), 1);
+    let facets = response.facets.unwrap();
+    assert_eq!(facet_counts(&facets.types), vec![("link", 3), ("note", 1)]);
+    // 标签和域名只返回数量最多的 facet_limit 项
+    assert_eq!(facet_counts(&facets.tags), vec![("web", 3)]);
+    assert_eq!(
+        facet_counts(&facets.domains),
+        vec![("blog.rust-lang.org", 1)]
+    );
+    assert_eq!(facets.collections.len(), 1);
+    assert_eq!(facets.collections[0].id, collection_id);
+    assert_eq!(facets.collections[0].name, "Design");
+    assert_eq!(facets.collections[0].count, 2);
+    assert_eq!(facets.flags.favorite, 1);
+    assert_eq!(facets.flags.read, 1);
+    assert_eq!(facets.flags.unread, 3);
+    assert_eq!(facets.flags.archived, 0);
+    assert_eq!(facets.created_at.len(), 2);
+    assert_eq!(facets.created_at[1].value, "2025-01");
+    assert_eq!(facets.created_at[1].count, 1);
+
+    // 分面与列表使用相同的筛选条件
+    let mut filters = search_filters("rust domain:github.com", None);
+    filters.facet_limit = Some(10);
+    let facets = SearchService::search_resources(user_id, filters, &pool)
+        .await
+        .unwrap()
+        .facets
+        .unwrap();
+    assert_eq!(
+        facet_counts(&facets.domains),
+        vec![("docs.github.com", 1), ("github.com", 1)]
+    );
+    assert_eq!(facet_counts(&facets.types), vec![("link", 2)]);
+}
+
+#[tokio::test]
+async fn test_request_filters_match_listing_filters() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    create_link(
+        &pool,
+        user_id,
+        "Rust compiler",
+        "https://github.com/rust-lang/rust",
+        None,
+        true,
+    )
+    .await;
+    create_link(
+        &pool,
+        user_id,
+        "Rust blog",
+        "https://blog.rust-lang.org/",
+        None,
+        false,
+    )
+    .await;
+    create_note(&pool, user_id, "Rust notes", None, "").await;
+    sqlx::query("UPDATE resources SET is_archived = 1 WHERE title = 'Rust blog'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let search = |filters: FilterCriteria| {
+        let pool = pool.clone();
+        async move {
+            let mut request = search_filters("rust", None);
+            request.filters = filters;
+            let mut titles: Vec<String> = SearchService::search_resources(user_id, request, &pool)
+                .await
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|item| item.resource.title)
+                .collect();
+            titles.sort();
+            titles
+        }
+    };
+
+    assert_eq!(
+        search(FilterCriteria {
+            resource_types: vec!["link".to_string(), "note".to_string()],
+            ..Default::default()
+        })
+        .await,
+        vec!["Rust blog", "Rust compiler", "Rust notes"]
+    );
+    assert_eq!(
+        search(FilterCriteria {
+            is_favorite: Some(true),
+            ..Default::default()
+        })
+        .await,
+        vec!["Rust compiler"]
+    );
+    assert_eq!(
+        search(FilterCriteria {
+            is_archived: Some(false),
+            exclude_tags: vec!["reading".to_string()],
+            ..Default::default()
+        })
+        .await,
+        vec!["Rust compiler"]
+    );
+
+    // 查询语言中的类型条件优先于请求参数
+    let mut request = search_filters("rust type:note", None);
+    request.filters.resource_types = vec!["link".to_string()];
+    let response = SearchService::search_resources(user_id, request, &pool)
+        .await
+        .unwrap();
+    assert_eq!(response.items.len(), 1);
+    assert_eq!(response.items[0].resource.title, "Rust notes");
+}
+
+#[tokio::test]
+async fn test_did_you_mean_uses_own_vocabulary() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+    let other_user_id: i64 = sqlx::query_scalar(
+        "INSERT INTO users (username, email, password_hash) VALUES ('other', 'other@example.com', 'x') RETURNING id",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    create_note(
+        &pool,
+        user_id,
+        "Rust async runtime",
+        None,
+        "futures and executors",
+    )
+    .await;
+    create_note(&pool, other_user_id, "Tokio internals", None, "scheduler").await;
+
+    let response =
+        SearchService::search_resources(user_id, search_filters("rust asnyc", None), &pool)
+            .await
+            .unwrap();
+    assert!(response.items.is_empty());
+    assert_eq!(response.did_you_mean.as_deref(), Some("rust async"));
+
+    // 只在其他用户资源中出现的词不作为纠正
+    let response = SearchService::search_resources(user_id, search_filters("tokoi", None), &pool)
+        .await
+        .unwrap();
+    assert_eq!(response.did_you_mean, None);
+
+    // 其他用户资源中更多更常见的相近词不会挤掉自己的词
+    for suffix in 'a'..='k' {
+        let title = format!("asny{}", suffix);
+        for _ in 0..2 {
+            create_note(&pool, other_user_id, &title, None, "").await;
+        }
+    }
+    let response =
+        SearchService::search_resources(user_id, search_filters("rust asnyc", None), &pool)
+            .await
+            .unwrap();
+    assert_eq!(response.did_you_mean.as_deref(), Some("rust async"));
+
+    // 有结果时不纠正
+    let response =
+        SearchService::search_resources(user_id, search_filters("rust async", None), &pool)
+            .await
+            .unwrap();
+    assert_eq!(response.items.len(), 1);
+    assert_eq!(response.did_you_mean, None);
+}
+
+#[tokio::test]
+async fn test_suggestions_complete_and_correct_terms() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    create_note(
+        &pool,
+        user_id,
+        "Rust async runtime",
+        None,
+        "asynchronous io",
+    )
+    .await;
+    create_note(&pool, user_id, "Async patterns", None, "").await;
+
+    let suggestions = SearchService::get_search_suggestions(user_id, "rust asy", None, &pool)
+        .await
+        .unwrap();
+    let terms: Vec<(&str, i64)> = suggestions
+        .iter()
+        .filter(|suggestion| suggestion.suggestion_type == "term")
+        .map(|suggestion| (suggestion.text.as_str(), suggestion.count))
+        .collect();
+    // 出现在更多资源中的词排在前面
+    assert_eq!(terms, vec![("rust async", 2), ("rust asynchronous", 1)]);
+
+    let suggestions = SearchService::get_search_suggestions(user_id, "runtmie", None, &pool)
+        .await
+        .unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].text, "runtime");
+    assert_eq!(suggestions[0].suggestion_type, "correction");
+
+    let suggestions = SearchService::get_search_suggestions(user_id, "Async", None, &pool)
+        .await
+        .unwrap();
+    // 标题和索引词文本相同时 (忽略大小写) 只保留一条
+    assert!(suggestions
+        .iter()
+        .any(|suggestion| suggestion.text == "Async patterns"));
+    assert_eq!(
+        suggestions
+            .iter()
+            .filter(|suggestion| suggestion.text.eq_ignore_ascii_case("async"))
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_title_match_outranks_long_content_match() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let body = "tokio ".repeat(20) + &"scheduler notes ".repeat(200);
+    create_note(&pool, user_id, "Reading list", None, &body).await;
+    let title_id = create_note(&pool, user_id, "Tokio internals", None, "short").await;
+
+    let filters = SearchFilters {
+        include_scores: true,
+        ..search_filters("tokio", None)
+    };
+    let response = SearchService::search_resources(user_id, filters, &pool)
+        .await
+        .unwrap();
+    assert_eq!(response.items.len(), 2);
+    assert_eq!(response.items[0].resource.id, title_id);
+
+    let scores = response.scores.unwrap();
+    let first = scores[&response.items[0].resource.id];
+    let second = scores[&response.items[1].resource.id];
+    assert!(first.score > second.score);
+    assert!(first.text_score > 0.0);
+    // 新建资源取得全部新近度加成
+    assert!((first.boost - 1.1).abs() < 1e-6);
+
+    // 正文权重高于标题时长正文中的多次命中排在前面
+    let filters = SearchFilters {
+        ranking: RankingConfig {
+            title_weight: 1.0,
+            content_weight: 2.0,
+            ..Default::default()
+        },
+        ..search_filters("tokio", None)
+    };
+    let response = SearchService::search_resources(user_id, filters, &pool)
+        .await
+        .unwrap();
+    assert_ne!(response.items[0].resource.id, title_id);
+    assert!(response.scores.is_none());
+}
+
+#[tokio::test]
+async fn test_favorite_and_visits_boost_rank() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "searcher").await;
+
+    let plain_id = create_link(&pool, user_id, "Axum guide", "https://a.dev/1", None, false).await;
+    let favorite_id =
+        create_link(&pool, user_id, "Axum guide", "https://a.dev/2", None, true).await;
+    let visited_id =
+        create_link(&pool, user_id, "Axum guide", "https://a.dev/3", None, false).await;
+    sqlx::query("UPDATE resources SET visit_count = 500 WHERE id = ?")
+        .bind(visited_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let ids = |ranking: RankingConfig| {
+        let pool = pool.clone();
+        async move {
+            let filters = SearchFilters {
+                ranking,
+                ..search_filters("axum", None)
+            };
+            SearchService::search_resources(user_id, filters, &pool)
+                .await
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|item| item.resource.id)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // 收藏加成 0.2 大于访问加成上限 0.1
+    assert_eq!(
+        ids(RankingConfig::default()).await,
+        vec![favorite_id, visited_id, plain_id]
+    );
+
+    let ranking = RankingConfig {
+        favorite_boost: 0.0,
+        ..Default::default()
+    };
+    assert_eq!(ids(ranking).await[0], visited_id);
+}
@@ -0,0 +1,348 @@
+use chrono::Utc;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::config::search::SemanticConfig;
+use crate::models::{
+    ResourceType, SearchFilters, SearchPagination, SearchResponse, SemanticMatch,
+    SemanticSearchQuery, SemanticSearchResponse,
+};
+use crate::services::embedding::{cosine_similarity, decode_vector, encode_vector, Embedder};
+use crate::services::query_helper::{self, QueryOptions};
+use crate::services::search_history_service::SearchHistoryService;
+use crate::services::search_service::{SearchService, MIN_SEARCH_QUERY_LENGTH};
+use crate::utils::error::{AppError, AppResult};
+
+const DEFAULT_SEMANTIC_LIMIT: i64 = 20;
+const MAX_SEMANTIC_LIMIT: i64 = 100;
+
+/// 语义搜索 (embeddings feature)
+///
+/// 向量在搜索时按需计算: 新增、更新过或模型变化的资源先重新计算向量再参与检索,
+/// 相似度在内存中逐个计算, 适合单个用户规模的资源数量。
+pub struct SemanticSearchService;
+
+impl SemanticSearchService {
+    /// 为用户新增、更新过或模型变化的资源计算向量, 返回计算的数量
+    pub async fn sync_embeddings(
+        user_id: i64,
+        embedder: &dyn Embedder,
+        db_pool: &SqlitePool,
+    ) -> AppResult<u64> {
+        // 同一秒内的更新无法区分先后, 使用 >= 宁可多算一次
+        let rows = sqlx::query(
+            r#"
+            SELECT r.id, r.title, r.description, r.content
+            FROM resources r
+            LEFT JOIN resource_embeddings e ON e.resource_id = r.id
+            WHERE r.user_id = $1
+              AND (e.resource_id IS NULL
+                   OR e.model <> $2
+                   OR e.dimensions <> $3
+                   OR r.updated_at >= e.embedded_at)
+            "#,
+        )
+        .bind(user_id)
+        .bind(embedder.model())
+        .bind(embedder.dimensions() as i64)
+        .fetch_all(db_pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let embedded_at = Utc::now().timestamp();
+        let mut tx = db_pool.begin().await?;
+        for row in &rows {
+            let text = resource_text(row.get("title"), row.get("description"), row.get("content"));
+            let vector = embedder.embed(&text);
+
+            sqlx::query(
+                r#"
+                INSERT INTO resource_embeddings (
+                    resource_id, user_id, model, dimensions, vector, embedded_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT(resource_id) DO UPDATE SET
+                    model = excluded.model,
+                    dimensions = excluded.dimensions,
+                    vector = excluded.vector,
+                    embedded_at = excluded.embedded_at
+                "#,
+            )
+            .bind(row.get::<i64, _>("id"))
+            .bind(user_id)
+            .bind(embedder.model())
+            .bind(vector.len() as i64)
+            .bind(encode_vector(&vector))
+            .bind(embedded_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(rows.len() as u64)
+    }
+
+    /// 按与查询的相似度返回资源
+    pub async fn semantic_search(
+        user_id: i64,
+        query: SemanticSearchQuery,
+        embedder: &dyn Embedder,
+        config: &SemanticConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<SemanticSearchResponse> {
+        let text = query.q.trim();
+        if text.len() < MIN_SEARCH_QUERY_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Search query must be at least {} characters",
+                MIN_SEARCH_QUERY_LENGTH
+            )));
+        }
+        let resource_types = match query.resource_type.as_deref() {
+            Some(resource_type) => vec![ResourceType::from(resource_type)
+                .map_err(AppError::BadRequest)?
+                .as_str()
+                .to_string()],
+            None => Vec::new(),
+        };
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_SEMANTIC_LIMIT)
+            .clamp(1, MAX_SEMANTIC_LIMIT);
+        let min_similarity = query.min_similarity.unwrap_or(config.min_similarity);
+
+        let start = Instant::now();
+        Self::sync_embeddings(user_id, embedder, db_pool).await?;
+
+        let options = QueryOptions {
+            user_id,
+            collection_id: query.collection_id,
+            resource_types: &resource_types,
+            ..Default::default()
+        };
+        let ranked: Vec<(i64, f64)> =
+            Self::rank_by_similarity(&embedder.embed(text), &options, embedder, db_pool)
+                .await?
+                .into_iter()
+                .filter(|(_, similarity)| *similarity >= min_similarity)
+                .take(limit as usize)
+                .collect();
+
+        let ids: Vec<i64> = ranked.iter().map(|(id, _)| *id).collect();
+        let resources = query_helper::fetch_resources_in_order(db_pool, user_id, &ids).await?;
+        let items = resources
+            .into_iter()
+            .zip(ranked)
+            .map(|(resource, (_, similarity))| SemanticMatch {
+                resource,
+                similarity,
+            })
+            .collect();
+
+        Ok(SemanticSearchResponse {
+            items,
+            model: embedder.model().to_string(),
+            search_time: start.elapsed().as_secs_f64(),
+        })
+    }
+
+    /// 混合搜索: 全文搜索和语义搜索各取候选, 按倒数排名融合 (RRF) 排序
+    ///
+    /// 两路使用相同的过滤条件; 融合后的结果只支持页码分页,
+    /// 高亮片段只为全文匹配的结果生成, 不统计分面。查询只包含过滤条件时与全文搜索相同。
+    pub async fn hybrid_search(
+        user_id: i64,
+        filters: SearchFilters,
+        embedder: &dyn Embedder,
+        config: &SemanticConfig,
+        db_pool: &SqlitePool,
+    ) -> AppResult<SearchResponse> {
+        if filters.cursor.is_some() {
+            return Err(AppError::BadRequest(
+                "Cursor pagination is not supported in hybrid mode".to_string(),
+            ));
+        }
+        if filters.query.trim().len() < MIN_SEARCH_QUERY_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Search query must be at least {} characters",
+                MIN_SEARCH_QUERY_LENGTH
+            )));
+        }
+
+        let start = Instant::now();
+        let resolved =
+            SearchService::resolve_query(user_id, &filters.query, &filters.filters, db_pool)
+                .await?;
+        let Some(fts_query) = resolved.parsed.fts_query.as_deref() else {
+            return SearchService::search_resources(user_id, filters, db_pool).await;
+        };
+
+        let keyword_options = QueryOptions {
+            search_type: Some(&filters.search_type),
+            limit: config.hybrid_candidates,
+            sort_by: "rank",
+            ranking: Some(&filters.ranking),
+            ..resolved.query_options(user_id)
+        };
+        let keyword_ids = query_helper::fetch_resource_ids(db_pool, &keyword_options).await?;
+
+        // 语义部分只使用查询中的词语, 过滤条件与全文搜索相同
+        let text = semantic_text(&filters.query);
+        let semantic_ids: Vec<i64> = if text.is_empty() {
+            Vec::new()
+        } else {
+            Self::sync_embeddings(user_id, embedder, db_pool).await?;
+            let semantic_options = QueryOptions {
+                search_term: None,
+                ..resolved.query_options(user_id)
+            };
+            Self::rank_by_similarity(&embedder.embed(&text), &semantic_options, embedder, db_pool)
+                .await?
+                .into_iter()
+                .filter(|(_, similarity)| *similarity >= config.min_similarity)
+                .take(config.hybrid_candidates.max(0) as usize)
+                .map(|(id, _)| id)
+                .collect()
+        };
+
+        let fused = reciprocal_rank_fusion(&[&keyword_ids, &semantic_ids], config.rrf_k);
+        let page_ids: Vec<i64> = fused
+            .iter()
+            .skip(filters.pagination.offset.max(0) as usize)
+            .take(filters.pagination.limit as usize)
+            .map(|(id, _)| *id)
+            .collect();
+        let resources = query_helper::fetch_resources_in_order(db_pool, user_id, &page_ids).await?;
+
+        let highlights = match &filters.highlight {
+            Some(highlight) if !page_ids.is_empty() => Some(
+                SearchService::fetch_highlights(
+                    fts_query,
+                    &filters.search_type,
+                    &page_ids,
+                    highlight,
+                    db_pool,
+                )
+                .await?,
+            ),
+            _ => None,
+        };
+
+        let total = fused.len() as i64;
+        let total_pages = (total + filters.pagination.limit - 1) / filters.pagination.limit;
+        let page = filters.pagination.page();
+        let response = SearchResponse {
+            items: resources,
+            pagination: SearchPagination {
+                page: Some(page),
+                limit: filters.pagination.limit,
+                total,
+                total_pages,
+                has_next: page < total_pages,
+                has_prev: page > 1,
+                next_cursor: None,
+                prev_cursor: None,
+            },
+            search_time: start.elapsed().as_secs_f64(),
+            highlights,
+            facets: None,
+            did_you_mean: None,
+            scores: None,
+        };
+
+        // 只记录第一页, 记录失败不影响搜索结果
+        if filters.pagination.offset == 0 {
+            if let Err(e) =
+                SearchHistoryService::record_search(user_id, &filters, &response, db_pool).await
+            {
+                tracing::warn!("Failed to record search history: {}", e);
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// 满足筛选条件且已计算向量的资源, 按与查询向量的相似度倒序
+    ///
+    /// options 不能包含全文搜索条件
+    async fn rank_by_similarity(
+        query_vector: &[f32],
+        options: &QueryOptions<'_>,
+        embedder: &dyn Embedder,
+        db_pool: &SqlitePool,
+    ) -> AppResult<Vec<(i64, f64)>> {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT e.resource_id, e.vector
+             FROM resource_embeddings e
+             JOIN resources r ON r.id = e.resource_id",
+        );
+        query_helper::push_conditions(&mut query_builder, options);
+        query_builder.push(" AND e.model = ");
+        query_builder.push_bind(embedder.model());
+
+        let rows = query_builder.build().fetch_all(db_pool).await?;
+        let mut ranked: Vec<(i64, f64)> = rows
+            .iter()
+            .map(|row| {
+                let vector = decode_vector(row.get::<&[u8], _>("vector"));
+                (
+                    row.get("resource_id"),
+                    f64::from(cosine_similarity(query_vector, &vector)),
+                )
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+
+        Ok(ranked)
+    }
+}
+
+/// 倒数排名融合: 在每个排名中排第 n 位 (从 1 开始) 的结果得到 1 / (k + n), 按总分倒序
+pub fn reciprocal_rank_fusion(rankings: &[&[i64]], k: f64) -> Vec<(i64, f64)> {
+    let mut scores: HashMap<i64, f64> = HashMap::new();
+    for ranking in rankings {
+        for (index, id) in ranking.iter().enumerate() {
+            *scores.entry(*id).or_default() += 1.0 / (k + index as f64 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(i64, f64)> = scores.into_iter().collect();
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    fused
+}
+
+/// 计算资源向量使用的文本
+fn resource_text(title: String, description: Option<String>, content: Option<String>) -> String {
+    [Some(title), description, content]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 查询中用于计算语义向量的文本: 去掉 key:value 过滤条件、布尔运算符和排除的词
+fn semantic_text(query: &str) -> String {
+    let mut words = Vec::new();
+    let mut excluded = false;
+    for word in query.split_whitespace() {
+        match word {
+            "AND" | "OR" => continue,
+            "NOT" => {
+                excluded = true;
+                continue;
+            }
+            _ => {}
+        }
+        if std::mem::take(&mut excluded) || word.starts_with('-') || word.contains(':') {
+            continue;
+        }
+        let word = word.trim_matches(|c| matches!(c, '"' | '(' | ')' | '*'));
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words.join(" ")
+}
@@ -0,0 +1,221 @@
+use sqlx::SqlitePool;
+
+use crate::config::search::SemanticConfig;
+use crate::config::RankingConfig;
+use crate::models::{
+    CreateResource, FilterCriteria, PaginationParams, SearchFilters, SearchMode, SearchType,
+    SemanticSearchQuery,
+};
+use crate::services::embedding::{Embedder, HashingEmbedder};
+use crate::services::indexer_service::IndexerService;
+use crate::services::resource_service::ResourceService;
+use crate::services::semantic_search_service::{reciprocal_rank_fusion, SemanticSearchService};
+use crate::services::test_support::{create_test_pool, create_test_user};
+use crate::utils::error::AppError;
+
+async fn create_note(pool: &SqlitePool, user_id: i64, title: &str, content: &str) -> i64 {
+    let resource_data = CreateResource {
+        title: title.to_string(),
+        url: None,
+        description: None,
+        collection_id: None,
+        tags: None,
+        is_favorite: None,
+        is_private: None,
+        resource_type: "note".to_string(),
+        content: Some(content.to_string()),
+        source: None,
+        mime_type: None,
+    };
+
+    let resource_id = ResourceService::create_resource(user_id, resource_data, pool)
+        .await
+        .unwrap()
+        .id;
+
+    // create_resource 在后台任务中建立索引, 这里同步索引避免竞争
+    IndexerService::index_resource_with_pool(pool, resource_id, user_id)
+        .await
+        .unwrap();
+    resource_id
+}
+
+fn semantic_query(q: &str) -> SemanticSearchQuery {
+    SemanticSearchQuery {
+        q: q.to_string(),
+        collection_id: None,
+        resource_type: None,
+        limit: None,
+        min_similarity: None,
+    }
+}
+
+fn hybrid_filters(query: &str) -> SearchFilters {
+    SearchFilters {
+        query: query.to_string(),
+        search_type: SearchType::All,
+        mode: SearchMode::Hybrid,
+        filters: FilterCriteria::default(),
+        pagination: PaginationParams::from_page(1, 20),
+        highlight: None,
+        facet_limit: None,
+        cursor: None,
+        ranking: RankingConfig::default(),
+        include_scores: false,
+    }
+}
+
+#[test]
+fn test_reciprocal_rank_fusion() {
+    let fused = reciprocal_rank_fusion(&[&[1, 2, 3], &[3, 4]], 60.0);
+    let ids: Vec<i64> = fused.iter().map(|(id, _)| *id).collect();
+    // 同时出现在两个排名中的结果排在最前
+    assert_eq!(ids, vec![3, 1, 4, 2]);
+    assert!((fused[0].1 - (1.0 / 63.0 + 1.0 / 61.0)).abs() < 1e-12);
+}
+
+#[tokio::test]
+async fn test_embeddings_follow_resource_updates() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "semantic").await;
+    let embedder = HashingEmbedder::new(64);
+
+    let resource_id = create_note(&pool, user_id, "Sourdough", "bread baking notes").await;
+    create_note(&pool, user_id, "Tokio", "async runtime").await;
+
+    assert_eq!(
+        SemanticSearchService::sync_embeddings(user_id, &embedder, &pool)
+            .await
+            .unwrap(),
+        2
+    );
+
+    // 向量晚于资源更新时不再重新计算
+    sqlx::query("UPDATE resource_embeddings SET embedded_at = embedded_at + 10")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        SemanticSearchService::sync_embeddings(user_id, &embedder, &pool)
+            .await
+            .unwrap(),
+        0
+    );
+
+    sqlx::query(
+        "UPDATE resources SET content = 'rust async executors', updated_at = updated_at + 20
+         WHERE id = ?",
+    )
+    .bind(resource_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        SemanticSearchService::sync_embeddings(user_id, &embedder, &pool)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // 维度变化后全部重新计算
+    let embedder = HashingEmbedder::new(32);
+    assert_eq!(
+        SemanticSearchService::sync_embeddings(user_id, &embedder, &pool)
+            .await
+            .unwrap(),
+        2
+    );
+    let dimensions: i64 =
+        sqlx::query_scalar("SELECT dimensions FROM resource_embeddings WHERE resource_id = ?")
+            .bind(resource_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(dimensions, embedder.dimensions() as i64);
+}
+
+#[tokio::test]
+async fn test_semantic_search_orders_by_similarity() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "semantic").await;
+    let embedder = HashingEmbedder::new(256);
+    let config = SemanticConfig::default();
+
+    let runtime_id = create_note(&pool, user_id, "Tokio", "an asynchronous runtime").await;
+    create_note(&pool, user_id, "Sourdough", "bread baking at home").await;
+
+    // 查询词的词形与资源不同, 全文搜索无法匹配
+    let response = SemanticSearchService::semantic_search(
+        user_id,
+        semantic_query("runtimes for async"),
+        &embedder,
+        &config,
+        &pool,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.items[0].resource.resource.id, runtime_id);
+    assert!(response
+        .items
+        .windows(2)
+        .all(|pair| pair[0].similarity >= pair[1].similarity));
+    assert!(response
+        .items
+        .iter()
+        .all(|item| item.similarity >= config.min_similarity));
+    assert_eq!(response.model, embedder.model());
+
+    let query = SemanticSearchQuery {
+        resource_type: Some("link".to_string()),
+        ..semantic_query("runtimes for async")
+    };
+    let response =
+        SemanticSearchService::semantic_search(user_id, query, &embedder, &config, &pool)
+            .await
+            .unwrap();
+    assert!(response.items.is_empty());
+
+    let query = SemanticSearchQuery {
+        resource_type: Some("video".to_string()),
+        ..semantic_query("runtimes for async")
+    };
+    let result =
+        SemanticSearchService::semantic_search(user_id, query, &embedder, &config, &pool).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn test_hybrid_search_fuses_keyword_and_semantic_matches() {
+    let pool = create_test_pool().await;
+    let user_id = create_test_user(&pool, "semantic").await;
+    let embedder = HashingEmbedder::new(256);
+    let config = SemanticConfig::default();
+
+    let keyword_id = create_note(&pool, user_id, "Runtime notes", "runtime internals").await;
+    let semantic_id = create_note(&pool, user_id, "Executors", "runtimes and executors").await;
+    create_note(&pool, user_id, "Sourdough", "bread baking at home").await;
+
+    let response = SemanticSearchService::hybrid_search(
+        user_id,
+        hybrid_filters("runtime -bread"),
+        &embedder,
+        &config,
+        &pool,
+    )
+    .await
+    .unwrap();
+    let ids: Vec<i64> = response.items.iter().map(|item| item.resource.id).collect();
+    // 全文和语义都匹配的结果排在前面, 只有语义匹配的结果也会返回
+    assert_eq!(ids[0], keyword_id);
+    assert!(ids.contains(&semantic_id));
+    assert_eq!(response.pagination.total, ids.len() as i64);
+    assert!(response.facets.is_none());
+
+    let filters = SearchFilters {
+        cursor: Some(String::new()),
+        ..hybrid_filters("runtime")
+    };
+    let result =
+        SemanticSearchService::hybrid_search(user_id, filters, &embedder, &config, &pool).await;
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+}
@@ -172,7 +172,7 @@ impl StatsService {
 
         // Convert to sorted vector
         let mut result: Vec<RecentActivityEntry> = activities.into_values().collect();
-        result.sort_by(|a, b| b.date.cmp(&a.date));
+        result.sort_by_key(|entry| std::cmp::Reverse(entry.date));
         result.truncate(30);
         Ok(result)
     }
@@ -0,0 +1,140 @@
+//! 本地文件系统存储
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::{ByteRange, Storage};
+use crate::utils::error::{AppError, AppResult};
+
+/// 对象保存为 `{root}/{key}`
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 键只能是相对路径, 防止越出根目录
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        let is_safe = !key.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_safe {
+            return Err(AppError::Internal(format!("Invalid storage key: {}", key)));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+fn io_error(action: &str, e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AppError::NotFound("Stored file not found".to_string())
+    } else {
+        AppError::Internal(format!("Failed to {} stored file: {}", action, e))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error("create directory for", e))?;
+        }
+
+        // 先写临时文件再重命名, 读取方不会看到写了一半的文件
+        let temp_path = path.with_extension("part");
+        tokio::fs::write(&temp_path, data)
+            .await
+            .map_err(|e| io_error("write", e))?;
+        tokio::fs::rename(&temp_path, &path)
+            .await
+            .map_err(|e| io_error("write", e))
+    }
+
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> AppResult<Vec<u8>> {
+        let path = self.path(key)?;
+        let Some(range) = range else {
+            return tokio::fs::read(&path)
+                .await
+                .map_err(|e| io_error("read", e));
+        };
+
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| io_error("read", e))?;
+        file.seek(SeekFrom::Start(range.start))
+            .await
+            .map_err(|e| io_error("read", e))?;
+
+        let mut data = Vec::with_capacity(range.length() as usize);
+        file.take(range.length())
+            .read_to_end(&mut data)
+            .await
+            .map_err(|e| io_error("read", e))?;
+
+        Ok(data)
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("delete", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        storage
+            .put("1/object", b"0123456789".to_vec(), "text/plain")
+            .await
+            .unwrap();
+        assert_eq!(storage.get("1/object", None).await.unwrap(), b"0123456789");
+        assert_eq!(
+            storage
+                .get("1/object", Some(ByteRange { start: 2, end: 4 }))
+                .await
+                .unwrap(),
+            b"234"
+        );
+
+        storage.delete("1/object").await.unwrap();
+        storage.delete("1/object").await.unwrap();
+        assert!(matches!(
+            storage.get("1/object", None).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_local_storage_rejects_escaping_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        for key in ["../outside", "/etc/passwd", "a/../../b", ""] {
+            assert!(storage.put(key, Vec::new(), "text/plain").await.is_err());
+        }
+    }
+}
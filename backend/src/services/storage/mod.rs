@@ -0,0 +1,138 @@
+//! 上传文件存储后端
+//!
+//! 每种后端实现 `Storage`, 只负责按键读写对象;
+//! 文件元数据 (大小、校验和、内容类型) 由 FileService 记录在 resource_files 表中。
+//! 对象键由 FileService 生成 (`{user_id}/{uuid}`), 后端不需要处理任意字符。
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::config::{StorageBackend, StorageConfig};
+use crate::utils::error::AppResult;
+
+pub mod local;
+pub mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// 字节范围, 首尾均包含在内
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// 范围内的字节数
+    pub fn length(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// 文件存储后端
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// 后端名称, 与 StorageBackend 的序列化值一致
+    fn name(&self) -> &'static str;
+
+    /// 写入对象, 已存在时覆盖
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> AppResult<()>;
+
+    /// 读取对象, 指定范围时只返回该范围内的字节; 对象不存在时返回 NotFound
+    async fn get(&self, key: &str, range: Option<ByteRange>) -> AppResult<Vec<u8>>;
+
+    /// 删除对象, 对象不存在时视为成功
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
+
+/// 根据配置创建存储后端
+pub fn build_storage(config: &StorageConfig) -> AppResult<Arc<dyn Storage>> {
+    let storage: Arc<dyn Storage> = match config.backend {
+        StorageBackend::Local => Arc::new(LocalStorage::new(&config.local_dir)),
+        StorageBackend::S3 => Arc::new(S3Storage::new(config.s3.clone())?),
+    };
+
+    Ok(storage)
+}
+
+/// 请求的范围超出文件大小, 应响应 416
+#[derive(Debug, PartialEq, Eq)]
+pub struct RangeNotSatisfiable;
+
+/// 解析 Range 请求头 (只支持单个范围)
+///
+/// 返回 Ok(None) 表示请求头格式不支持, 按完整内容响应
+pub fn parse_range(header: &str, size: u64) -> Result<Option<ByteRange>, RangeNotSatisfiable> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // bytes=-N: 最后 N 个字节
+        let Ok(suffix) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix == 0 || size == 0 {
+            return Err(RangeNotSatisfiable);
+        }
+        ByteRange {
+            start: size.saturating_sub(suffix),
+            end: size - 1,
+        }
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if end.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            let Ok(end) = end.parse::<u64>() else {
+                return Ok(None);
+            };
+            if end < start {
+                return Ok(None);
+            }
+            end.min(size.saturating_sub(1))
+        };
+        if start >= size {
+            return Err(RangeNotSatisfiable);
+        }
+        ByteRange { start, end }
+    };
+
+    Ok(Some(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        let range = |start, end| Ok(Some(ByteRange { start, end }));
+
+        assert_eq!(parse_range("bytes=0-9", 100), range(0, 9));
+        assert_eq!(parse_range("bytes=90-", 100), range(90, 99));
+        assert_eq!(parse_range("bytes=90-200", 100), range(90, 99));
+        assert_eq!(parse_range("bytes=-10", 100), range(90, 99));
+        assert_eq!(parse_range("bytes=-200", 100), range(0, 99));
+        assert_eq!(parse_range("bytes=0-9", 100).unwrap().unwrap().length(), 10);
+
+        assert_eq!(parse_range("bytes=100-", 100), Err(RangeNotSatisfiable));
+        assert_eq!(parse_range("bytes=-0", 100), Err(RangeNotSatisfiable));
+        assert_eq!(parse_range("bytes=0-", 0), Err(RangeNotSatisfiable));
+
+        assert_eq!(parse_range("items=0-9", 100), Ok(None));
+        assert_eq!(parse_range("bytes=0-1,5-9", 100), Ok(None));
+        assert_eq!(parse_range("bytes=9-0", 100), Ok(None));
+        assert_eq!(parse_range("bytes=a-b", 100), Ok(None));
+    }
+}
@@ -0,0 +1,77 @@
+//! 服务测试共用的辅助函数
+use std::sync::Arc;
+
+use axum::Router;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+
+use crate::config::{ArchiveConfig, FetcherConfig, JobsConfig, StorageConfig};
+use crate::services::job_service::JobWorker;
+use crate::services::storage::{build_storage, Storage};
+
+/// 创建已执行迁移的内存数据库
+pub async fn create_test_pool() -> SqlitePool {
+    // `sqlite::memory:` 的每个连接都会打开一个独立的空数据库,
+    // 连接池只保留一个连接, 迁移、测试数据和后台任务才会落在同一个数据库中
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+/// 创建测试用户, 邮箱为 `<username>@example.com`
+pub async fn create_test_user(pool: &SqlitePool, username: &str) -> i64 {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO users (username, email, password_hash)
+        VALUES ($1, $1 || '@example.com', 'hashed_password')
+        RETURNING id
+        "#,
+    )
+    .bind(username)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+/// 在本地随机端口启动模拟网站, 返回其根地址
+pub async fn spawn_stub_server(app: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
+
+/// 按默认配置创建的存储后端
+pub fn default_storage() -> Arc<dyn Storage> {
+    build_storage(&StorageConfig::default()).unwrap()
+}
+
+/// 创建可以访问本地模拟网站的后台任务 worker, 失败的任务立即重试
+pub fn test_worker(
+    pool: &SqlitePool,
+    max_attempts: i64,
+    archive_config: ArchiveConfig,
+    storage: Arc<dyn Storage>,
+) -> JobWorker {
+    JobWorker::new(
+        pool.clone(),
+        JobsConfig {
+            max_attempts,
+            retry_delay_secs: 0,
+            ..Default::default()
+        },
+        FetcherConfig {
+            allow_private_networks: true,
+            ..Default::default()
+        },
+        archive_config,
+        storage,
+    )
+    .unwrap()
+}
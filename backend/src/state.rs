@@ -1,20 +1,54 @@
+use std::sync::Arc;
+
 use axum::extract::FromRef;
 use axum_jwt_auth::Decoder;
 use sqlx::SqlitePool;
 
+use crate::config::{AuthConfig, SearchConfig};
+#[cfg(feature = "embeddings")]
+use crate::services::embedding::{build_embedder, Embedder};
+use crate::services::mailer::Mailer;
+use crate::services::storage::Storage;
 use crate::utils::jwt::JwtClaims;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: SqlitePool,
     pub jwt_decoder: Decoder<JwtClaims>,
+    pub storage: Arc<dyn Storage>,
+    /// 单个上传文件的大小上限 (字节)
+    pub max_upload_bytes: usize,
+    /// 搜索高亮等默认参数
+    pub search_config: SearchConfig,
+    /// 重置密码链接有效期、前端地址等认证配置
+    pub auth_config: AuthConfig,
+    /// 通知邮件的发送后端
+    pub mailer: Arc<dyn Mailer>,
+    /// 语义搜索使用的向量化模型
+    #[cfg(feature = "embeddings")]
+    pub embedder: Arc<dyn Embedder>,
 }
 
 impl AppState {
-    pub fn new(db_pool: SqlitePool, jwt_decoder: Decoder<JwtClaims>) -> Self {
+    pub fn new(
+        db_pool: SqlitePool,
+        jwt_decoder: Decoder<JwtClaims>,
+        storage: Arc<dyn Storage>,
+        max_upload_bytes: usize,
+        search_config: SearchConfig,
+        auth_config: AuthConfig,
+        mailer: Arc<dyn Mailer>,
+    ) -> Self {
         Self {
             db_pool,
             jwt_decoder,
+            storage,
+            max_upload_bytes,
+            #[cfg(feature = "embeddings")]
+            embedder: build_embedder(&search_config.semantic),
+            search_config,
+            auth_config,
+            mailer,
         }
     }
 }
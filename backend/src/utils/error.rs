@@ -14,6 +14,9 @@ pub enum AppError {
     #[error("Authentication error: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Conflict error: {0}")]
     Conflict(String),
 
@@ -23,6 +26,9 @@ pub enum AppError {
     #[error("Not found error: {0}")]
     NotFound(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Internal server error: {0}")]
     Internal(String),
 
@@ -38,6 +44,9 @@ pub enum AppError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
     #[error("Anyhow error: {0}")]
     Anyhow(anyhow::Error),
 }
@@ -73,9 +82,11 @@ impl IntoResponse for AppError {
                 }
             }
             AppError::Unauthorized(ref msg) => (StatusCode::UNAUTHORIZED, msg.as_str()),
+            AppError::Forbidden(ref msg) => (StatusCode::FORBIDDEN, msg.as_str()),
             AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.as_str()),
             AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
+            AppError::TooManyRequests(ref msg) => (StatusCode::TOO_MANY_REQUESTS, msg.as_str()),
             AppError::Internal(ref msg) => {
                 // 记录详细错误到日志
                 tracing::error!("Internal error: {}", msg);
@@ -108,6 +119,11 @@ impl IntoResponse for AppError {
                     "Data serialization error",
                 )
             }
+            AppError::Http(ref err) => {
+                // 记录外部请求错误详情
+                tracing::warn!("HTTP error: {:?}", err);
+                (StatusCode::BAD_GATEWAY, "Upstream request failed")
+            }
             AppError::Anyhow(ref err) => {
                 // 记录 Anyhow 错误详情
                 tracing::error!("Anyhow error: {:?}", err);
@@ -0,0 +1,229 @@
+//! 访问外部网页的 HTTP 客户端
+//!
+//! 抓取地址由用户提供, 为避免服务端请求伪造 (SSRF), 默认拒绝访问回环、内网和链路本地地址:
+//! - 域名通过自定义 DNS 解析器过滤, 重定向后的新域名同样经过该解析器
+//! - IP 字面量不经过 DNS, 在请求前和每次重定向时单独检查
+//!
+//! `allow_private_networks` 仅用于开发和测试环境
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use url::{Host, Url};
+
+use crate::config::FetcherConfig;
+use crate::utils::error::{AppError, AppResult};
+
+/// 抓取结果
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    /// 跟随重定向后的最终地址
+    pub final_url: Url,
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// 响应体 (最多 max_body_bytes 字节, 按 UTF-8 宽松解码)
+    pub body: String,
+}
+
+impl FetchedPage {
+    /// 是否为 HTML 页面, 未声明 Content-Type 时按 HTML 处理
+    pub fn is_html(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_none_or(|content_type| content_type.contains("html"))
+    }
+}
+
+/// 创建 HTTP 客户端
+pub fn build_client(config: &FetcherConfig) -> AppResult<reqwest::Client> {
+    let max_redirects = config.max_redirects;
+    let allow_private = config.allow_private_networks;
+
+    let redirect_policy = Policy::custom(move |attempt| {
+        if attempt.previous().len() > max_redirects {
+            attempt.error("too many redirects")
+        } else if !allow_private && !is_allowed_host(attempt.url()) {
+            attempt.error("redirect to a private address is not allowed")
+        } else {
+            attempt.follow()
+        }
+    });
+
+    let mut builder = reqwest::Client::builder()
+        .user_agent(config.user_agent.clone())
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .redirect(redirect_policy);
+    if !allow_private {
+        builder = builder.dns_resolver(Arc::new(PublicResolver));
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// 验证待抓取的地址: 只允许 http(s), 并拒绝指向内网的 IP 字面量
+pub fn ensure_fetchable(raw_url: &str, config: &FetcherConfig) -> AppResult<Url> {
+    let url =
+        Url::parse(raw_url).map_err(|e| AppError::BadRequest(format!("Invalid URL: {}", e)))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported URL scheme: {}",
+            url.scheme()
+        )));
+    }
+
+    if !config.allow_private_networks && !is_allowed_host(&url) {
+        return Err(AppError::BadRequest(
+            "Fetching private network addresses is not allowed".to_string(),
+        ));
+    }
+
+    Ok(url)
+}
+
+/// 发送 GET 请求并读取响应体, 超过上限的部分直接丢弃
+pub async fn fetch_page(
+    client: &reqwest::Client,
+    url: Url,
+    max_body_bytes: usize,
+) -> AppResult<FetchedPage> {
+    let mut response = client.get(url).send().await?;
+
+    let final_url = response.url().clone();
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        let remaining = max_body_bytes - body.len();
+        if chunk.len() >= remaining {
+            body.extend_from_slice(&chunk[..remaining]);
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(FetchedPage {
+        final_url,
+        status,
+        content_type,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// 只过滤 IP 字面量, 域名交给 PublicResolver 处理
+fn is_allowed_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        Some(Host::Domain(domain)) => !domain.eq_ignore_ascii_case("localhost"),
+        None => false,
+    }
+}
+
+/// 是否为公网地址
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || a == 0
+                // 100.64.0.0/10 运营商级 NAT
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                // fc00::/7 唯一本地地址
+                || (first & 0xfe00) == 0xfc00
+                // fe80::/10 链路本地地址
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// 只返回公网地址的 DNS 解析器
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} does not resolve to a public address", name.as_str()).into(),
+                );
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(
+                !is_public_ip(ip.parse().unwrap()),
+                "{} should be private",
+                ip
+            );
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+
+    #[test]
+    fn test_ensure_fetchable() {
+        let config = FetcherConfig::default();
+        assert!(ensure_fetchable("https://example.com/", &config).is_ok());
+        assert!(ensure_fetchable("ftp://example.com/", &config).is_err());
+        assert!(ensure_fetchable("http://127.0.0.1:8080/", &config).is_err());
+        assert!(ensure_fetchable("http://localhost/", &config).is_err());
+        assert!(ensure_fetchable("http://[::1]/", &config).is_err());
+
+        let config = FetcherConfig {
+            allow_private_networks: true,
+            ..Default::default()
+        };
+        assert!(ensure_fetchable("http://127.0.0.1:8080/", &config).is_ok());
+    }
+}
@@ -5,77 +5,123 @@ use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
 
+use crate::config::AuthConfig;
 use crate::utils::error::{AppError, AppResult};
 
+/// 令牌类型, 防止刷新令牌被当作访问令牌使用, 反之亦然
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JwtClaims {
     pub sub: String, // User ID
     pub exp: usize,  // Expiration time
     pub iat: usize,  // Issued at time
+    pub typ: TokenType,
+    /// 会话 ID, 即刷新令牌家族 ID
+    pub sid: String,
+    /// 刷新令牌 ID, 对应 refresh_tokens.id, 访问令牌没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+impl JwtClaims {
+    pub fn user_id(&self) -> AppResult<i64> {
+        self.sub
+            .parse::<i64>()
+            .map_err(|_| AppError::Unauthorized("Invalid token format".to_string()))
+    }
 }
 
 #[derive(Clone)]
 pub struct JWTService {
     pub(crate) secret: String,
+    /// 访问令牌有效期 (分钟)
+    access_token_expires_in: i64,
+    /// 刷新令牌有效期 (分钟)
+    refresh_token_expires_in: i64,
 }
 
 impl JWTService {
+    /// 使用默认有效期
     pub fn new(secret: String) -> Self {
-        Self { secret }
+        Self::from_config(&AuthConfig::new(secret))
     }
 
-    pub fn generate_access_token(&self, user_id: i64) -> AppResult<String> {
+    /// 使用配置中的密钥和令牌有效期
+    pub fn from_config(config: &AuthConfig) -> Self {
+        Self {
+            secret: config.jwt_secret.clone(),
+            access_token_expires_in: config.jwt_expires_in as i64,
+            refresh_token_expires_in: config.refresh_token_expires_in as i64,
+        }
+    }
+
+    pub fn generate_access_token(&self, user_id: i64, session_id: &str) -> AppResult<String> {
         let now = Utc::now();
-        let exp = now + Duration::minutes(15); // 15 minutes
+        let exp = now + Duration::minutes(self.access_token_expires_in);
 
         let claims = JwtClaims {
             sub: user_id.to_string(),
             exp: exp.timestamp() as usize,
             iat: now.timestamp() as usize,
+            typ: TokenType::Access,
+            sid: session_id.to_string(),
+            jti: None,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_ref()),
-        )?;
-
-        Ok(token)
+        self.encode(&claims)
     }
 
-    pub fn generate_refresh_token(&self, user_id: i64) -> AppResult<String> {
+    /// 生成刷新令牌, 返回令牌和过期时间 (Unix 时间戳)
+    pub fn generate_refresh_token(
+        &self,
+        user_id: i64,
+        session_id: &str,
+        token_id: &str,
+    ) -> AppResult<(String, i64)> {
         let now = Utc::now();
-        let exp = now + Duration::days(7); // 7 days
+        let exp = now + Duration::minutes(self.refresh_token_expires_in);
 
         let claims = JwtClaims {
             sub: user_id.to_string(),
             exp: exp.timestamp() as usize,
             iat: now.timestamp() as usize,
+            typ: TokenType::Refresh,
+            sid: session_id.to_string(),
+            jti: Some(token_id.to_string()),
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_ref()),
-        )?;
-
-        Ok(token)
+        Ok((self.encode(&claims)?, exp.timestamp()))
     }
 
-    pub fn verify_token(&self, token: &str) -> AppResult<i64> {
+    /// 校验签名、有效期和令牌类型
+    pub fn verify_token(&self, token: &str, expected: TokenType) -> AppResult<JwtClaims> {
         let token_data = decode::<JwtClaims>(
             token,
             &DecodingKey::from_secret(self.secret.as_ref()),
             &Validation::default(),
         )?;
 
-        let user_id = token_data
-            .claims
-            .sub
-            .parse::<i64>()
-            .map_err(|_| AppError::Unauthorized("Invalid token format".to_string()))?;
+        if token_data.claims.typ != expected {
+            return Err(AppError::Unauthorized("Invalid token type".to_string()));
+        }
 
-        Ok(user_id)
+        Ok(token_data.claims)
+    }
+
+    fn encode(&self, claims: &JwtClaims) -> AppResult<String> {
+        let token = encode(
+            &Header::default(),
+            claims,
+            &EncodingKey::from_secret(self.secret.as_ref()),
+        )?;
+
+        Ok(token)
     }
 }
 
@@ -112,7 +158,7 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 123;
 
-        let token = service.generate_access_token(user_id);
+        let token = service.generate_access_token(user_id, "session");
         assert!(token.is_ok());
 
         let token_str = token.unwrap();
@@ -124,11 +170,32 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 456;
 
-        let token = service.generate_refresh_token(user_id);
+        let token = service.generate_refresh_token(user_id, "session", "token");
         assert!(token.is_ok());
 
-        let token_str = token.unwrap();
+        let (token_str, expires_at) = token.unwrap();
         assert!(!token_str.is_empty());
+        assert!(expires_at > Utc::now().timestamp());
+    }
+
+    #[test]
+    fn test_token_lifetimes_from_config() {
+        let config = AuthConfig {
+            jwt_expires_in: 5,
+            refresh_token_expires_in: 60,
+            ..AuthConfig::new("test_secret".to_string())
+        };
+        let service = JWTService::from_config(&config);
+        let now = Utc::now().timestamp();
+
+        let token = service.generate_access_token(1, "session").unwrap();
+        let claims = service.verify_token(&token, TokenType::Access).unwrap();
+        assert!((claims.exp as i64 - now - 5 * 60).abs() <= 1);
+
+        let (_, expires_at) = service
+            .generate_refresh_token(1, "session", "token")
+            .unwrap();
+        assert!((expires_at - now - 60 * 60).abs() <= 1);
     }
 
     #[test]
@@ -136,10 +203,11 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 789;
 
-        let token = service.generate_access_token(user_id).unwrap();
-        let verified_id = service.verify_token(&token).unwrap();
+        let token = service.generate_access_token(user_id, "session").unwrap();
+        let claims = service.verify_token(&token, TokenType::Access).unwrap();
 
-        assert_eq!(verified_id, user_id);
+        assert_eq!(claims.user_id().unwrap(), user_id);
+        assert_eq!(claims.sid, "session");
     }
 
     #[test]
@@ -147,7 +215,7 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let invalid_token = "invalid.token.here";
 
-        let result = service.verify_token(invalid_token);
+        let result = service.verify_token(invalid_token, TokenType::Access);
         assert!(result.is_err());
     }
 
@@ -157,8 +225,8 @@ mod tests {
         let service2 = JWTService::new("secret2".to_string());
         let user_id = 999;
 
-        let token = service1.generate_access_token(user_id).unwrap();
-        let result = service2.verify_token(&token);
+        let token = service1.generate_access_token(user_id, "session").unwrap();
+        let result = service2.verify_token(&token, TokenType::Access);
 
         assert!(result.is_err());
     }
@@ -168,7 +236,7 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 12345;
 
-        let token = service.generate_access_token(user_id).unwrap();
+        let token = service.generate_access_token(user_id, "session").unwrap();
 
         let token_data = decode::<JwtClaims>(
             &token,
@@ -186,7 +254,7 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 123;
 
-        let token = service.generate_access_token(user_id).unwrap();
+        let token = service.generate_access_token(user_id, "session").unwrap();
         let token_data = decode::<JwtClaims>(
             &token,
             &DecodingKey::from_secret(service.secret.as_ref()),
@@ -207,7 +275,9 @@ mod tests {
         let service = JWTService::new("test_secret".to_string());
         let user_id = 123;
 
-        let token = service.generate_refresh_token(user_id).unwrap();
+        let (token, _) = service
+            .generate_refresh_token(user_id, "session", "token")
+            .unwrap();
         let token_data = decode::<JwtClaims>(
             &token,
             &DecodingKey::from_secret(service.secret.as_ref()),
@@ -222,4 +292,26 @@ mod tests {
         assert!(token_data.claims.exp >= expected_exp - 60);
         assert!(token_data.claims.exp <= expected_exp + 60);
     }
+
+    #[test]
+    fn test_token_type_is_checked() {
+        let service = JWTService::new("test_secret".to_string());
+
+        let access_token = service.generate_access_token(1, "session").unwrap();
+        let (refresh_token, _) = service
+            .generate_refresh_token(1, "session", "token")
+            .unwrap();
+
+        assert!(service
+            .verify_token(&access_token, TokenType::Refresh)
+            .is_err());
+        assert!(service
+            .verify_token(&refresh_token, TokenType::Access)
+            .is_err());
+
+        let claims = service
+            .verify_token(&refresh_token, TokenType::Refresh)
+            .unwrap();
+        assert_eq!(claims.jti.as_deref(), Some("token"));
+    }
 }
@@ -1,5 +1,13 @@
 pub mod error;
+pub mod http_client;
 pub mod jwt;
+pub mod netscape;
+pub mod page_metadata;
+pub mod readability;
 pub mod response;
+pub mod search_query;
 pub mod segmenter;
+pub mod spelling;
+pub mod text_extractor;
+pub mod token;
 pub mod validation;
@@ -0,0 +1,494 @@
+//! Netscape 书签文件 (bookmarks.html) 解析与生成工具
+//!
+//! 浏览器导出的书签文件并不是规范的 HTML, 而是一种约定格式:
+//! - `<DT><H3 ...>文件夹名</H3>` 后面紧跟一个 `<DL>` 块, 块内为该文件夹的内容
+//! - `<DT><A HREF="..." ADD_DATE="..." TAGS="a,b">标题</A>` 表示一个书签
+//! - 书签后可选的 `<DD>描述` 表示书签描述
+//!
+//! 这里只做基于标签的轻量扫描, 不依赖完整的 HTML 解析器
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// 匹配解析所需的开标签 (以及 `</DL>` 闭标签)
+static TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<\s*(/?)\s*(dl|h3|a|dd)\b([^>]*)>").expect("Failed to compile token regex")
+});
+
+/// 匹配标签属性 KEY="value"
+static ATTR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)([a-z_:\-]+)\s*=\s*"([^"]*)""#).expect("Failed to compile attribute regex")
+});
+
+/// 匹配 HTML 实体
+static ENTITY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("Failed to compile entity regex")
+});
+
+/// 解析出的单个书签
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetscapeBookmark {
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// ADD_DATE 属性 (Unix 时间戳, 秒)
+    pub add_date: Option<i64>,
+    /// 所在文件夹路径, 顶层书签为空
+    pub folder_path: Vec<String>,
+}
+
+/// 解析结果
+#[derive(Debug, Default)]
+pub struct NetscapeDocument {
+    /// 按文档顺序出现的文件夹路径 (父文件夹总是先于子文件夹出现)
+    pub folders: Vec<Vec<String>>,
+    pub bookmarks: Vec<NetscapeBookmark>,
+}
+
+/// 解析 Netscape 书签文件
+pub fn parse_bookmarks(html: &str) -> NetscapeDocument {
+    let mut document = NetscapeDocument::default();
+
+    // 每个 <DL> 入栈一项, 由文件夹打开的 <DL> 记录文件夹名
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    // 最近一个书签的下标, 用于挂接紧随其后的 <DD> 描述
+    let mut last_bookmark: Option<usize> = None;
+
+    for caps in TOKEN_REGEX.captures_iter(html) {
+        let whole = caps.get(0).expect("match always has group 0");
+        let is_closing = !caps[1].is_empty();
+        let tag = caps[2].to_ascii_lowercase();
+        let attrs = caps.get(3).map(|m| m.as_str()).unwrap_or_default();
+
+        match (tag.as_str(), is_closing) {
+            ("dl", false) => {
+                stack.push(pending_folder.take());
+                last_bookmark = None;
+            }
+            ("dl", true) => {
+                stack.pop();
+                last_bookmark = None;
+            }
+            ("h3", false) => {
+                let name = decode_entities(inner_text(html, whole.end()).trim());
+                let mut path = current_path(&stack);
+                path.push(name.clone());
+                document.folders.push(path);
+                pending_folder = Some(name);
+                last_bookmark = None;
+            }
+            ("a", false) => {
+                let attributes = parse_attributes(attrs);
+                let Some(url) = attributes
+                    .get("HREF")
+                    .map(|href| decode_entities(href.trim()))
+                else {
+                    continue;
+                };
+                if url.is_empty() {
+                    continue;
+                }
+
+                let title = decode_entities(inner_text(html, whole.end()).trim());
+                let tags = attributes
+                    .get("TAGS")
+                    .map(|tags| split_tags(&decode_entities(tags)))
+                    .unwrap_or_default();
+                let add_date = attributes
+                    .get("ADD_DATE")
+                    .and_then(|value| value.trim().parse::<i64>().ok())
+                    .filter(|value| *value > 0);
+
+                document.bookmarks.push(NetscapeBookmark {
+                    title,
+                    url,
+                    description: None,
+                    tags,
+                    add_date,
+                    folder_path: current_path(&stack),
+                });
+                last_bookmark = Some(document.bookmarks.len() - 1);
+            }
+            ("dd", false) => {
+                let Some(index) = last_bookmark.take() else {
+                    continue;
+                };
+                let description = decode_entities(inner_text(html, whole.end()).trim());
+                if !description.is_empty() {
+                    document.bookmarks[index].description = Some(description);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    document
+}
+
+/// 待导出的文件夹
+#[derive(Debug, Clone)]
+pub struct NetscapeFolder {
+    pub id: i64,
+    pub name: String,
+    /// 父文件夹 id, 不存在于导出集合中的父文件夹视为顶层
+    pub parent_id: Option<i64>,
+    pub add_date: Option<i64>,
+}
+
+/// 待导出的书签
+#[derive(Debug, Clone)]
+pub struct NetscapeLink {
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub add_date: Option<i64>,
+    pub last_modified: Option<i64>,
+    /// 所在文件夹 id, 为空或不存在时放在顶层
+    pub folder_id: Option<i64>,
+}
+
+/// 生成 Netscape 书签文件
+///
+/// 文件夹按 parent_id 还原为嵌套的 `<DL>` 结构, 输出可被 parse_bookmarks 和主流浏览器读取
+pub fn render_bookmarks(folders: &[NetscapeFolder], links: &[NetscapeLink]) -> String {
+    let known: HashSet<i64> = folders.iter().map(|folder| folder.id).collect();
+    let resolve = |id: Option<i64>| id.filter(|id| known.contains(id));
+
+    let mut child_folders: HashMap<Option<i64>, Vec<&NetscapeFolder>> = HashMap::new();
+    for folder in folders {
+        child_folders
+            .entry(resolve(folder.parent_id))
+            .or_default()
+            .push(folder);
+    }
+
+    let mut child_links: HashMap<Option<i64>, Vec<&NetscapeLink>> = HashMap::new();
+    for link in links {
+        child_links
+            .entry(resolve(link.folder_id))
+            .or_default()
+            .push(link);
+    }
+
+    let mut out = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <!-- This is an automatically generated file.\n\
+         \x20    It will be read and overwritten.\n\
+         \x20    DO NOT EDIT! -->\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+
+    let mut visited: HashSet<i64> = HashSet::new();
+    write_folder_contents(
+        &mut out,
+        None,
+        1,
+        &child_folders,
+        &child_links,
+        &mut visited,
+    );
+
+    // parent_id 成环的文件夹无法从顶层到达, 放到顶层输出以免丢失
+    for folder in folders {
+        if !visited.contains(&folder.id) {
+            write_folder(
+                &mut out,
+                folder,
+                1,
+                &child_folders,
+                &child_links,
+                &mut visited,
+            );
+        }
+    }
+
+    out.push_str("</DL><p>\n");
+    out
+}
+
+/// 输出某个文件夹 (None 表示顶层) 下的子文件夹和书签
+fn write_folder_contents(
+    out: &mut String,
+    parent: Option<i64>,
+    depth: usize,
+    child_folders: &HashMap<Option<i64>, Vec<&NetscapeFolder>>,
+    child_links: &HashMap<Option<i64>, Vec<&NetscapeLink>>,
+    visited: &mut HashSet<i64>,
+) {
+    for folder in child_folders.get(&parent).into_iter().flatten() {
+        if !visited.contains(&folder.id) {
+            write_folder(out, folder, depth, child_folders, child_links, visited);
+        }
+    }
+
+    let indent = "    ".repeat(depth);
+    for link in child_links.get(&parent).into_iter().flatten() {
+        out.push_str(&format!(
+            "{}<DT><A HREF=\"{}\"",
+            indent,
+            escape_html(&link.url)
+        ));
+        if let Some(add_date) = link.add_date {
+            out.push_str(&format!(" ADD_DATE=\"{}\"", add_date));
+        }
+        if let Some(last_modified) = link.last_modified {
+            out.push_str(&format!(" LAST_MODIFIED=\"{}\"", last_modified));
+        }
+        if !link.tags.is_empty() {
+            out.push_str(&format!(" TAGS=\"{}\"", escape_html(&link.tags.join(","))));
+        }
+        out.push_str(&format!(">{}</A>\n", escape_html(&link.title)));
+
+        if let Some(description) = link.description.as_deref().filter(|d| !d.is_empty()) {
+            out.push_str(&format!("{}<DD>{}\n", indent, escape_html(description)));
+        }
+    }
+}
+
+/// 输出单个文件夹及其内容
+fn write_folder(
+    out: &mut String,
+    folder: &NetscapeFolder,
+    depth: usize,
+    child_folders: &HashMap<Option<i64>, Vec<&NetscapeFolder>>,
+    child_links: &HashMap<Option<i64>, Vec<&NetscapeLink>>,
+    visited: &mut HashSet<i64>,
+) {
+    visited.insert(folder.id);
+
+    let indent = "    ".repeat(depth);
+    out.push_str(&format!("{}<DT><H3", indent));
+    if let Some(add_date) = folder.add_date {
+        out.push_str(&format!(" ADD_DATE=\"{}\"", add_date));
+    }
+    out.push_str(&format!(">{}</H3>\n", escape_html(&folder.name)));
+    out.push_str(&format!("{}<DL><p>\n", indent));
+    write_folder_contents(
+        out,
+        Some(folder.id),
+        depth + 1,
+        child_folders,
+        child_links,
+        visited,
+    );
+    out.push_str(&format!("{}</DL><p>\n", indent));
+}
+
+/// 转义 HTML 特殊字符 (用于文本和属性值)
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            // 书签文件按行组织, 换行会破坏结构
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// 解码常见 HTML 实体
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    ENTITY_REGEX
+        .replace_all(text, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse::<u32>().ok().and_then(char::from_u32)
+            } else {
+                match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    _ => None,
+                }
+            };
+
+            decoded
+                .map(String::from)
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// 当前所在文件夹路径
+fn current_path(stack: &[Option<String>]) -> Vec<String> {
+    stack.iter().flatten().cloned().collect()
+}
+
+/// 读取开标签之后的文本 (直到下一个标签)
+fn inner_text(html: &str, start: usize) -> &str {
+    let rest = &html[start..];
+    let end = rest.find('<').unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// 解析标签属性, 属性名统一转为大写
+fn parse_attributes(attrs: &str) -> HashMap<String, String> {
+    ATTR_REGEX
+        .captures_iter(attrs)
+        .map(|caps| (caps[1].to_ascii_uppercase(), caps[2].to_string()))
+        .collect()
+}
+
+/// 拆分逗号分隔的标签, 去除空白与重复项
+fn split_tags(tags: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !result.iter().any(|existing| existing == tag) {
+            result.push(tag.to_string());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><A HREF="https://www.rust-lang.org/" ADD_DATE="1700000000" TAGS="rust,lang">Rust &amp; Cargo</A>
+    <DD>The Rust language
+    <DT><H3 ADD_DATE="1700000000">Dev</H3>
+    <DL><p>
+        <DT><A HREF="https://github.com/">GitHub</A>
+        <DT><H3>Tools</H3>
+        <DL><p>
+            <DT><A HREF="https://crates.io/" TAGS="rust, rust, crates">crates.io</A>
+        </DL><p>
+    </DL><p>
+    <DT><A HREF="https://example.com/">Example</A>
+</DL><p>
+"#;
+
+    #[test]
+    fn test_parse_bookmarks_structure() {
+        let document = parse_bookmarks(SAMPLE);
+
+        assert_eq!(
+            document.folders,
+            vec![
+                vec!["Dev".to_string()],
+                vec!["Dev".to_string(), "Tools".to_string()]
+            ]
+        );
+        assert_eq!(document.bookmarks.len(), 4);
+
+        let rust = &document.bookmarks[0];
+        assert_eq!(rust.title, "Rust & Cargo");
+        assert_eq!(rust.url, "https://www.rust-lang.org/");
+        assert_eq!(rust.description.as_deref(), Some("The Rust language"));
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(rust.add_date, Some(1_700_000_000));
+        assert!(rust.folder_path.is_empty());
+
+        assert_eq!(document.bookmarks[1].folder_path, vec!["Dev"]);
+        assert_eq!(document.bookmarks[2].folder_path, vec!["Dev", "Tools"]);
+        assert_eq!(document.bookmarks[2].tags, vec!["rust", "crates"]);
+        assert!(document.bookmarks[3].folder_path.is_empty());
+        assert!(document.bookmarks[3].description.is_none());
+    }
+
+    #[test]
+    fn test_parse_bookmarks_empty() {
+        let document = parse_bookmarks("<html><body>nothing here</body></html>");
+        assert!(document.folders.is_empty());
+        assert!(document.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn test_render_bookmarks_round_trip() {
+        let folders = vec![
+            NetscapeFolder {
+                id: 1,
+                name: "Dev".to_string(),
+                parent_id: None,
+                add_date: Some(1_700_000_000),
+            },
+            NetscapeFolder {
+                id: 2,
+                name: "Tools & <Utils>".to_string(),
+                parent_id: Some(1),
+                add_date: None,
+            },
+        ];
+        let links = vec![
+            NetscapeLink {
+                title: "Rust \"lang\"".to_string(),
+                url: "https://www.rust-lang.org/?a=1&b=2".to_string(),
+                description: Some("A systems language".to_string()),
+                tags: vec!["rust".to_string(), "lang".to_string()],
+                add_date: Some(1_600_000_000),
+                last_modified: None,
+                folder_id: Some(2),
+            },
+            NetscapeLink {
+                title: "Example".to_string(),
+                url: "https://example.com/".to_string(),
+                description: None,
+                tags: Vec::new(),
+                add_date: None,
+                last_modified: None,
+                folder_id: Some(42), // 不存在的文件夹, 放在顶层
+            },
+        ];
+
+        let html = render_bookmarks(&folders, &links);
+        let document = parse_bookmarks(&html);
+
+        assert_eq!(
+            document.folders,
+            vec![
+                vec!["Dev".to_string()],
+                vec!["Dev".to_string(), "Tools & <Utils>".to_string()]
+            ]
+        );
+        assert_eq!(document.bookmarks.len(), 2);
+
+        let rust = &document.bookmarks[0];
+        assert_eq!(rust.title, "Rust \"lang\"");
+        assert_eq!(rust.url, "https://www.rust-lang.org/?a=1&b=2");
+        assert_eq!(rust.description.as_deref(), Some("A systems language"));
+        assert_eq!(rust.tags, vec!["rust", "lang"]);
+        assert_eq!(rust.add_date, Some(1_600_000_000));
+        assert_eq!(rust.folder_path, vec!["Dev", "Tools & <Utils>"]);
+
+        assert!(document.bookmarks[1].folder_path.is_empty());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#39;c&#x27; &unknown;"),
+            "a <b> 'c' &unknown;"
+        );
+        assert_eq!(decode_entities("plain text"), "plain text");
+    }
+}
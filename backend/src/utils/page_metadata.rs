@@ -0,0 +1,238 @@
+//! 网页元数据提取工具
+//!
+//! 从页面 `<head>` 中提取标题、描述、预览图、规范链接和站点图标,
+//! 按 Open Graph -> Twitter Card -> 标准 HTML 标签的优先级取值。
+//! 与 Netscape 解析一样只做基于标签的轻量扫描, 不依赖完整的 HTML 解析器
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::utils::netscape::decode_entities;
+
+/// 匹配 `<title>`、`<meta>` 和 `<link>` 标签
+static HEAD_TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<title[^>]*>(.*?)</title>|<(meta|link)\b([^>]*)>")
+        .expect("Failed to compile head token regex")
+});
+
+/// 匹配标签属性 key="value" 或 key='value'
+static HEAD_ATTR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)([a-z_:\-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("Failed to compile head attribute regex")
+});
+
+/// 匹配连续空白
+static WHITESPACE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s+").expect("Failed to compile whitespace regex"));
+
+/// 提取出的页面元数据, 链接均已解析为绝对地址
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub canonical_url: Option<String>,
+    pub favicon_url: Option<String>,
+    pub site_name: Option<String>,
+}
+
+/// 从 HTML 中提取元数据
+///
+/// `base_url` 为页面最终地址 (跟随重定向后), 用于解析相对链接;
+/// 页面没有声明图标时回退到站点根目录下的 `/favicon.ico`
+pub fn extract_metadata(html: &str, base_url: &Url) -> PageMetadata {
+    // 元数据只出现在 head 中, 避免扫描正文
+    let head = html
+        .to_ascii_lowercase()
+        .find("</head>")
+        .and_then(|end| html.get(..end))
+        .unwrap_or(html);
+
+    let mut title_tag = None;
+    let mut meta: HashMap<String, String> = HashMap::new();
+    let mut canonical = None;
+    let mut icon = None;
+    let mut touch_icon = None;
+
+    for caps in HEAD_TOKEN_REGEX.captures_iter(head) {
+        if let Some(title) = caps.get(1) {
+            if title_tag.is_none() {
+                title_tag = clean_text(title.as_str());
+            }
+            continue;
+        }
+
+        let attrs = parse_attrs(caps.get(3).map(|m| m.as_str()).unwrap_or_default());
+        if caps[2].eq_ignore_ascii_case("meta") {
+            let key = attrs.get("property").or_else(|| attrs.get("name"));
+            if let (Some(key), Some(content)) = (key, attrs.get("content")) {
+                // 同名标签以第一次出现为准
+                meta.entry(key.to_lowercase())
+                    .or_insert_with(|| content.clone());
+            }
+            continue;
+        }
+
+        let (Some(rel), Some(href)) = (attrs.get("rel"), attrs.get("href")) else {
+            continue;
+        };
+        let rel = rel.to_lowercase();
+        let rels: Vec<&str> = rel.split_whitespace().collect();
+        if rels.contains(&"canonical") {
+            canonical.get_or_insert_with(|| href.clone());
+        } else if rels.contains(&"apple-touch-icon") {
+            touch_icon.get_or_insert_with(|| href.clone());
+        } else if rels.contains(&"icon") {
+            icon.get_or_insert_with(|| href.clone());
+        }
+    }
+
+    let meta_text = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| meta.get(*key))
+            .find_map(|value| clean_text(value))
+    };
+    let meta_url = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| meta.get(*key))
+            .find_map(|value| resolve_url(base_url, value))
+    };
+
+    PageMetadata {
+        title: meta_text(&["og:title", "twitter:title"]).or(title_tag),
+        description: meta_text(&["description", "og:description", "twitter:description"]),
+        image: meta_url(&[
+            "og:image",
+            "og:image:url",
+            "twitter:image",
+            "twitter:image:src",
+        ]),
+        canonical_url: canonical
+            .and_then(|href| resolve_url(base_url, &href))
+            .or_else(|| meta_url(&["og:url"])),
+        favicon_url: icon
+            .or(touch_icon)
+            .and_then(|href| resolve_url(base_url, &href))
+            .or_else(|| resolve_url(base_url, "/favicon.ico")),
+        site_name: meta_text(&["og:site_name"]),
+    }
+}
+
+/// 解析标签属性, 属性名统一为小写, 值已解码 HTML 实体
+fn parse_attrs(raw: &str) -> HashMap<String, String> {
+    HEAD_ATTR_REGEX
+        .captures_iter(raw)
+        .map(|attr| {
+            let value = attr.get(2).or_else(|| attr.get(3)).map(|m| m.as_str());
+            (
+                attr[1].to_lowercase(),
+                decode_entities(value.unwrap_or_default().trim()),
+            )
+        })
+        .collect()
+}
+
+/// 解码实体并合并空白, 空字符串返回 None
+fn clean_text(raw: &str) -> Option<String> {
+    let text = WHITESPACE_REGEX
+        .replace_all(&decode_entities(raw), " ")
+        .trim()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// 相对链接转换为绝对地址, 只保留 http(s) 链接
+fn resolve_url(base_url: &Url, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() {
+        return None;
+    }
+
+    base_url
+        .join(href)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/blog/post").unwrap()
+    }
+
+    #[test]
+    fn test_extract_prefers_open_graph() {
+        let html = r#"<!DOCTYPE html>
+<html><head>
+<title>  Plain
+    Title </title>
+<meta name="description" content="Plain &amp; simple">
+<meta property="og:title" content="OG Title">
+<meta property="og:description" content="OG description">
+<meta property="og:image" content="/images/cover.png">
+<meta property="og:site_name" content="Example">
+<link rel="canonical" href="https://example.com/post">
+<link rel="shortcut icon" href="/static/favicon.png">
+</head><body><title>Body title</title></body></html>"#;
+
+        let metadata = extract_metadata(html, &base());
+        assert_eq!(metadata.title.as_deref(), Some("OG Title"));
+        assert_eq!(metadata.description.as_deref(), Some("Plain & simple"));
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://example.com/images/cover.png")
+        );
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/post")
+        );
+        assert_eq!(
+            metadata.favicon_url.as_deref(),
+            Some("https://example.com/static/favicon.png")
+        );
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn test_extract_falls_back_to_twitter_and_title() {
+        let html = r#"<html><head>
+<TITLE>Plain Title</TITLE>
+<meta name='twitter:description' content='Twitter description'>
+<meta name="twitter:image" content="cover.jpg">
+<link rel="apple-touch-icon" href="touch.png">
+</head></html>"#;
+
+        let metadata = extract_metadata(html, &base());
+        assert_eq!(metadata.title.as_deref(), Some("Plain Title"));
+        assert_eq!(metadata.description.as_deref(), Some("Twitter description"));
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://example.com/blog/cover.jpg")
+        );
+        assert_eq!(
+            metadata.favicon_url.as_deref(),
+            Some("https://example.com/blog/touch.png")
+        );
+        assert!(metadata.canonical_url.is_none());
+    }
+
+    #[test]
+    fn test_extract_defaults_favicon_and_ignores_non_http_links() {
+        let html = r#"<head><meta property="og:image" content="javascript:alert(1)">
+<meta property="og:title" content="   "></head>"#;
+
+        let metadata = extract_metadata(html, &base());
+        assert!(metadata.title.is_none());
+        assert!(metadata.image.is_none());
+        assert_eq!(
+            metadata.favicon_url.as_deref(),
+            Some("https://example.com/favicon.ico")
+        );
+    }
+}
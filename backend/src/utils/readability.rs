@@ -0,0 +1,285 @@
+//! 网页可读快照工具
+//!
+//! 为离线存档生成可读版本: 移除脚本、样式、表单和导航等与阅读无关的元素,
+//! 优先保留 `<article>` / `<main>` 中的正文, 并提取纯文本用于全文索引。
+//! 与元数据提取一样只做基于正则的轻量扫描, 不依赖完整的 HTML 解析器
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use url::Url;
+
+use crate::utils::netscape::{decode_entities, escape_html};
+
+/// 连同内容一起移除的元素
+const STRIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "canvas",
+    "form", "button", "select", "textarea", "nav", "aside", "footer",
+];
+
+/// 匹配需要移除的完整元素, regex 不支持反向引用, 每种元素单独编译
+static STRIPPED_ELEMENT_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    STRIPPED_ELEMENTS
+        .iter()
+        .map(|tag| {
+            Regex::new(&format!(r"(?is)<{0}\b[^>]*>.*?</{0}\s*>", tag))
+                .expect("Failed to compile stripped element regex")
+        })
+        .collect()
+});
+
+/// 匹配未闭合或单独出现的危险标签
+static DANGLING_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?i)</?(?:{}|link|meta|base|input|frame|frameset)\b[^>]*>",
+        STRIPPED_ELEMENTS.join("|")
+    ))
+    .expect("Failed to compile dangling tag regex")
+});
+
+/// 匹配 HTML 注释
+static COMMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<!--.*?-->").expect("Failed to compile comment regex"));
+
+/// 匹配 `<title>`
+static TITLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("Failed to compile title regex")
+});
+
+/// 正文容器, 按优先级排列
+static CONTENT_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    ["article", "main", "body"]
+        .iter()
+        .map(|tag| {
+            Regex::new(&format!(r"(?is)<{0}\b[^>]*>(.*)</{0}\s*>", tag))
+                .expect("Failed to compile content regex")
+        })
+        .collect()
+});
+
+/// 没有正文容器时需要去掉的文档结构
+static DOCUMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<head\b.*?</head\s*>|<!doctype[^>]*>|</?(?:html|body)\b[^>]*>")
+        .expect("Failed to compile document regex")
+});
+
+/// 匹配开始标签
+static START_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<([a-zA-Z][a-zA-Z0-9]*)((?:[^>]*?))(/?)>")
+        .expect("Failed to compile start tag regex")
+});
+
+/// 匹配标签属性, 支持带引号和不带引号的值
+static ATTR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?s)\s+([a-zA-Z_:][a-zA-Z0-9_:.\-]*)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#,
+    )
+    .expect("Failed to compile attribute regex")
+});
+
+/// 匹配产生换行的标签
+static BLOCK_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)<br\s*/?>|</?(?:p|div|h[1-6]|li|ul|ol|dl|dt|dd|tr|table|blockquote|pre|section|article|header|main|figure|figcaption|hr)\b[^>]*>",
+    )
+    .expect("Failed to compile block tag regex")
+});
+
+/// 匹配任意标签
+static TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<[^>]*>").expect("Failed to compile tag regex"));
+
+/// 匹配连续空白
+static WHITESPACE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s+").expect("Failed to compile whitespace regex"));
+
+/// 网页的可读快照
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadablePage {
+    pub title: Option<String>,
+    /// 清理后的完整 HTML 文档, 通过 `<base>` 保持相对链接可用
+    pub html: String,
+    /// 正文纯文本, 段落之间以换行分隔
+    pub text: String,
+}
+
+/// 生成页面的可读快照
+///
+/// `base_url` 为页面最终地址 (跟随重定向后)
+pub fn make_readable(html: &str, base_url: &Url) -> ReadablePage {
+    let title = TITLE_REGEX
+        .captures(html)
+        .map(|caps| {
+            WHITESPACE_REGEX
+                .replace_all(&decode_entities(&caps[1]), " ")
+                .trim()
+                .to_string()
+        })
+        .filter(|title| !title.is_empty());
+
+    let cleaned = strip_elements(html);
+    let content = CONTENT_REGEXES
+        .iter()
+        .find_map(|regex| regex.captures(&cleaned).map(|caps| caps[1].to_string()))
+        .unwrap_or_else(|| DOCUMENT_REGEX.replace_all(&cleaned, "").into_owned());
+    let content = START_TAG_REGEX
+        .replace_all(&content, sanitize_start_tag)
+        .trim()
+        .to_string();
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<base href=\"{}\">\n<title>{}</title>\n</head>\n<body>\n<article>\n{}\n</article>\n</body>\n</html>\n",
+        escape_html(base_url.as_str()),
+        escape_html(title.as_deref().unwrap_or_default()),
+        content
+    );
+    let text = html_to_text(&content);
+
+    ReadablePage { title, html, text }
+}
+
+/// 将 HTML 转换为纯文本: 去掉脚本和样式, 合并源码中的空白, 块级元素转为换行
+pub fn html_to_text(html: &str) -> String {
+    let cleaned = strip_elements(html);
+    let collapsed = WHITESPACE_REGEX.replace_all(&cleaned, " ");
+    let with_breaks = BLOCK_TAG_REGEX.replace_all(&collapsed, "\n");
+    let without_tags = TAG_REGEX.replace_all(&with_breaks, "");
+    let decoded = decode_entities(&without_tags);
+
+    decoded
+        .lines()
+        .map(|line| WHITESPACE_REGEX.replace_all(line, " ").trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 移除注释和与阅读无关的元素
+fn strip_elements(html: &str) -> String {
+    let mut cleaned = COMMENT_REGEX.replace_all(html, "").into_owned();
+    for regex in STRIPPED_ELEMENT_REGEXES.iter() {
+        cleaned = regex.replace_all(&cleaned, "").into_owned();
+    }
+    DANGLING_TAG_REGEX.replace_all(&cleaned, "").into_owned()
+}
+
+/// 去掉事件处理属性和脚本链接, 其余属性原样保留
+fn sanitize_start_tag(caps: &Captures) -> String {
+    let mut tag = format!("<{}", &caps[1]);
+    for attr in ATTR_REGEX.captures_iter(&caps[2]) {
+        let name = attr[1].to_ascii_lowercase();
+        let value = attr
+            .get(2)
+            .or_else(|| attr.get(3))
+            .or_else(|| attr.get(4))
+            .map(|m| m.as_str());
+
+        if name.starts_with("on") || name == "srcdoc" {
+            continue;
+        }
+        if let Some(value) = value {
+            // 实体编码和空白可以绕过前缀检查, 先解码再比较
+            let scheme = decode_entities(value)
+                .chars()
+                .filter(|c| !c.is_whitespace() && !c.is_control())
+                .collect::<String>()
+                .to_ascii_lowercase();
+            if scheme.starts_with("javascript:")
+                || scheme.starts_with("vbscript:")
+                || scheme.starts_with("data:text/html")
+            {
+                continue;
+            }
+        }
+
+        tag.push_str(&attr[0]);
+    }
+    tag.push_str(&caps[3]);
+    tag.push('>');
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/blog/post").unwrap()
+    }
+
+    #[test]
+    fn test_make_readable_prefers_article() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Post &amp; Notes</title>
+<style>body { color: red; }</style>
+<script>alert(1)</script></head>
+<body>
+<nav><a href="/">Home</a></nav>
+<article>
+<h1>Heading</h1>
+<!-- tracking -->
+<p>First <b>paragraph</b>.</p>
+<script src="/ads.js"></script>
+<p>Second<br>line</p>
+</article>
+<footer>Copyright</footer>
+</body></html>"#;
+
+        let page = make_readable(html, &base());
+        assert_eq!(page.title.as_deref(), Some("Post & Notes"));
+        assert_eq!(page.text, "Heading\nFirst paragraph.\nSecond\nline");
+        assert!(page
+            .html
+            .contains(r#"<base href="https://example.com/blog/post">"#));
+        assert!(page.html.contains("<title>Post &amp; Notes</title>"));
+        assert!(page.html.contains("<p>First <b>paragraph</b>.</p>"));
+        for removed in ["<script", "<style", "<nav", "Home", "Copyright", "tracking"] {
+            assert!(
+                !page.html.contains(removed),
+                "{} should be removed",
+                removed
+            );
+        }
+    }
+
+    #[test]
+    fn test_make_readable_falls_back_to_body() {
+        let html = "<html><body><div>Only body</div></body></html>";
+        let page = make_readable(html, &base());
+        assert_eq!(page.title, None);
+        assert_eq!(page.text, "Only body");
+
+        let fragment = "<p>No structure</p>";
+        assert_eq!(make_readable(fragment, &base()).text, "No structure");
+    }
+
+    #[test]
+    fn test_make_readable_removes_script_attributes() {
+        let html = r#"<body>
+<a href="javascript:alert(1)" title="x">bad</a>
+<a href=" jav&#x61;script:alert(1)">encoded</a>
+<a href="/next" onclick="steal()">next</a>
+<img src=/logo.png onerror=alert(1)>
+<iframe src="https://evil.example/"></iframe>
+<script>unterminated
+</body>"#;
+
+        let page = make_readable(html, &base());
+        assert!(page.html.contains(r#"<a title="x">bad</a>"#));
+        assert!(page.html.contains("<a>encoded</a>"));
+        assert!(page.html.contains(r#"<a href="/next">next</a>"#));
+        assert!(page.html.contains("<img src=/logo.png>"));
+        for removed in ["javascript", "onclick", "onerror", "<iframe", "<script"] {
+            assert!(
+                !page.html.contains(removed),
+                "{} should be removed",
+                removed
+            );
+        }
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<ul><li>One &lt;1&gt;</li><li>Two\n   words</li></ul>\n\n<p>  </p>";
+        assert_eq!(html_to_text(html), "One <1>\nTwo words");
+        assert_eq!(html_to_text("plain text"), "plain text");
+    }
+}
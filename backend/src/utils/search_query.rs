@@ -0,0 +1,744 @@
+//! 搜索查询语言
+//!
+//! 语法:
+//! - 词语: `rust`, 以 `*` 结尾表示前缀匹配, 例如 `prog*`
+//! - 短语: `"async runtime"`, 同样支持前缀匹配 `"async run"*`
+//! - 布尔运算: `AND` (相邻词语默认为 AND)、`OR`、`NOT` 或 `-词语`, 可用括号分组
+//! - 过滤条件: `tag:rust` `type:snippet` `collection:Design` `is:favorite` `is:unread`
+//!   `before:2025-01-01` `after:2025-01-01` `domain:github.com`, 值包含空格时使用引号,
+//!   例如 `collection:"Design Team"`
+//!
+//! 全文部分编译为 FTS5 表达式, 每个词语都以带引号的字符串输出,
+//! 因此 `c++`、未配对的引号等输入不会被 FTS5 当作语法解析。
+//! 过滤条件只能出现在顶层, 与全文条件为 AND 关系。
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::models::ResourceType;
+use crate::utils::segmenter::prepare_for_search;
+
+const FILTER_KEYS: [&str; 7] = [
+    "tag",
+    "type",
+    "collection",
+    "is",
+    "before",
+    "after",
+    "domain",
+];
+/// 查询的最大长度 (字符)
+const MAX_QUERY_LENGTH: usize = 1000;
+/// 括号和 NOT 的最大嵌套层数, 解析和编译都是递归实现, 需要限制深度防止栈溢出
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// 查询语法错误, position 为出错位置 (从 1 开始的字符序号)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParseError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl QueryParseError {
+    fn new(message: impl Into<String>, index: usize) -> Self {
+        Self {
+            message: message.into(),
+            position: index + 1,
+        }
+    }
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.position)
+    }
+}
+
+/// 解析后的搜索查询
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// FTS5 MATCH 表达式, 查询只包含过滤条件时为 None
+    pub fts_query: Option<String>,
+    pub tags: Vec<String>,
+    pub resource_type: Option<String>,
+    /// 收藏夹名称, 由调用方解析为 ID
+    pub collection: Option<String>,
+    pub is_favorite: Option<bool>,
+    pub is_read: Option<bool>,
+    pub is_archived: Option<bool>,
+    pub is_private: Option<bool>,
+    /// 创建时间下限 (包含)
+    pub date_from: Option<i64>,
+    /// 创建时间上限 (包含)
+    pub date_to: Option<i64>,
+    pub domain: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Term { text: String, prefix: bool },
+    Filter { key: String, value: String },
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    index: usize,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Term {
+        text: String,
+        prefix: bool,
+    },
+    Filter {
+        key: String,
+        value: String,
+        index: usize,
+    },
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>, usize),
+}
+
+/// 解析搜索查询
+pub fn parse_search_query(input: &str) -> Result<SearchQuery, QueryParseError> {
+    if input.chars().count() > MAX_QUERY_LENGTH {
+        return Err(QueryParseError::new(
+            format!(
+                "Search query is too long (max {} characters)",
+                MAX_QUERY_LENGTH
+            ),
+            MAX_QUERY_LENGTH,
+        ));
+    }
+
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(QueryParseError::new("Search query is empty", 0));
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(QueryParseError::new("Unexpected ')'", token.index));
+    }
+
+    // 顶层的过滤条件单独处理, 其余部分编译为 FTS5 表达式
+    let items = match expr {
+        Expr::And(items) => items,
+        expr => vec![expr],
+    };
+    let mut query = SearchQuery::default();
+    let mut text_items = Vec::new();
+    for item in items {
+        match item {
+            Expr::Filter { key, value, index } => query.apply_filter(&key, &value, index)?,
+            item => {
+                reject_nested_filters(&item)?;
+                text_items.push(item);
+            }
+        }
+    }
+
+    query.fts_query = compile_and(&text_items)?;
+    if query.fts_query.is_none() && query == SearchQuery::default() {
+        return Err(QueryParseError::new(
+            "Search query has no searchable terms",
+            0,
+        ));
+    }
+
+    Ok(query)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let index = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let kind = match c {
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            '"' => {
+                let text = read_quoted(&chars, &mut i)?;
+                TokenKind::Term {
+                    text,
+                    prefix: take_star(&chars, &mut i),
+                }
+            }
+            // 词首的 - 表示排除, 词中的 - 属于词语本身
+            '-' if chars
+                .get(i + 1)
+                .is_some_and(|next| !next.is_whitespace() && *next != ')') =>
+            {
+                i += 1;
+                TokenKind::Not
+            }
+            _ => {
+                let mut word = String::new();
+                while i < chars.len() && !chars[i].is_whitespace() && !"()\"".contains(chars[i]) {
+                    word.push(chars[i]);
+                    i += 1;
+                }
+
+                // 过滤条件的值可以使用引号, 例如 collection:"Design Team"
+                if let Some(key) = word.strip_suffix(':') {
+                    let key = key.to_lowercase();
+                    if chars.get(i) == Some(&'"') && FILTER_KEYS.contains(&key.as_str()) {
+                        let value = read_quoted(&chars, &mut i)?;
+                        tokens.push(Token {
+                            kind: TokenKind::Filter { key, value },
+                            index,
+                        });
+                        continue;
+                    }
+                }
+
+                match word.as_str() {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => match word.split_once(':') {
+                        Some((key, value))
+                            if FILTER_KEYS.contains(&key.to_lowercase().as_str()) =>
+                        {
+                            TokenKind::Filter {
+                                key: key.to_lowercase(),
+                                value: value.to_string(),
+                            }
+                        }
+                        _ => match word.strip_suffix('*') {
+                            Some(text) => TokenKind::Term {
+                                text: text.to_string(),
+                                prefix: true,
+                            },
+                            None => TokenKind::Term {
+                                text: word,
+                                prefix: false,
+                            },
+                        },
+                    },
+                }
+            }
+        };
+
+        tokens.push(Token { kind, index });
+    }
+
+    Ok(tokens)
+}
+
+/// 读取引号中的内容, i 指向开始的引号, 返回后指向结束引号之后
+fn read_quoted(chars: &[char], i: &mut usize) -> Result<String, QueryParseError> {
+    let start = *i;
+    let Some(length) = chars[start + 1..].iter().position(|c| *c == '"') else {
+        return Err(QueryParseError::new("Unterminated quoted phrase", start));
+    };
+
+    *i = start + length + 2;
+    Ok(chars[start + 1..start + 1 + length].iter().collect())
+}
+
+fn take_star(chars: &[char], i: &mut usize) -> bool {
+    if chars.get(*i) == Some(&'*') {
+        *i += 1;
+        true
+    } else {
+        false
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// 当前的括号和 NOT 嵌套层数
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    /// 当前位置, 已到末尾时为最后一个词之后
+    fn index(&self) -> usize {
+        match self.tokens.get(self.pos) {
+            Some(token) => token.index,
+            None => self.tokens.last().map_or(0, |token| token.index + 1),
+        }
+    }
+
+    /// 进入一层括号或 NOT, 超过最大层数时报错
+    fn enter(&mut self, index: usize) -> Result<(), QueryParseError> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(QueryParseError::new(
+                format!(
+                    "Search query is nested too deeply (max {} levels)",
+                    MAX_NESTING_DEPTH
+                ),
+                index,
+            ));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryParseError> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&TokenKind::Or) {
+            self.pos += 1;
+            self.expect_operand("OR")?;
+            items.push(self.parse_and()?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryParseError> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                None | Some(TokenKind::RParen) | Some(TokenKind::Or) => break,
+                Some(TokenKind::And) => {
+                    self.pos += 1;
+                    self.expect_operand("AND")?;
+                }
+                _ => {}
+            }
+            items.push(self.parse_unary()?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryParseError> {
+        if self.peek() == Some(&TokenKind::Not) {
+            let index = self.index();
+            self.pos += 1;
+            self.expect_operand("NOT")?;
+            self.enter(index)?;
+            let expr = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(Expr::Not(Box::new(expr), index));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, QueryParseError> {
+        let index = self.index();
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(QueryParseError::new("Expected a search term", index));
+        };
+        self.pos += 1;
+
+        match token.kind {
+            TokenKind::Term { text, prefix } => Ok(Expr::Term { text, prefix }),
+            TokenKind::Filter { key, value } => Ok(Expr::Filter { key, value, index }),
+            TokenKind::LParen => {
+                if self.peek() == Some(&TokenKind::RParen) {
+                    return Err(QueryParseError::new("Empty parentheses", index));
+                }
+                self.enter(index)?;
+                let expr = self.parse_or()?;
+                self.depth -= 1;
+                if self.peek() != Some(&TokenKind::RParen) {
+                    return Err(QueryParseError::new("Missing closing parenthesis", index));
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            TokenKind::RParen => Err(QueryParseError::new("Unexpected ')'", index)),
+            TokenKind::And | TokenKind::Or => Err(QueryParseError::new(
+                format!(
+                    "Expected a search term before {}",
+                    if token.kind == TokenKind::And {
+                        "AND"
+                    } else {
+                        "OR"
+                    }
+                ),
+                index,
+            )),
+            TokenKind::Not => unreachable!("NOT is handled by parse_unary"),
+        }
+    }
+
+    fn expect_operand(&self, operator: &str) -> Result<(), QueryParseError> {
+        match self.peek() {
+            None | Some(TokenKind::RParen) | Some(TokenKind::And) | Some(TokenKind::Or) => {
+                Err(QueryParseError::new(
+                    format!("Expected a search term after {}", operator),
+                    self.index(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn reject_nested_filters(expr: &Expr) -> Result<(), QueryParseError> {
+    match expr {
+        Expr::Filter { key, value, index } => Err(QueryParseError::new(
+            format!(
+                "Filter '{}:{}' cannot be used inside OR, NOT or parentheses",
+                key, value
+            ),
+            *index,
+        )),
+        Expr::And(items) | Expr::Or(items) => items.iter().try_for_each(reject_nested_filters),
+        Expr::Not(expr, _) => reject_nested_filters(expr),
+        Expr::Term { .. } => Ok(()),
+    }
+}
+
+/// AND 组合: 排除项以 FTS5 的二元 NOT 连接在包含项之后
+fn compile_and(items: &[Expr]) -> Result<Option<String>, QueryParseError> {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    let mut first_not = None;
+
+    for item in items {
+        match item {
+            Expr::Not(expr, index) => {
+                first_not.get_or_insert(*index);
+                if let Some(compiled) = compile(expr)? {
+                    exclude.push(compiled);
+                }
+            }
+            item => include.extend(compile(item)?),
+        }
+    }
+
+    if include.is_empty() {
+        return match first_not {
+            Some(index) if !exclude.is_empty() => Err(QueryParseError::new(
+                "NOT must be combined with a term to search for",
+                index,
+            )),
+            _ => Ok(None),
+        };
+    }
+
+    let mut compiled = if include.len() == 1 {
+        include.remove(0)
+    } else {
+        format!("({})", include.join(" AND "))
+    };
+    for excluded in exclude {
+        compiled = format!("{} NOT {}", compiled, excluded);
+    }
+
+    Ok(Some(compiled))
+}
+
+fn compile(expr: &Expr) -> Result<Option<String>, QueryParseError> {
+    match expr {
+        Expr::Term { text, prefix } => Ok(compile_term(text, *prefix)),
+        Expr::And(items) => Ok(compile_and(items)?.map(|compiled| format!("({})", compiled))),
+        Expr::Or(items) => {
+            let mut compiled = Vec::new();
+            for item in items {
+                if let Expr::Not(_, index) = item {
+                    return Err(QueryParseError::new(
+                        "NOT must be combined with a term to search for",
+                        *index,
+                    ));
+                }
+                compiled.extend(compile(item)?);
+            }
+            Ok(match compiled.len() {
+                0 => None,
+                1 => compiled.pop(),
+                _ => Some(format!("({})", compiled.join(" OR "))),
+            })
+        }
+        Expr::Not(_, index) => Err(QueryParseError::new(
+            "NOT must be combined with a term to search for",
+            *index,
+        )),
+        Expr::Filter { .. } => Ok(None),
+    }
+}
+
+/// 词语按分词策略处理后输出为 FTS5 字符串; 不含字母或数字的词语 (例如 `++`) 被忽略
+fn compile_term(text: &str, prefix: bool) -> Option<String> {
+    let keywords = prepare_for_search(Some(text));
+    if !keywords.chars().any(char::is_alphanumeric) {
+        return None;
+    }
+
+    Some(format!(
+        "\"{}\"{}",
+        keywords.replace('"', "\"\""),
+        if prefix { "*" } else { "" }
+    ))
+}
+
+impl SearchQuery {
+    fn apply_filter(
+        &mut self,
+        key: &str,
+        value: &str,
+        index: usize,
+    ) -> Result<(), QueryParseError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(QueryParseError::new(
+                format!("Filter '{}:' requires a value", key),
+                index,
+            ));
+        }
+        let error = |message: String| QueryParseError::new(message, index);
+
+        match key {
+            "tag" => self.tags.push(value.to_string()),
+            "type" => {
+                let resource_type = ResourceType::from(value)
+                    .map_err(|_| {
+                        error(format!(
+                            "Unknown resource type '{}' (expected link, note, snippet or file)",
+                            value
+                        ))
+                    })?
+                    .as_str()
+                    .to_string();
+                set_once(&mut self.resource_type, resource_type, "type:").map_err(error)?;
+            }
+            "collection" => {
+                set_once(&mut self.collection, value.to_string(), "collection:").map_err(error)?
+            }
+            "is" => {
+                let (flag, flag_value) = match value.to_lowercase().as_str() {
+                    "favorite" => (&mut self.is_favorite, true),
+                    "read" => (&mut self.is_read, true),
+                    "unread" => (&mut self.is_read, false),
+                    "archived" => (&mut self.is_archived, true),
+                    "private" => (&mut self.is_private, true),
+                    "public" => (&mut self.is_private, false),
+                    _ => {
+                        return Err(error(format!(
+                            "Unknown value '{}' for is: (expected favorite, read, unread, archived, private or public)",
+                            value
+                        )))
+                    }
+                };
+                set_once(flag, flag_value, "is:").map_err(error)?;
+            }
+            "before" | "after" => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                    error(format!(
+                        "Invalid date '{}' for {}: (expected YYYY-MM-DD)",
+                        value, key
+                    ))
+                })?;
+                let start_of_day = |date: NaiveDate| {
+                    date.and_hms_opt(0, 0, 0)
+                        .map(|datetime| datetime.and_utc().timestamp())
+                };
+
+                // before 不包含当天, after 从次日开始; 重复时取范围更小的一个
+                if key == "before" {
+                    let date_to = start_of_day(date).map(|timestamp| timestamp - 1);
+                    self.date_to = self.date_to.into_iter().chain(date_to).min();
+                } else {
+                    let date_from = date.succ_opt().and_then(start_of_day);
+                    self.date_from = self.date_from.into_iter().chain(date_from).max();
+                }
+            }
+            "domain" => {
+                let domain = value.to_lowercase();
+                let is_valid = domain
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.');
+                if !is_valid {
+                    return Err(error(format!("Invalid domain '{}'", value)));
+                }
+                set_once(&mut self.domain, domain, "domain:").map_err(error)?;
+            }
+            _ => unreachable!("unknown filter key {}", key),
+        }
+
+        Ok(())
+    }
+}
+
+/// 同一过滤条件只能设置一个值, 重复相同的值可以接受
+fn set_once<T: PartialEq>(slot: &mut Option<T>, value: T, name: &str) -> Result<(), String> {
+    match slot {
+        Some(existing) if *existing != value => Err(format!("Conflicting {} filters", name)),
+        _ => {
+            *slot = Some(value);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fts(input: &str) -> Option<String> {
+        parse_search_query(input).unwrap().fts_query
+    }
+
+    fn error(input: &str) -> QueryParseError {
+        parse_search_query(input).unwrap_err()
+    }
+
+    #[cfg(not(feature = "jieba"))]
+    #[test]
+    fn test_compile_text_queries() {
+        assert_eq!(fts("rust"), Some(r#""rust""#.to_string()));
+        assert_eq!(
+            fts("rust async"),
+            Some(r#"("rust" AND "async")"#.to_string())
+        );
+        assert_eq!(
+            fts(r#""async runtime" OR tokio"#),
+            Some(r#"("async runtime" OR "tokio")"#.to_string())
+        );
+        assert_eq!(fts("prog*"), Some(r#""prog"*"#.to_string()));
+        assert_eq!(
+            fts("rust -unsafe NOT python"),
+            Some(r#""rust" NOT "unsafe" NOT "python""#.to_string())
+        );
+        assert_eq!(
+            fts("(rust OR go) AND web"),
+            Some(r#"(("rust" OR "go") AND "web")"#.to_string())
+        );
+        // FTS5 语法字符作为普通文本处理
+        assert_eq!(fts("c++"), Some(r#""c++""#.to_string()));
+        assert_eq!(fts("tokio-rs"), Some(r#""tokio-rs""#.to_string()));
+        assert_eq!(fts("rust ++"), Some(r#""rust""#.to_string()));
+        assert_eq!(
+            fts("https://example.com"),
+            Some(r#""https://example.com""#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_filters() {
+        let query = parse_search_query(
+            r#"tag:rust tag:web type:Snippet collection:"Design Team" is:favorite is:unread domain:GitHub.com async"#,
+        )
+        .unwrap();
+        assert_eq!(query.tags, vec!["rust", "web"]);
+        assert_eq!(query.resource_type.as_deref(), Some("snippet"));
+        assert_eq!(query.collection.as_deref(), Some("Design Team"));
+        assert_eq!(query.is_favorite, Some(true));
+        assert_eq!(query.is_read, Some(false));
+        assert_eq!(query.domain.as_deref(), Some("github.com"));
+        assert!(query.fts_query.is_some());
+
+        // 只有过滤条件时没有全文表达式
+        let query = parse_search_query("before:2025-01-01 after:2024-12-30").unwrap();
+        assert_eq!(query.fts_query, None);
+        assert_eq!(query.date_to, Some(1735689600 - 1));
+        assert_eq!(query.date_from, Some(1735603200));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert_eq!(
+            error(r#"rust "async"#),
+            QueryParseError {
+                message: "Unterminated quoted phrase".to_string(),
+                position: 6
+            }
+        );
+        assert_eq!(error("(rust OR go").message, "Missing closing parenthesis");
+        assert_eq!(error("rust)").message, "Unexpected ')'");
+        assert_eq!(error("rust OR").message, "Expected a search term after OR");
+        assert_eq!(
+            error("AND rust").message,
+            "Expected a search term before AND"
+        );
+        assert_eq!(
+            error("NOT rust").message,
+            "NOT must be combined with a term to search for"
+        );
+        assert_eq!(
+            error("rust OR -go").message,
+            "NOT must be combined with a term to search for"
+        );
+        assert_eq!(
+            error("rust OR tag:web").message,
+            "Filter 'tag:web' cannot be used inside OR, NOT or parentheses"
+        );
+        assert_eq!(error("tag:").message, "Filter 'tag:' requires a value");
+        assert_eq!(error("++").message, "Search query has no searchable terms");
+        assert!(error("type:video")
+            .message
+            .contains("Unknown resource type"));
+        assert!(error("is:starred")
+            .message
+            .contains("Unknown value 'starred'"));
+        assert!(error("before:yesterday").message.contains("Invalid date"));
+        assert!(error("domain:%").message.contains("Invalid domain"));
+        assert_eq!(
+            error("is:read is:unread").message,
+            "Conflicting is: filters"
+        );
+        assert_eq!(
+            error("rust (").to_string(),
+            "Expected a search term at character 7"
+        );
+    }
+
+    #[test]
+    fn test_query_limits() {
+        let too_long = "rust ".repeat(MAX_QUERY_LENGTH / 5 + 1);
+        assert!(error(&too_long).message.contains("too long"));
+
+        // 深层嵌套在递归解析前被拒绝, 不会栈溢出
+        let nested = format!("rust {}go{}", "(".repeat(900), ")".repeat(90));
+        assert!(error(&nested).message.contains("nested too deeply"));
+        let not_chain = format!("rust {}go", "-".repeat(900));
+        assert!(error(&not_chain).message.contains("nested too deeply"));
+        let not_chain = format!("rust {}go", "NOT ".repeat(200));
+        assert!(error(&not_chain).message.contains("nested too deeply"));
+
+        let allowed = format!(
+            "rust {}go{}",
+            "(".repeat(MAX_NESTING_DEPTH),
+            ")".repeat(MAX_NESTING_DEPTH)
+        );
+        assert!(parse_search_query(&allowed).is_ok());
+    }
+}
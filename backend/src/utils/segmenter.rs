@@ -93,6 +93,36 @@ pub fn prepare_tags_for_search(tags: &[String]) -> String {
     }
 }
 
+/// 将索引中的分词文本还原为便于展示的原文，用于 FTS5 返回的高亮片段
+///
+/// # 分词策略
+/// - 默认模式：索引中保存的就是原文，原样返回
+/// - jieba 模式：分词结果以单个空格连接，原文中的每个空格本身也是一个词，
+///   因此连续 n 个空格对应原文中的 (n - 1) / 2 个空格，单个空格是分词时插入的分隔符
+pub fn restore_segmented_text(text: &str) -> String {
+    #[cfg(feature = "jieba")]
+    {
+        let mut restored = String::with_capacity(text.len());
+        let mut spaces: usize = 0;
+        for c in text.chars().chain(std::iter::once('\0')) {
+            if c == ' ' {
+                spaces += 1;
+                continue;
+            }
+            restored.extend(std::iter::repeat_n(' ', spaces.saturating_sub(1) / 2));
+            spaces = 0;
+            if c != '\0' {
+                restored.push(c);
+            }
+        }
+        restored
+    }
+    #[cfg(not(feature = "jieba"))]
+    {
+        text.to_string()
+    }
+}
+
 // 注意：simple_text_processing 函数已被移除
 // 现在默认使用 SQLite FTS5 的内置分词器，不需要在 Rust 层面进行预处理
 // 如果需要自定义预处理逻辑，可以在启用 jieba feature 时实现
@@ -201,4 +231,19 @@ mod tests {
         let words: Vec<&str> = result.split_whitespace().collect();
         assert!(words.len() >= 2); // 至少应该分成两个词
     }
+
+    #[test]
+    fn test_restore_segmented_text() {
+        for text in ["Linux 内核开发\n第二行", "hello  world", "Rust语言"] {
+            let segmented = prepare_for_search(Some(text));
+            assert_eq!(restore_segmented_text(&segmented), text);
+        }
+
+        // 高亮标记插入在词语两侧, 不影响分隔符的还原
+        #[cfg(feature = "jieba")]
+        assert_eq!(
+            restore_segmented_text("Linux <b>内核</b> 开发"),
+            "Linux<b>内核</b>开发"
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! 拼写纠正辅助函数
+//!
+//! 只处理 ASCII 字母数字组成的词语; 中文等文本的分词结果不适合按编辑距离纠正。
+
+use std::collections::HashMap;
+
+/// 参与纠正的最短词长, 更短的词编辑一次就可能变成任意其他词
+pub const MIN_CORRECTION_LENGTH: usize = 3;
+
+/// 编辑距离 (插入、删除、替换以及相邻字符交换各计 1 次)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // 保留前两行用于计算相邻交换
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// 按词长允许的最大编辑距离
+pub fn max_edit_distance(word: &str) -> usize {
+    if word.chars().count() <= 4 {
+        1
+    } else {
+        2
+    }
+}
+
+/// 查询中可以纠正的词语 (已转为小写)
+///
+/// 跳过布尔运算符、`key:value` 过滤条件、引号内的短语和前缀查询。
+pub fn correctable_words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for (word, _) in split_words(query) {
+        let word = word.to_lowercase();
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// 用纠正后的词替换查询中的原词, 其余部分保持不变
+pub fn apply_corrections(query: &str, corrections: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(query.len());
+    let mut last = 0;
+    for (word, start) in split_words(query) {
+        if let Some(correction) = corrections.get(&word.to_lowercase()) {
+            result.push_str(&query[last..start]);
+            result.push_str(correction);
+            last = start + word.len();
+        }
+    }
+    result.push_str(&query[last..]);
+    result
+}
+
+/// 以空白分隔查询, 返回可纠正的词及其字节位置
+fn split_words(query: &str) -> Vec<(&str, usize)> {
+    let mut words = Vec::new();
+    let mut in_quotes = false;
+    let mut offset = 0;
+
+    for token in query.split_inclusive(char::is_whitespace) {
+        let start = offset;
+        offset += token.len();
+        let token = token.trim_end();
+
+        // 引号内的短语按原样匹配
+        let quotes = token.matches('"').count();
+        let quoted = in_quotes || quotes > 0;
+        if quotes % 2 == 1 {
+            in_quotes = !in_quotes;
+        }
+        if quoted {
+            continue;
+        }
+
+        // 去掉排除前缀和括号, 前缀查询不纠正
+        let leading = token.len() - token.trim_start_matches(['-', '(']).len();
+        let word = token[leading..].trim_end_matches(')');
+        if word.len() < MIN_CORRECTION_LENGTH
+            || matches!(word, "AND" | "OR" | "NOT")
+            || !word.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            continue;
+        }
+        words.push((word, start + leading));
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("rust", "rust"), 0);
+        assert_eq!(edit_distance("rust", "rast"), 1);
+        assert_eq!(edit_distance("rust", "rusty"), 1);
+        assert_eq!(edit_distance("rust", "urst"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_correctable_words_skip_syntax() {
+        assert_eq!(
+            correctable_words(r#"Rust OR asnyc -unsafe tag:web "exact phrase" prog* (tokio)"#),
+            vec!["rust", "asnyc", "unsafe", "tokio"]
+        );
+        assert!(correctable_words("编程 go").is_empty());
+    }
+
+    #[test]
+    fn test_apply_corrections_keeps_query_structure() {
+        let corrections = HashMap::from([
+            ("asnyc".to_string(), "async".to_string()),
+            ("tokoi".to_string(), "tokio".to_string()),
+        ]);
+        assert_eq!(
+            apply_corrections("rust Asnyc OR (tokoi) tag:asnyc", &corrections),
+            "rust async OR (tokio) tag:asnyc"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+/// 上传文件的正文提取
+///
+/// 支持纯文本、Markdown、HTML、PDF 以及 Office Open XML / OpenDocument 文档 (docx、pptx、odt、odp)。
+/// 格式按内容类型判断, 内容类型不明确时按扩展名判断。
+/// 提取结果合并多余空白并限制长度, 之后由 IndexerService 按分词策略写入全文索引。
+use std::io::{Cursor, Read};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::utils::readability::html_to_text;
+
+/// 提取文本的最大字节数, 超出部分截断
+pub const MAX_EXTRACTED_TEXT_BYTES: usize = 1024 * 1024;
+/// 文档压缩包中单个 XML 文件的最大解压字节数, 防止压缩炸弹
+const MAX_XML_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 可提取正文的文档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Text,
+    Html,
+    Pdf,
+    Docx,
+    Pptx,
+    OpenDocument,
+}
+
+impl DocumentKind {
+    /// 根据内容类型和文件名判断格式, 不支持时返回 None
+    pub fn detect(content_type: &str, file_name: &str) -> Option<Self> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        let by_mime = match mime.as_str() {
+            "text/html" | "application/xhtml+xml" => Some(Self::Html),
+            "application/pdf" => Some(Self::Pdf),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
+                Some(Self::Docx)
+            }
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+                Some(Self::Pptx)
+            }
+            "application/vnd.oasis.opendocument.text"
+            | "application/vnd.oasis.opendocument.presentation" => Some(Self::OpenDocument),
+            "application/json" | "application/xml" => Some(Self::Text),
+            mime if mime.starts_with("text/") => Some(Self::Text),
+            _ => None,
+        };
+        if by_mime.is_some() {
+            return by_mime;
+        }
+
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())?;
+        match extension.as_str() {
+            "txt" | "text" | "md" | "markdown" | "csv" | "json" | "xml" => Some(Self::Text),
+            "html" | "htm" | "xhtml" => Some(Self::Html),
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
+            "pptx" => Some(Self::Pptx),
+            "odt" | "odp" => Some(Self::OpenDocument),
+            _ => None,
+        }
+    }
+}
+
+/// 提取文档正文
+///
+/// PDF 解析较慢, 调用方应在阻塞线程中执行
+pub fn extract_text(kind: DocumentKind, data: &[u8]) -> Result<String, String> {
+    let text = match kind {
+        DocumentKind::Text => decode_text(data),
+        DocumentKind::Html => html_to_text(&decode_text(data)),
+        DocumentKind::Pdf => pdf_extract::extract_text_from_mem(data)
+            .map_err(|e| format!("Failed to parse PDF: {}", e))?,
+        DocumentKind::Docx => zip_xml_text(data, |name| name == "word/document.xml")?,
+        DocumentKind::Pptx => zip_xml_text(data, |name| {
+            name.starts_with("ppt/slides/slide") && name.ends_with(".xml")
+        })?,
+        DocumentKind::OpenDocument => zip_xml_text(data, |name| name == "content.xml")?,
+    };
+
+    Ok(normalize(&text))
+}
+
+/// 按 UTF-8 解码, 去掉 BOM, 无效字节替换为 U+FFFD
+fn decode_text(data: &[u8]) -> String {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    String::from_utf8_lossy(data).into_owned()
+}
+
+/// 读取压缩包中匹配的 XML 文件并提取文本, 多个文件按文件名中的序号排序
+fn zip_xml_text(data: &[u8], matches: impl Fn(&str) -> bool) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|e| format!("Failed to open document: {}", e))?;
+
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| matches(name))
+        .map(str::to_string)
+        .collect();
+    if names.is_empty() {
+        return Err("Document has no text content".to_string());
+    }
+    // slide2.xml 应排在 slide10.xml 之前
+    names.sort_by_key(|name| {
+        let digits: String = name.chars().filter(char::is_ascii_digit).collect();
+        (digits.parse::<u64>().unwrap_or(0), name.clone())
+    });
+
+    let mut text = String::new();
+    for name in names {
+        let mut xml = String::new();
+        archive
+            .by_name(&name)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?
+            .take(MAX_XML_BYTES)
+            .read_to_string(&mut xml)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        xml_text(&xml, &mut text)?;
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+/// 收集 XML 中的文本节点; 段落 (w:p、a:p、text:p) 和标题 (text:h) 结束时换行
+fn xml_text(xml: &str, out: &mut String) -> Result<(), String> {
+    let mut reader = Reader::from_str(xml);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Text(text)) => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| format!("Invalid document XML: {}", e))?;
+                out.push_str(&text);
+            }
+            Ok(Event::CData(text)) => out.push_str(&String::from_utf8_lossy(&text)),
+            Ok(Event::End(element)) => {
+                if matches!(element.local_name().as_ref(), b"p" | b"h") {
+                    out.push('\n');
+                }
+            }
+            Ok(Event::Empty(element)) => match element.local_name().as_ref() {
+                b"tab" | b"s" => out.push(' '),
+                b"br" | b"line-break" => out.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Eof) => return Ok(()),
+            Ok(_) => {}
+            Err(e) => return Err(format!("Invalid document XML: {}", e)),
+        }
+    }
+}
+
+/// 合并行内空白、去掉空行, 并按字符边界截断到最大长度
+fn normalize(text: &str) -> String {
+    let mut normalized = String::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push('\n');
+        }
+        normalized.push_str(&line);
+        if normalized.len() >= MAX_EXTRACTED_TEXT_BYTES {
+            break;
+        }
+    }
+
+    if normalized.len() > MAX_EXTRACTED_TEXT_BYTES {
+        let mut end = MAX_EXTRACTED_TEXT_BYTES;
+        while !normalized.is_char_boundary(end) {
+            end -= 1;
+        }
+        normalized.truncate(end);
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_document(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_detect_document_kind() {
+        assert_eq!(
+            DocumentKind::detect("text/markdown; charset=utf-8", "notes"),
+            Some(DocumentKind::Text)
+        );
+        assert_eq!(
+            DocumentKind::detect("application/pdf", "scan.bin"),
+            Some(DocumentKind::Pdf)
+        );
+        assert_eq!(
+            DocumentKind::detect("application/octet-stream", "README.MD"),
+            Some(DocumentKind::Text)
+        );
+        assert_eq!(
+            DocumentKind::detect("application/octet-stream", "report.docx"),
+            Some(DocumentKind::Docx)
+        );
+        assert_eq!(DocumentKind::detect("image/png", "photo.png"), None);
+        assert_eq!(DocumentKind::detect("application/zip", "archive"), None);
+    }
+
+    #[test]
+    fn test_extract_plain_text_and_html() {
+        assert_eq!(
+            extract_text(
+                DocumentKind::Text,
+                b"\xEF\xBB\xBF# Title\n\n  body   text \n"
+            )
+            .unwrap(),
+            "# Title\nbody text"
+        );
+        assert_eq!(
+            extract_text(
+                DocumentKind::Html,
+                b"<html><script>ignored()</script><body><h1>Title</h1><p>Body</p></body></html>"
+            )
+            .unwrap(),
+            "Title\nBody"
+        );
+    }
+
+    #[test]
+    fn test_extract_office_documents() {
+        let docx = zip_document(&[
+            ("[Content_Types].xml", "<Types/>"),
+            (
+                "word/document.xml",
+                r#"<w:document xmlns:w="w"><w:body>
+                <w:p><w:r><w:t>First</w:t></w:r><w:r><w:tab/><w:t>paragraph &amp; more</w:t></w:r></w:p>
+                <w:p><w:r><w:t>Second</w:t></w:r></w:p>
+                </w:body></w:document>"#,
+            ),
+        ]);
+        assert_eq!(
+            extract_text(DocumentKind::Docx, &docx).unwrap(),
+            "First paragraph & more\nSecond"
+        );
+
+        let pptx = zip_document(&[
+            (
+                "ppt/slides/slide10.xml",
+                "<p:sld><a:p><a:t>Ten</a:t></a:p></p:sld>",
+            ),
+            (
+                "ppt/slides/slide2.xml",
+                "<p:sld><a:p><a:t>Two</a:t></a:p></p:sld>",
+            ),
+        ]);
+        assert_eq!(extract_text(DocumentKind::Pptx, &pptx).unwrap(), "Two\nTen");
+
+        let odt = zip_document(&[(
+            "content.xml",
+            "<office:text><text:h>Heading</text:h><text:p>Body<text:s/>text</text:p></office:text>",
+        )]);
+        assert_eq!(
+            extract_text(DocumentKind::OpenDocument, &odt).unwrap(),
+            "Heading\nBody text"
+        );
+
+        assert!(extract_text(DocumentKind::Docx, b"not a zip").is_err());
+    }
+
+    #[test]
+    fn test_extracted_text_is_truncated() {
+        let data = "长".repeat(MAX_EXTRACTED_TEXT_BYTES);
+        let text = extract_text(DocumentKind::Text, data.as_bytes()).unwrap();
+        assert!(text.len() <= MAX_EXTRACTED_TEXT_BYTES);
+        assert!(text.chars().all(|c| c == '长'));
+    }
+}
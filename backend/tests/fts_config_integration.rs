@@ -12,7 +12,7 @@ fn test_fts_config_integration() {
     println!("=== FTS5 分词器配置验证 ===\n");
 
     // 模拟资源数据
-    let resources = vec![
+    let resources = [
         (
             "Linux内核开发指南",
             "深入讲解Linux内核的开发技术和调试方法",
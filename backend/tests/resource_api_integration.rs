@@ -3,11 +3,16 @@
 
 use resources_api::models::{CreateResource, ResourceQuery};
 use resources_api::services::ResourceService;
-use sqlx::SqlitePool;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
 
 /// 创建测试数据库连接池
 async fn create_test_pool() -> SqlitePool {
-    let pool = SqlitePool::connect(":memory:").await.unwrap();
+    // 后台索引任务与测试并发写入共享缓存的内存数据库时会触发表锁, 使用单连接串行执行
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(":memory:")
+        .await
+        .unwrap();
 
     // 创建必要的数据库表
     sqlx::query(
@@ -137,6 +142,7 @@ async fn create_test_pool() -> SqlitePool {
             content,
             tags,
             url,
+            extracted_text,
             tokenize = 'unicode61 remove_diacritics 2'
         )
         "#,
@@ -164,6 +170,100 @@ async fn create_test_pool() -> SqlitePool {
     .await
     .unwrap();
 
+    sqlx::query(
+        r#"
+        CREATE TABLE background_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            user_id INTEGER NOT NULL,
+            resource_id INTEGER,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            run_after INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+            created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+            updated_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+            FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+            FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE link_checks (
+            resource_id INTEGER PRIMARY KEY,
+            status_code INTEGER,
+            final_url TEXT,
+            error TEXT,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0,
+            last_checked_at INTEGER NOT NULL,
+            last_success_at INTEGER,
+            FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE resource_archives (
+            resource_id INTEGER PRIMARY KEY,
+            url TEXT NOT NULL,
+            title TEXT,
+            html TEXT,
+            storage_path TEXT,
+            text TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            archived_at INTEGER NOT NULL,
+            FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE resource_files (
+            resource_id INTEGER PRIMARY KEY,
+            storage_backend TEXT NOT NULL,
+            storage_key TEXT NOT NULL UNIQUE,
+            file_name TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            checksum TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+            extracted_text TEXT,
+            extracted_at INTEGER,
+            FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        CREATE TABLE storage_deletions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            storage_backend TEXT NOT NULL,
+            storage_key TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
     pool
 }
 